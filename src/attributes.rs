@@ -401,6 +401,104 @@ impl ToWgslString for MatrixType {
     }
 }
 
+/// Fixed-size array type (`array<T, N>`).
+///
+/// Describes the type of an array of `N` values of a same element type. The
+/// element type is a scalar, vector, or matrix type; arrays of arrays are not
+/// supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub struct ArrayType {
+    /// Type of all elements of the array.
+    elem_type: ArrayElemType,
+    /// Number of elements. Never zero.
+    count: u32,
+}
+
+/// Element type of an [`ArrayType`].
+///
+/// This mirrors the non-array variants of [`ValueType`], which can't be stored
+/// directly inside the [`ArrayType`] without making [`ValueType`] recursive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+enum ArrayElemType {
+    Scalar(ScalarType),
+    Vector(VectorType),
+    Matrix(MatrixType),
+}
+
+impl Display for ArrayType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "array<{}, {}>", self.elem_type(), self.count)
+    }
+}
+
+impl ArrayType {
+    /// Create a new array type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elem_type` is itself an array type, or if `count` is zero.
+    pub fn new(elem_type: impl Into<ValueType>, count: u32) -> Self {
+        assert!(count > 0, "Array types must have at least one element.");
+        let elem_type = match elem_type.into() {
+            ValueType::Scalar(s) => ArrayElemType::Scalar(s),
+            ValueType::Vector(v) => ArrayElemType::Vector(v),
+            ValueType::Matrix(m) => ArrayElemType::Matrix(m),
+            ValueType::Array(_) => panic!("Arrays of arrays are not supported."),
+        };
+        Self { elem_type, count }
+    }
+
+    /// Type of the individual array elements.
+    pub fn elem_type(&self) -> ValueType {
+        match self.elem_type {
+            ArrayElemType::Scalar(s) => ValueType::Scalar(s),
+            ArrayElemType::Vector(v) => ValueType::Vector(v),
+            ArrayElemType::Matrix(m) => ValueType::Matrix(m),
+        }
+    }
+
+    /// Number of elements.
+    pub const fn count(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Size of a value of this type, in bytes.
+    ///
+    /// This corresponds to the size of a variable of that type when part of a
+    /// struct in WGSL.
+    pub fn size(&self) -> usize {
+        // N * roundUp(AlignOf(E), SizeOf(E))
+        // https://gpuweb.github.io/gpuweb/wgsl/#alignment-and-size
+        self.count() * self.stride()
+    }
+
+    /// Alignment of a value of this type, in bytes.
+    ///
+    /// This corresponds to the alignment of a variable of that type when part
+    /// of a struct in WGSL.
+    pub fn align(&self) -> usize {
+        // AlignOf(E)
+        // https://gpuweb.github.io/gpuweb/wgsl/#alignment-and-size
+        self.elem_type().align()
+    }
+
+    /// Distance between two consecutive elements of the array, in bytes.
+    pub fn stride(&self) -> usize {
+        let elem_type = self.elem_type();
+        elem_type.size().next_multiple_of(elem_type.align())
+    }
+}
+
+impl ToWgslString for ArrayType {
+    fn to_wgsl_string(&self) -> String {
+        format!(
+            "array<{},{}>",
+            self.elem_type().to_wgsl_string(),
+            self.count
+        )
+    }
+}
+
 /// Type of an [`Attribute`]'s value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -411,6 +509,11 @@ pub enum ValueType {
     Vector(VectorType),
     /// A floating-point matrix type of size between 2x2 and 4x4.
     Matrix(MatrixType),
+    /// A fixed-size array type.
+    ///
+    /// Array types can't be used for attributes or properties; they're only
+    /// used to describe collections of values passed between graph nodes.
+    Array(ArrayType),
 }
 
 impl Display for ValueType {
@@ -421,6 +524,7 @@ impl Display for ValueType {
             ValueType::Scalar(s) => s.fmt(f),
             ValueType::Vector(v) => v.fmt(f),
             ValueType::Matrix(m) => m.fmt(f),
+            ValueType::Array(a) => a.fmt(f),
         }
     }
 }
@@ -432,6 +536,7 @@ impl ValueType {
             ValueType::Scalar(s) => s.is_numeric(),
             ValueType::Vector(v) => v.is_numeric(),
             ValueType::Matrix(_) => true,
+            ValueType::Array(_) => false,
         }
     }
 
@@ -450,12 +555,18 @@ impl ValueType {
         matches!(self, ValueType::Matrix(_))
     }
 
+    /// Is the type an array type?
+    pub fn is_array(&self) -> bool {
+        matches!(self, ValueType::Array(_))
+    }
+
     /// Size of a value of this type, in bytes.
     pub fn size(&self) -> usize {
         match self {
             ValueType::Scalar(s) => s.size(),
             ValueType::Vector(v) => v.size(),
             ValueType::Matrix(m) => m.size(),
+            ValueType::Array(a) => a.size(),
         }
     }
 
@@ -468,6 +579,7 @@ impl ValueType {
             ValueType::Scalar(s) => s.align(),
             ValueType::Vector(v) => v.align(),
             ValueType::Matrix(m) => m.align(),
+            ValueType::Array(a) => a.align(),
         }
     }
 }
@@ -490,12 +602,19 @@ impl From<MatrixType> for ValueType {
     }
 }
 
+impl From<ArrayType> for ValueType {
+    fn from(value: ArrayType) -> Self {
+        ValueType::Array(value)
+    }
+}
+
 impl ToWgslString for ValueType {
     fn to_wgsl_string(&self) -> String {
         match self {
             ValueType::Scalar(s) => s.to_wgsl_string(),
             ValueType::Vector(v) => v.to_wgsl_string(),
             ValueType::Matrix(m) => m.to_wgsl_string(),
+            ValueType::Array(a) => a.to_wgsl_string(),
         }
     }
 }
@@ -1940,6 +2059,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn array_type() {
+        let t = ArrayType::new(VectorType::VEC3F, 4);
+        assert_eq!(t.elem_type(), ValueType::Vector(VectorType::VEC3F));
+        assert_eq!(t.count(), 4);
+        assert_eq!(t.stride(), 16);
+        assert_eq!(t.size(), 64);
+        assert_eq!(t.align(), 16);
+        assert_eq!(t.to_string(), "array<vec3<f32>, 4>");
+        assert_eq!(t.to_wgsl_string(), "array<vec3<f32>,4>");
+
+        let t = ArrayType::new(ScalarType::Float, 3);
+        assert_eq!(t.elem_type(), ValueType::Scalar(ScalarType::Float));
+        assert_eq!(t.stride(), 4);
+        assert_eq!(t.size(), 12);
+        assert_eq!(t.align(), 4);
+
+        let v = ValueType::Array(t);
+        assert!(v.is_array());
+        assert!(!v.is_scalar());
+        assert!(!v.is_vector());
+        assert!(!v.is_matrix());
+        assert!(!v.is_numeric());
+        assert_eq!(v.size(), t.size());
+        assert_eq!(v.align(), t.align());
+        assert_eq!(ValueType::from(t), v);
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_type_empty() {
+        let _ = ArrayType::new(ScalarType::Float, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_type_nested() {
+        let _ = ArrayType::new(ArrayType::new(ScalarType::Float, 2), 2);
+    }
+
     const TEST_ATTR_NAME: &str = "test_attr";
     const TEST_ATTR_INNER: &AttributeInner = &AttributeInner::new(
         Cow::Borrowed(TEST_ATTR_NAME),
@@ -102,16 +102,20 @@
 //! [`Modifier`]: crate::Modifier
 //! [`EffectAsset`]: crate::EffectAsset
 
-use std::{cell::RefCell, num::NonZeroU32, rc::Rc};
+use std::{cell::RefCell, num::NonZeroU32, ops::Sub, rc::Rc};
 
-use bevy::{prelude::default, reflect::Reflect};
+use bevy::{
+    math::{Vec2, Vec3, Vec4},
+    prelude::default,
+    reflect::{FromReflect, Reflect},
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::Value;
+use super::{GraphError, Value};
 use crate::{
-    Attribute, ModifierContext, ParticleLayout, Property, PropertyLayout, ScalarType,
-    TextureLayout, TextureSlot, ToWgslString, ValueType, VectorType,
+    gradient::Lerp, Attribute, Gradient, ModifierContext, ParticleLayout, Property, PropertyLayout,
+    ScalarType, TextureLayout, TextureSlot, ToWgslString, ValueType, VectorType,
 };
 
 /// A one-based ID into a collection of a [`Module`].
@@ -235,6 +239,39 @@ impl TextureHandle {
     }
 }
 
+/// Handle of a curve inside a given [`Module`].
+///
+/// A handle uniquely references a [`Curve`] stored inside a [`Module`]. It's a
+/// lightweight representation, similar to a simple array index. For this
+/// reason, it's easily copyable. However it's also lacking any kind of error
+/// checking, and mixing handles to different modules produces undefined
+/// behaviors (like an index does when indexing the wrong array).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, Serialize, Deserialize,
+)]
+#[repr(transparent)]
+#[serde(transparent)]
+pub struct CurveHandle {
+    id: Id,
+}
+
+impl CurveHandle {
+    /// Create a new handle from a 1-based [`Id`] as a `usize`, for cases where
+    /// the index is known to be non-zero already.
+    #[allow(unsafe_code)]
+    unsafe fn new_unchecked(id: usize) -> Self {
+        debug_assert!(id != 0);
+        Self {
+            id: NonZeroU32::new_unchecked(id as u32),
+        }
+    }
+
+    /// Get the zero-based index into the array of the module.
+    fn index(&self) -> usize {
+        (self.id.get() - 1) as usize
+    }
+}
+
 /// Container for expressions.
 ///
 /// A module represents a storage for a set of expressions used in a single
@@ -262,6 +299,9 @@ pub struct Module {
     properties: Vec<Property>,
     /// Texture layout.
     texture_layout: TextureLayout,
+    /// Curves sampled by a [`CurveSampleExpr`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    curves: Vec<Curve>,
 }
 
 macro_rules! impl_module_unary {
@@ -301,6 +341,7 @@ impl Module {
             expressions: expr,
             properties: vec![],
             texture_layout: default(),
+            curves: vec![],
         }
     }
 
@@ -375,6 +416,41 @@ impl Module {
         }
     }
 
+    /// Add a new curve to the module.
+    ///
+    /// The curve can then be sampled with [`sample_curve()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the curve has no key.
+    ///
+    /// [`sample_curve()`]: crate::Module::sample_curve
+    pub fn add_curve(&mut self, curve: impl Into<Curve>) -> CurveHandle {
+        let curve = curve.into();
+        assert!(!curve.is_empty(), "Cannot add a curve without any key.");
+        self.curves.push(curve);
+        // SAFETY - We just pushed a new curve into the array, so its length is
+        // non-zero.
+        #[allow(unsafe_code)]
+        unsafe {
+            CurveHandle::new_unchecked(self.curves.len())
+        }
+    }
+
+    /// Get an existing curve by handle.
+    ///
+    /// Existing curves are curves previously created with [`add_curve()`].
+    ///
+    /// [`add_curve()`]: crate::Module::add_curve
+    pub fn get_curve(&self, curve: CurveHandle) -> Option<&Curve> {
+        self.curves.get(curve.index())
+    }
+
+    /// Get the list of existing curves.
+    pub fn curves(&self) -> &[Curve] {
+        &self.curves
+    }
+
     /// Append a new expression to the module.
     fn push(&mut self, expr: impl Into<Expr>) -> ExprHandle {
         self.expressions.push(expr.into());
@@ -437,26 +513,32 @@ impl Module {
     impl_module_unary!(abs, Abs);
     impl_module_unary!(all, All);
     impl_module_unary!(any, Any);
+    impl_module_unary!(atan, Atan);
     impl_module_unary!(ceil, Ceil);
     impl_module_unary!(cos, Cos);
     impl_module_unary!(exp, Exp);
     impl_module_unary!(exp2, Exp2);
     impl_module_unary!(floor, Floor);
     impl_module_unary!(fract, Fract);
+    impl_module_unary!(hash, Hash);
     impl_module_unary!(inverse_sqrt, InvSqrt);
     impl_module_unary!(length, Length);
     impl_module_unary!(log, Log);
     impl_module_unary!(log2, Log2);
     impl_module_unary!(normalize, Normalize);
+    impl_module_unary!(not, Not);
     impl_module_unary!(pack4x8snorm, Pack4x8snorm);
     impl_module_unary!(pack4x8unorm, Pack4x8unorm);
+    impl_module_unary!(round, Round);
     impl_module_unary!(saturate, Saturate);
+    impl_module_unary!(simplex_noise, SimplexNoise);
     impl_module_unary!(sign, Sign);
     impl_module_unary!(sin, Sin);
     impl_module_unary!(sqrt, Sqrt);
     impl_module_unary!(tan, Tan);
     impl_module_unary!(unpack4x8snorm, Unpack4x8snorm);
     impl_module_unary!(unpack4x8unorm, Unpack4x8unorm);
+    impl_module_unary!(value_noise, ValueNoise);
     impl_module_unary!(w, W);
     impl_module_unary!(x, X);
     impl_module_unary!(y, Y);
@@ -490,10 +572,13 @@ impl Module {
     }
 
     impl_module_binary!(add, Add);
+    impl_module_binary!(and, And);
+    impl_module_binary!(atan2, Atan2);
     impl_module_binary!(cross, Cross);
     impl_module_binary!(distance, Distance);
     impl_module_binary!(div, Div);
     impl_module_binary!(dot, Dot);
+    impl_module_binary!(equal, Equal);
     impl_module_binary!(ge, GreaterThanOrEqual);
     impl_module_binary!(gt, GreaterThan);
     impl_module_binary!(le, LessThanOrEqual);
@@ -501,6 +586,9 @@ impl Module {
     impl_module_binary!(max, Max);
     impl_module_binary!(min, Min);
     impl_module_binary!(mul, Mul);
+    impl_module_binary!(or, Or);
+    impl_module_binary!(pow, Pow);
+    impl_module_binary!(reflect, Reflect);
     impl_module_binary!(rem, Remainder);
     impl_module_binary!(step, Step);
     impl_module_binary!(sub, Sub);
@@ -542,8 +630,12 @@ impl Module {
         })
     }
 
+    impl_module_ternary!(clamp, Clamp);
     impl_module_ternary!(mix, Mix);
+    impl_module_ternary!(refract, Refract);
+    impl_module_ternary!(select, Select);
     impl_module_ternary!(smoothstep, SmoothStep);
+    impl_module_ternary!(vec3, Vec3);
 
     /// Build a cast expression and append it to the module.
     ///
@@ -573,6 +665,44 @@ impl Module {
         self.push(Expr::Cast(expr))
     }
 
+    /// Build a curve sample expression and append it to the module.
+    ///
+    /// The expression samples the `curve` at the position given by the `t`
+    /// expression. The curve must have been previously added to this module
+    /// with [`add_curve()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics in some cases if the `t` handle does not reference an existing
+    /// expression in the current module.
+    ///
+    /// [`add_curve()`]: crate::Module::add_curve
+    pub fn sample_curve(&mut self, curve: CurveHandle, t: ExprHandle) -> ExprHandle {
+        assert!(t.index() < self.expressions.len());
+        self.push(Expr::CurveSample(CurveSampleExpr::new(curve, t)))
+    }
+
+    /// Build a swizzle expression and append it to the module.
+    ///
+    /// The handle to the expression representing the operand of the swizzle
+    /// must be valid, that is reference an expression contained in the current
+    /// [`Module`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the swizzle mask is invalid. See [`SwizzleExpr::new()`]
+    /// for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics in some cases if the operand handle does not reference an
+    /// existing expression in the current module.
+    pub fn swizzle(&mut self, expr: ExprHandle, mask: &str) -> Result<ExprHandle, ExprError> {
+        assert!(expr.index() < self.expressions.len());
+        let expr = SwizzleExpr::new(expr, mask)?;
+        Ok(self.push(Expr::Swizzle(expr)))
+    }
+
     /// Get an existing expression from its handle.
     #[inline]
     pub fn get(&self, expr: ExprHandle) -> Option<&Expr> {
@@ -633,6 +763,54 @@ impl Module {
         expr.has_side_effect(self)
     }
 
+    /// Get the particle attributes read by an expression.
+    ///
+    /// This walks the expression and all its sub-expressions, and returns the
+    /// attributes of all the [`Expr::Attribute`] expressions found, without
+    /// duplicates, in the order they're first found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expr` doesn't refer to an expression of this module.
+    pub fn used_attributes(&self, expr: ExprHandle) -> Vec<Attribute> {
+        let mut attributes = vec![];
+        self.collect_attributes(expr, &mut attributes);
+        attributes
+    }
+
+    fn collect_attributes(&self, expr: ExprHandle, attributes: &mut Vec<Attribute>) {
+        match self.get(expr).unwrap() {
+            Expr::BuiltIn(_) | Expr::Literal(_) | Expr::Property(_) => {}
+            Expr::Attribute(expr) => {
+                if !attributes.contains(&expr.attr) {
+                    attributes.push(expr.attr);
+                }
+            }
+            Expr::Unary { expr, .. } => self.collect_attributes(*expr, attributes),
+            Expr::Binary { left, right, .. } => {
+                self.collect_attributes(*left, attributes);
+                self.collect_attributes(*right, attributes);
+            }
+            Expr::Ternary {
+                first,
+                second,
+                third,
+                ..
+            } => {
+                self.collect_attributes(*first, attributes);
+                self.collect_attributes(*second, attributes);
+                self.collect_attributes(*third, attributes);
+            }
+            Expr::Cast(expr) => self.collect_attributes(expr.inner, attributes),
+            Expr::TextureSample(expr) => {
+                self.collect_attributes(expr.image, attributes);
+                self.collect_attributes(expr.coordinates, attributes);
+            }
+            Expr::Swizzle(expr) => self.collect_attributes(expr.inner, attributes),
+            Expr::CurveSample(expr) => self.collect_attributes(expr.t, attributes),
+        }
+    }
+
     /// Get the texture layout of this module.
     pub fn texture_layout(&self) -> TextureLayout {
         self.texture_layout.clone()
@@ -659,6 +837,15 @@ pub enum ExprError {
     #[error("Graph evaluation error: {0}")]
     GraphEvalError(String),
 
+    /// Graph error.
+    ///
+    /// Raised when evaluating a [`Graph`] which is not valid, for example
+    /// because it contains a cycle or some input slot is not linked.
+    ///
+    /// [`Graph`]: crate::graph::Graph
+    #[error("Graph error: {0}")]
+    GraphError(#[from] GraphError),
+
     /// Error resolving a property.
     ///
     /// An unknown property was not defined in the evaluation context, which
@@ -841,6 +1028,36 @@ pub enum Expr {
     /// An expression to sample a texture from the effect's material. Currently
     /// only color textures (returning a `vec4<f32>`) are supported.
     TextureSample(TextureSampleExpr),
+
+    /// Swizzle expression.
+    ///
+    /// An expression to rearrange the components of a vector expression.
+    Swizzle(SwizzleExpr),
+
+    /// Curve sample expression.
+    ///
+    /// An expression to sample a [`Curve`] stored in the [`Module`].
+    CurveSample(CurveSampleExpr),
+}
+
+/// Get the suffix of the functions we define ourselves for a given operand
+/// type, which disambiguate the overloads WGSL doesn't support.
+fn type_suffix(value_type: ValueType) -> Result<&'static str, ExprError> {
+    match value_type {
+        ValueType::Scalar(ScalarType::Float) => Ok("f"),
+        ValueType::Vector(vector_type) if vector_type.elem_type() == ScalarType::Float => {
+            match vector_type.count() {
+                2 => Ok("vec2"),
+                3 => Ok("vec3"),
+                4 => Ok("vec4"),
+                _ => unreachable!(),
+            }
+        }
+        _ => {
+            // Add more types here as needed.
+            Err(ExprError::TypeError("Unsupported type".to_string()))
+        }
+    }
 }
 
 impl Expr {
@@ -886,6 +1103,8 @@ impl Expr {
             } => module.is_const(*first) && module.is_const(*second) && module.is_const(*third),
             Expr::Cast(expr) => module.is_const(expr.inner),
             Expr::TextureSample(_) => false,
+            Expr::Swizzle(expr) => module.is_const(expr.inner),
+            Expr::CurveSample(expr) => module.is_const(expr.t),
         }
     }
 
@@ -918,6 +1137,35 @@ impl Expr {
             }
             Expr::Cast(expr) => module.has_side_effect(expr.inner),
             Expr::TextureSample(_) => false,
+            Expr::Swizzle(expr) => module.has_side_effect(expr.inner),
+            Expr::CurveSample(expr) => module.has_side_effect(expr.t),
+        }
+    }
+
+    /// Get the literal expression, if this is an [`Expr::Literal`].
+    ///
+    /// This allows inspecting the constant value of an expression, for example
+    /// to fold constant sub-expressions of the expressions produced by a
+    /// [`Graph`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut module = Module::default();
+    /// let x = module.lit(3.);
+    /// let literal = module.get(x).unwrap().as_literal().unwrap();
+    /// assert_eq!(literal.value(), graph::Value::Scalar(3_f32.into()));
+    ///
+    /// let y = module.attr(Attribute::AGE);
+    /// assert!(module.get(y).unwrap().as_literal().is_none());
+    /// ```
+    ///
+    /// [`Graph`]: crate::graph::Graph
+    pub fn as_literal(&self) -> Option<&LiteralExpr> {
+        match self {
+            Expr::Literal(expr) => Some(expr),
+            _ => None,
         }
     }
 
@@ -951,6 +1199,8 @@ impl Expr {
             Expr::Ternary { .. } => None,
             Expr::Cast(expr) => Some(expr.value_type()),
             Expr::TextureSample(expr) => Some(expr.value_type()),
+            Expr::Swizzle(_) => None,
+            Expr::CurveSample(_) => None,
         }
     }
 
@@ -985,9 +1235,9 @@ impl Expr {
             Expr::Literal(expr) => expr.eval(context),
             Expr::Property(expr) => expr.eval(module, context),
             Expr::Attribute(expr) => expr.eval(context),
-            Expr::Unary { op, expr } => {
+            Expr::Unary { op, expr: inner } => {
                 // Recursively evaluate child expressions throught the context to ensure caching
-                let expr = context.eval(module, *expr)?;
+                let expr = context.eval(module, *inner)?;
 
                 // if expr.value_type() != self.value_type() {
                 //     return Err(ExprError::TypeError(format!(
@@ -996,7 +1246,16 @@ impl Expr {
                 //     )));
                 // }
 
-                Ok(if op.is_functional() {
+                Ok(if op.needs_type_suffix() {
+                    let value_type = module.get(*inner).and_then(|arg| arg.value_type());
+                    let Some(value_type) = value_type else {
+                        return Err(ExprError::TypeError(
+                            "Can't determine the type of the operand".to_string(),
+                        ));
+                    };
+                    let suffix = type_suffix(value_type)?;
+                    format!("{}_{}({})", op.to_wgsl_string(), suffix, expr)
+                } else if op.is_functional() {
                     format!("{}({})", op.to_wgsl_string(), expr)
                 } else {
                     format!("{}.{}", expr, op.to_wgsl_string())
@@ -1026,24 +1285,7 @@ impl Expr {
                         if lhs_type != rhs_type {
                             return Err(ExprError::TypeError("Mismatched types".to_string()));
                         }
-                        let value_type = lhs_type.unwrap();
-                        let suffix = match value_type {
-                            ValueType::Scalar(ScalarType::Float) => "f",
-                            ValueType::Vector(vector_type)
-                                if vector_type.elem_type() == ScalarType::Float =>
-                            {
-                                match vector_type.count() {
-                                    2 => "vec2",
-                                    3 => "vec3",
-                                    4 => "vec4",
-                                    _ => unreachable!(),
-                                }
-                            }
-                            _ => {
-                                // Add more types here as needed.
-                                return Err(ExprError::TypeError("Unsupported type".to_string()));
-                            }
-                        };
+                        let suffix = type_suffix(lhs_type.unwrap())?;
 
                         Ok(format!(
                             "{}_{}({}, {})",
@@ -1102,6 +1344,13 @@ impl Expr {
                 Ok(format!("{}({})", expr.target.to_wgsl_string(), inner))
             }
             Expr::TextureSample(expr) => expr.eval(module, context),
+            Expr::Swizzle(expr) => {
+                // Recursively evaluate child expressions throught the context to ensure caching
+                let inner = context.eval(module, expr.inner)?;
+
+                Ok(format!("({}).{}", inner, expr.mask()))
+            }
+            Expr::CurveSample(expr) => expr.eval(module, context),
         }
     }
 }
@@ -1141,6 +1390,11 @@ impl LiteralExpr {
         self.value.value_type()
     }
 
+    /// Get the constant value of the expression.
+    pub fn value(&self) -> Value {
+        self.value
+    }
+
     /// Evaluate the expression in the given context.
     pub fn eval(&self, _context: &dyn EvalContext) -> Result<String, ExprError> {
         Ok(self.value.to_wgsl_string())
@@ -1306,12 +1560,17 @@ impl CastExpr {
                 }
                 ValueType::Vector(_) => {
                     // {scalar, vector} -> vector
-                    Some(!matches!(inner_type, ValueType::Matrix(_)))
+                    Some(matches!(
+                        inner_type,
+                        ValueType::Scalar(_) | ValueType::Vector(_)
+                    ))
                 }
                 ValueType::Matrix(_) => {
                     // matrix -> matrix only
                     Some(matches!(inner_type, ValueType::Matrix(_)))
                 }
+                // arrays can't be cast
+                ValueType::Array(_) => Some(false),
             }
         } else {
             None
@@ -1319,6 +1578,85 @@ impl CastExpr {
     }
 }
 
+/// Expression to rearrange the components of a vector.
+///
+/// The swizzle mask is a sequence of 1 to 4 components among `x`, `y`, `z`,
+/// and `w`, like `xyz` or `wzyx`. Components can be repeated, like in `xxxx`.
+/// The result is a scalar if the mask has a single component, or a vector with
+/// as many components as the mask otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub struct SwizzleExpr {
+    /// The operand expression to swizzle.
+    inner: ExprHandle,
+    /// Indices of the swizzled components, from 0 (`x`) to 3 (`w`). Only the
+    /// first `len` entries are valid.
+    components: [u8; 4],
+    /// Number of components of the swizzle mask.
+    len: u8,
+}
+
+impl SwizzleExpr {
+    /// Create a new swizzle expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ExprError::SyntaxError`] if the mask is invalid. See
+    /// [`validate_mask()`] for details.
+    ///
+    /// [`validate_mask()`]: SwizzleExpr::validate_mask
+    pub fn new(inner: ExprHandle, mask: &str) -> Result<Self, ExprError> {
+        Self::validate_mask(mask)?;
+        let mut components = [0; 4];
+        for (index, c) in mask.chars().enumerate() {
+            components[index] = "xyzw".find(c).unwrap() as u8;
+        }
+        Ok(Self {
+            inner,
+            components,
+            len: mask.len() as u8,
+        })
+    }
+
+    /// Check if a swizzle mask is valid.
+    ///
+    /// A valid mask has 1 to 4 components, each of which is one of `x`, `y`,
+    /// `z`, or `w`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ExprError::SyntaxError`] describing why the mask is
+    /// invalid.
+    pub fn validate_mask(mask: &str) -> Result<(), ExprError> {
+        if mask.is_empty() || mask.len() > 4 {
+            return Err(ExprError::SyntaxError(format!(
+                "Invalid swizzle mask '{}': expected 1 to 4 components.",
+                mask
+            )));
+        }
+        if let Some(c) = mask.chars().find(|c| !"xyzw".contains(*c)) {
+            return Err(ExprError::SyntaxError(format!(
+                "Invalid swizzle mask '{}': unknown component '{}'.",
+                mask, c
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get the operand expression to swizzle.
+    #[inline]
+    pub fn inner(&self) -> ExprHandle {
+        self.inner
+    }
+
+    /// Get the swizzle mask, like `xyz`.
+    pub fn mask(&self) -> String {
+        self.components[..self.len as usize]
+            .iter()
+            .map(|&index| ['x', 'y', 'z', 'w'][index as usize])
+            .collect()
+    }
+}
+
 /// Expression to sample a texture from the effect's material.
 ///
 /// This currently supports only color textures, that is all textures which
@@ -1381,6 +1719,166 @@ impl TextureSampleExpr {
     }
 }
 
+/// Curve of values sampled by a [`CurveSampleExpr`].
+///
+/// A curve is a [`Gradient`] of scalar or vector floating-point values, which
+/// is sampled in shader code by linearly interpolating between its keys.
+/// Outside of the range of its keys, the curve is clamped to the value of its
+/// first or last key.
+#[derive(Debug, Clone, PartialEq, Hash, Reflect, Serialize, Deserialize)]
+pub enum Curve {
+    /// Curve of `f32` values.
+    Float(Gradient<f32>),
+    /// Curve of `vec2<f32>` values.
+    Vec2(Gradient<Vec2>),
+    /// Curve of `vec3<f32>` values.
+    Vec3(Gradient<Vec3>),
+    /// Curve of `vec4<f32>` values.
+    Vec4(Gradient<Vec4>),
+}
+
+impl Curve {
+    /// Get the type of the values of the curve.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Curve::Float(_) => ValueType::Scalar(ScalarType::Float),
+            Curve::Vec2(_) => ValueType::Vector(VectorType::VEC2F),
+            Curve::Vec3(_) => ValueType::Vector(VectorType::VEC3F),
+            Curve::Vec4(_) => ValueType::Vector(VectorType::VEC4F),
+        }
+    }
+
+    /// Get the number of keys of the curve.
+    pub fn len(&self) -> usize {
+        match self {
+            Curve::Float(gradient) => gradient.len(),
+            Curve::Vec2(gradient) => gradient.len(),
+            Curve::Vec3(gradient) => gradient.len(),
+            Curve::Vec4(gradient) => gradient.len(),
+        }
+    }
+
+    /// Check if the curve has no key.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the WGSL code sampling the curve at the given position.
+    ///
+    /// The code is a single expression, which starts from the value of the
+    /// first key and adds the contribution of each segment between two
+    /// consecutive keys. Keys with the same ratio produce a step.
+    fn to_wgsl_sample(&self, t: &str) -> String {
+        match self {
+            Curve::Float(gradient) => Self::sample_code(gradient, t),
+            Curve::Vec2(gradient) => Self::sample_code(gradient, t),
+            Curve::Vec3(gradient) => Self::sample_code(gradient, t),
+            Curve::Vec4(gradient) => Self::sample_code(gradient, t),
+        }
+    }
+
+    fn sample_code<T>(gradient: &Gradient<T>, t: &str) -> String
+    where
+        T: Lerp + FromReflect + ToWgslString + Sub<Output = T>,
+    {
+        let keys = gradient.keys();
+        let mut code = keys[0].value.to_wgsl_string();
+        for pair in keys.windows(2) {
+            let delta = pair[1].value - pair[0].value;
+            let t0 = pair[0].ratio();
+            let dt = pair[1].ratio() - t0;
+            let factor = if dt > 0. {
+                format!(
+                    "clamp(({} - {}) * {}, 0., 1.)",
+                    t,
+                    t0.to_wgsl_string(),
+                    (1. / dt).to_wgsl_string()
+                )
+            } else {
+                format!("step({}, {})", t0.to_wgsl_string(), t)
+            };
+            code += &format!(" + {} * {}", delta.to_wgsl_string(), factor);
+        }
+        code
+    }
+}
+
+impl From<Gradient<f32>> for Curve {
+    fn from(value: Gradient<f32>) -> Self {
+        Curve::Float(value)
+    }
+}
+
+impl From<Gradient<Vec2>> for Curve {
+    fn from(value: Gradient<Vec2>) -> Self {
+        Curve::Vec2(value)
+    }
+}
+
+impl From<Gradient<Vec3>> for Curve {
+    fn from(value: Gradient<Vec3>) -> Self {
+        Curve::Vec3(value)
+    }
+}
+
+impl From<Gradient<Vec4>> for Curve {
+    fn from(value: Gradient<Vec4>) -> Self {
+        Curve::Vec4(value)
+    }
+}
+
+/// Expression to sample a [`Curve`] stored in a [`Module`].
+///
+/// The curve is sampled by linearly interpolating between its two keys
+/// surrounding the sampling position. The expression has the value type of the
+/// curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub struct CurveSampleExpr {
+    /// The curve to sample, added with [`Module::add_curve()`].
+    pub curve: CurveHandle,
+    /// The position to sample the curve at.
+    ///
+    /// Expression type: `f32`
+    pub t: ExprHandle,
+}
+
+impl CurveSampleExpr {
+    /// Create a new curve sample expression.
+    #[inline]
+    pub fn new(curve: CurveHandle, t: ExprHandle) -> Self {
+        Self { curve, t }
+    }
+
+    /// Evaluate the expression in the given context.
+    pub fn eval(
+        &self,
+        module: &Module,
+        context: &mut dyn EvalContext,
+    ) -> Result<String, ExprError> {
+        let curve = module.get_curve(self.curve).ok_or_else(|| {
+            ExprError::InvalidExprHandleError(format!(
+                "Cannot find curve with handle {:?} in the current module.",
+                self.curve
+            ))
+        })?;
+        if curve.is_empty() {
+            return Err(ExprError::TypeError(
+                "Cannot sample an empty curve.".to_string(),
+            ));
+        }
+
+        let mut t = context.eval(module, self.t)?;
+        if curve.len() > 1 {
+            // The position is used once per segment; store it in a local
+            // variable to evaluate it only once.
+            let var_name = context.make_local_var();
+            context.push_stmt(&format!("let {} = {};", var_name, t));
+            t = var_name;
+        }
+        Ok(format!("({})", curve.to_wgsl_sample(&t)))
+    }
+}
+
 /// Built-in operators.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum BuiltInOperator {
@@ -1467,6 +1965,39 @@ pub enum BuiltInOperator {
     ///
     /// Type: `bool`
     IsAlive,
+    /// Maximum number of particles of the particle group being updated, that
+    /// is its capacity.
+    ///
+    /// This value is only available in the update pass. Attempting to use it
+    /// inside either the init or render passes will generate an invalid shader.
+    ///
+    /// Type: `u32`
+    ParticleCount,
+    /// Number of alive particles of the particle group being updated.
+    ///
+    /// The value is the number of particles alive at the beginning of the
+    /// update pass, and doesn't account for particles dying during that pass.
+    ///
+    /// This value is only available in the update pass. Attempting to use it
+    /// inside either the init or render passes will generate an invalid shader.
+    ///
+    /// Type: `u32`
+    AliveCount,
+    /// Origin of the effect, in world space.
+    ///
+    /// This is the translation of the transform of the emitter, as read from
+    /// the spawner of the effect instance on the GPU.
+    ///
+    /// Type: `vec3<f32>`
+    EffectOrigin,
+    /// Position of the camera, in world space.
+    ///
+    /// This value is only available in the render pass, where it's the position
+    /// of the view being rendered. Attempting to use it inside either the init
+    /// or update passes will generate an invalid shader.
+    ///
+    /// Type: `vec3<f32>`
+    CameraPosition,
 }
 
 impl BuiltInOperator {
@@ -1504,9 +2035,14 @@ impl BuiltInOperator {
                     }
                 }
                 ValueType::Matrix(_) => panic!("Invalid BuiltInOperator::Rand(ValueType::Matrix)."),
+                ValueType::Array(_) => panic!("Invalid BuiltInOperator::Rand(ValueType::Array)."),
             },
             BuiltInOperator::AlphaCutoff => "alpha_cutoff",
             BuiltInOperator::IsAlive => "is_alive",
+            BuiltInOperator::ParticleCount => "particle_count",
+            BuiltInOperator::AliveCount => "alive_count",
+            BuiltInOperator::EffectOrigin => "effect_origin",
+            BuiltInOperator::CameraPosition => "camera_position",
         }
     }
 
@@ -1522,6 +2058,11 @@ impl BuiltInOperator {
             BuiltInOperator::Rand(value_type) => *value_type,
             BuiltInOperator::AlphaCutoff => ValueType::Scalar(ScalarType::Float),
             BuiltInOperator::IsAlive => ValueType::Scalar(ScalarType::Bool),
+            BuiltInOperator::ParticleCount => ValueType::Scalar(ScalarType::Uint),
+            BuiltInOperator::AliveCount => ValueType::Scalar(ScalarType::Uint),
+            BuiltInOperator::EffectOrigin | BuiltInOperator::CameraPosition => {
+                ValueType::Vector(VectorType::VEC3F)
+            }
         }
     }
 
@@ -1538,6 +2079,13 @@ impl ToWgslString for BuiltInOperator {
         match self {
             BuiltInOperator::Rand(_) => format!("{}()", self.name()),
             BuiltInOperator::IsAlive => "is_alive".to_string(),
+            BuiltInOperator::ParticleCount | BuiltInOperator::AliveCount => self.name().to_string(),
+            // The spawner transform is stored transposed, so the translation is
+            // the last column of each row.
+            BuiltInOperator::EffectOrigin => "vec3<f32>(spawner.transform[0].w, \
+spawner.transform[1].w, spawner.transform[2].w)"
+                .to_string(),
+            BuiltInOperator::CameraPosition => "view.world_from_view[3].xyz".to_string(),
             _ => format!("sim_params.{}", self.name()),
         }
     }
@@ -1632,6 +2180,12 @@ pub enum UnaryOperator {
     /// Invalid for any other type of operand.
     Any,
 
+    /// Arctangent operator.
+    ///
+    /// Return the arctangent of the operand, in radians in \[-π/2:π/2\],
+    /// component-wise for vectors.
+    Atan,
+
     /// Ceiling operator.
     ///
     /// Return the unique integral number `k` such that `k-1 < x <= k`, where
@@ -1665,6 +2219,15 @@ pub enum UnaryOperator {
     /// floor(x)`, component-wise for vectors.
     Fract,
 
+    /// Hash operator.
+    ///
+    /// Return a pseudo-random floating-point scalar in \[0:1\) obtained by
+    /// hashing the `u32` operand. Unlike the random operators, the result only
+    /// depends on the operand, so the same operand always produces the same
+    /// value, for example across frames when hashing a per-particle seed. The
+    /// WGSL hash function is defined by Hanabi, and available in all shaders.
+    Hash,
+
     /// Inverse square root operator.
     ///
     /// Return the inverse square root of the floating-point operand (`1.0 /
@@ -1699,6 +2262,12 @@ pub enum UnaryOperator {
     /// operands.
     Normalize,
 
+    /// Logical "not" operator.
+    ///
+    /// Return the logical negation of the operand, component-wise for vectors.
+    /// Only valid for boolean scalar or vector operands.
+    Not,
+
     /// Packing operator from `vec4<f32>` to `u32` (signed normalized).
     ///
     /// Convert the four components of a signed normalized floating point vector
@@ -1715,12 +2284,27 @@ pub enum UnaryOperator {
     /// be in `[0:1]` before packing; values outside this range are clamped.
     Pack4x8unorm,
 
+    /// Rounding operator.
+    ///
+    /// Return the integral number `k` closest to the operand `x`, rounding
+    /// halfway cases to the nearest even integer, component-wise for vectors.
+    Round,
+
     /// Saturate operator.
     ///
     /// Clamp the value of the operand to the \[0:1\] range, component-wise for
     /// vectors.
     Saturate,
 
+    /// Simplex noise operator.
+    ///
+    /// Return the value of a simplex noise at the position given by the
+    /// operand, as a floating-point scalar in \[-1:1\]. The operand must be a
+    /// `vec2<f32>` or a `vec3<f32>` whose type is known when the expression
+    /// is evaluated. The WGSL noise functions are defined by Hanabi, and
+    /// available in all shaders.
+    SimplexNoise,
+
     /// Sign operator.
     ///
     /// Return a value representing the sign of a floating point scalar or
@@ -1757,6 +2341,15 @@ pub enum UnaryOperator {
     /// `[0:1]`.
     Unpack4x8unorm,
 
+    /// Value noise operator.
+    ///
+    /// Return the value of a value noise at the position given by the
+    /// operand, as a floating-point scalar in \[-1:1\]. The operand must be a
+    /// `vec2<f32>` or a `vec3<f32>` whose type is known when the expression
+    /// is evaluated. The WGSL noise functions are defined by Hanabi, and
+    /// available in all shaders.
+    ValueNoise,
+
     /// Get the fourth component of a vector.
     ///
     /// This is only valid for vectors of rank 4.
@@ -1790,6 +2383,19 @@ impl UnaryOperator {
             UnaryOperator::X | UnaryOperator::Y | UnaryOperator::Z | UnaryOperator::W
         )
     }
+
+    /// Check if a unary operator needs a type suffix.
+    ///
+    /// This is currently just for `value_noise` ([`UnaryOperator::ValueNoise`])
+    /// and `simplex_noise` ([`UnaryOperator::SimplexNoise`]), which are
+    /// functions we define ourselves. WGSL doesn't support user-defined
+    /// function overloading, so we need a suffix to disambiguate the types.
+    pub fn needs_type_suffix(&self) -> bool {
+        matches!(
+            *self,
+            UnaryOperator::ValueNoise | UnaryOperator::SimplexNoise
+        )
+    }
 }
 
 impl ToWgslString for UnaryOperator {
@@ -1798,26 +2404,32 @@ impl ToWgslString for UnaryOperator {
             UnaryOperator::Abs => "abs".to_string(),
             UnaryOperator::All => "all".to_string(),
             UnaryOperator::Any => "any".to_string(),
+            UnaryOperator::Atan => "atan".to_string(),
             UnaryOperator::Ceil => "ceil".to_string(),
             UnaryOperator::Cos => "cos".to_string(),
             UnaryOperator::Exp => "exp".to_string(),
             UnaryOperator::Exp2 => "exp2".to_string(),
             UnaryOperator::Floor => "floor".to_string(),
             UnaryOperator::Fract => "fract".to_string(),
+            UnaryOperator::Hash => "hash_float01".to_string(),
             UnaryOperator::InvSqrt => "inverseSqrt".to_string(),
             UnaryOperator::Length => "length".to_string(),
             UnaryOperator::Log => "log".to_string(),
             UnaryOperator::Log2 => "log2".to_string(),
             UnaryOperator::Normalize => "normalize".to_string(),
+            UnaryOperator::Not => "!".to_string(),
             UnaryOperator::Pack4x8snorm => "pack4x8snorm".to_string(),
             UnaryOperator::Pack4x8unorm => "pack4x8unorm".to_string(),
+            UnaryOperator::Round => "round".to_string(),
             UnaryOperator::Saturate => "saturate".to_string(),
+            UnaryOperator::SimplexNoise => "simplex_noise".to_string(),
             UnaryOperator::Sign => "sign".to_string(),
             UnaryOperator::Sin => "sin".to_string(),
             UnaryOperator::Sqrt => "sqrt".to_string(),
             UnaryOperator::Tan => "tan".to_string(),
             UnaryOperator::Unpack4x8snorm => "unpack4x8snorm".to_string(),
             UnaryOperator::Unpack4x8unorm => "unpack4x8unorm".to_string(),
+            UnaryOperator::ValueNoise => "value_noise".to_string(),
             UnaryOperator::W => "w".to_string(),
             UnaryOperator::X => "x".to_string(),
             UnaryOperator::Y => "y".to_string(),
@@ -1838,6 +2450,21 @@ pub enum BinaryOperator {
     /// Returns the sum of its operands. Only valid for numeric operands.
     Add,
 
+    /// Logical "and" operator.
+    ///
+    /// Returns `true` if both operands are `true`. Only valid for boolean
+    /// scalar operands; the right operand is only evaluated if the left one is
+    /// `true`.
+    And,
+
+    /// Two-argument arctangent operator.
+    ///
+    /// Returns the angle in radians in \[-π:π\] between the positive X axis
+    /// and the point `(x, y)`, where `y` is the left operand and `x` the right
+    /// one. Only valid for floating point scalar or vectors of the same rank,
+    /// and applied component-wise for vectors.
+    Atan2,
+
     /// Cross product operator.
     ///
     /// Returns the cross product of the left and right operands. Only valid for
@@ -1863,6 +2490,13 @@ pub enum BinaryOperator {
     /// vector type operands. Always produce a scalar floating-point result.
     Dot,
 
+    /// Equality operator.
+    ///
+    /// Returns `true` if the left operand is equal to the right operand. Only
+    /// valid for scalar and vector types. If the operands are vectors, they
+    /// must be of the same rank, and the result is a bool vector of that rank.
+    Equal,
+
     /// Greater-than operator.
     ///
     /// Returns `true` if the left operand is strictly greater than the right
@@ -1916,6 +2550,28 @@ pub enum BinaryOperator {
     /// Returns the product of its operands. Only valid for numeric operands.
     Mul,
 
+    /// Logical "or" operator.
+    ///
+    /// Returns `true` if any of the operands is `true`. Only valid for boolean
+    /// scalar operands; the right operand is only evaluated if the left one is
+    /// `false`.
+    Or,
+
+    /// Power operator.
+    ///
+    /// Returns the left operand raised to the power of the right operand. Only
+    /// valid for floating point scalar or vectors of the same rank, and applied
+    /// component-wise for vectors.
+    Pow,
+
+    /// Reflection operator.
+    ///
+    /// Returns the reflection of the left operand (incident vector) against a
+    /// surface with the right operand as normal, that is `i - 2 * dot(n, i) *
+    /// n`. Only valid for floating point vectors of the same rank. The normal
+    /// is assumed to be normalized.
+    Reflect,
+
     /// Remainder operator.
     ///
     /// Returns the remainder of the division of the first operand by the
@@ -1976,19 +2632,25 @@ impl BinaryOperator {
     pub fn is_functional(&self) -> bool {
         match *self {
             BinaryOperator::Add
+            | BinaryOperator::And
             | BinaryOperator::Div
+            | BinaryOperator::Equal
             | BinaryOperator::GreaterThan
             | BinaryOperator::GreaterThanOrEqual
             | BinaryOperator::LessThan
             | BinaryOperator::LessThanOrEqual
             | BinaryOperator::Mul
+            | BinaryOperator::Or
             | BinaryOperator::Remainder
             | BinaryOperator::Sub => false,
-            BinaryOperator::Cross
+            BinaryOperator::Atan2
+            | BinaryOperator::Cross
             | BinaryOperator::Distance
             | BinaryOperator::Dot
             | BinaryOperator::Max
             | BinaryOperator::Min
+            | BinaryOperator::Pow
+            | BinaryOperator::Reflect
             | BinaryOperator::Step
             | BinaryOperator::UniformRand
             | BinaryOperator::NormalRand
@@ -2015,10 +2677,13 @@ impl ToWgslString for BinaryOperator {
     fn to_wgsl_string(&self) -> String {
         match *self {
             BinaryOperator::Add => "+".to_string(),
+            BinaryOperator::And => "&&".to_string(),
+            BinaryOperator::Atan2 => "atan2".to_string(),
             BinaryOperator::Cross => "cross".to_string(),
             BinaryOperator::Distance => "distance".to_string(),
             BinaryOperator::Div => "/".to_string(),
             BinaryOperator::Dot => "dot".to_string(),
+            BinaryOperator::Equal => "==".to_string(),
             BinaryOperator::GreaterThan => ">".to_string(),
             BinaryOperator::GreaterThanOrEqual => ">=".to_string(),
             BinaryOperator::LessThan => "<".to_string(),
@@ -2026,6 +2691,9 @@ impl ToWgslString for BinaryOperator {
             BinaryOperator::Max => "max".to_string(),
             BinaryOperator::Min => "min".to_string(),
             BinaryOperator::Mul => "*".to_string(),
+            BinaryOperator::Or => "||".to_string(),
+            BinaryOperator::Pow => "pow".to_string(),
+            BinaryOperator::Reflect => "reflect".to_string(),
             BinaryOperator::Remainder => "%".to_string(),
             BinaryOperator::Step => "step".to_string(),
             BinaryOperator::Sub => "-".to_string(),
@@ -2043,6 +2711,17 @@ impl ToWgslString for BinaryOperator {
 /// operator itself.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum TernaryOperator {
+    /// Clamping operator.
+    ///
+    /// Returns the first argument clamped to the range defined by the second
+    /// (minimum) and third (maximum) arguments. If the operands are vectors,
+    /// they must be of the same rank, and the clamping applies
+    /// component-wise.
+    ///
+    /// The clamping of `x` between `low` and `high` is equivalent to
+    /// `min(max(x, low), high)`.
+    Clamp,
+
     /// Linear blend ("mix") operator.
     ///
     /// Returns the linear blend between the first and second argument, based on
@@ -2054,11 +2733,35 @@ pub enum TernaryOperator {
     /// (1 - t) + y * t`.
     Mix,
 
-    /// Smooth stepping operator.
+    /// Refraction operator.
     ///
-    /// Returns the smooth Hermitian interpolation between the first and second
-    /// argument, calculated at the third argument. If the operands are vectors,
-    /// they must be of the same rank, and the result is a vector of that
+    /// Returns the refraction of the first argument (incident vector) through
+    /// a surface with the second argument as normal, for the ratio of indices
+    /// of refraction given by the third argument. The first and second
+    /// arguments must be floating point vectors of the same rank, and the
+    /// third one a floating point scalar. The incident vector and the normal
+    /// are assumed to be normalized.
+    ///
+    /// In case of total internal reflection, the result is a zero vector.
+    Refract,
+
+    /// Component selection operator.
+    ///
+    /// Returns the second argument if the third argument is `true`, or the
+    /// first argument otherwise. The first and second arguments must be of the
+    /// same type. The third argument is either a boolean scalar, or a vector of
+    /// booleans of the same rank as the other arguments to select
+    /// component-wise.
+    ///
+    /// The order of the arguments follows the WGSL `select(f, t, cond)`
+    /// built-in function.
+    Select,
+
+    /// Smooth stepping operator.
+    ///
+    /// Returns the smooth Hermitian interpolation between the first and second
+    /// argument, calculated at the third argument. If the operands are vectors,
+    /// they must be of the same rank, and the result is a vector of that
     /// rank and same element scalar type.
     ///
     /// The smooth stepping of `low` and `high` at position `x` is equivalent to
@@ -2078,7 +2781,10 @@ pub enum TernaryOperator {
 impl ToWgslString for TernaryOperator {
     fn to_wgsl_string(&self) -> String {
         match *self {
+            TernaryOperator::Clamp => "clamp".to_string(),
             TernaryOperator::Mix => "mix".to_string(),
+            TernaryOperator::Refract => "refract".to_string(),
+            TernaryOperator::Select => "select".to_string(),
             TernaryOperator::SmoothStep => "smoothstep".to_string(),
             TernaryOperator::Vec3 => "vec3".to_string(),
         }
@@ -2437,6 +3143,29 @@ impl WriterExpr {
         self.unary_op(UnaryOperator::Any)
     }
 
+    /// Apply the "atan" (arctangent) operator to the current float scalar or
+    /// vector expression.
+    ///
+    /// This is a unary operator, which applies component-wise to vector operand
+    /// expressions. The result is in radians.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec3;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec3<f32>(1., 1., 1.);`.
+    /// let x = w.lit(Vec3::ONE);
+    ///
+    /// // Atan: `y = atan(x);`
+    /// let y = x.atan();
+    /// ```
+    #[inline]
+    pub fn atan(self) -> Self {
+        self.unary_op(UnaryOperator::Atan)
+    }
+
     /// Apply the "ceil" operator to the current float scalar or vector
     /// expression.
     ///
@@ -2581,6 +3310,28 @@ impl WriterExpr {
         self.unary_op(UnaryOperator::Fract)
     }
 
+    /// Apply the "hash" operator to the current `u32` scalar expression.
+    ///
+    /// This is a unary operator, which applies to a `u32` scalar operand
+    /// expression to produce a pseudo-random float scalar in \[0:1\). The
+    /// same operand always produces the same value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = 42u;`.
+    /// let x = w.lit(42u32);
+    ///
+    /// // Hash: `y = hash_float01(x);`
+    /// let y = x.hash();
+    /// ```
+    #[inline]
+    pub fn hash(self) -> Self {
+        self.unary_op(UnaryOperator::Hash)
+    }
+
     /// Apply the "inverseSqrt" (inverse square root) operator to the current
     /// float scalar or vector expression.
     ///
@@ -2700,6 +3451,29 @@ impl WriterExpr {
         self.unary_op(UnaryOperator::Normalize)
     }
 
+    /// Apply the logical operator "not" to the current boolean expression.
+    ///
+    /// This is a unary operator, which applies component-wise to vector
+    /// operand expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::BVec3;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec3<bool>(true, false, true);`.
+    /// let x = w.lit(BVec3::new(true, false, true));
+    ///
+    /// // Not: `y = !(x);`
+    /// let y = x.not(); // == vec3<bool>(false, true, false)
+    /// ```
+    #[allow(clippy::should_implement_trait)]
+    #[inline]
+    pub fn not(self) -> Self {
+        self.unary_op(UnaryOperator::Not)
+    }
+
     /// Apply the "pack4x8snorm" operator to the current 4-component float
     /// vector expression.
     ///
@@ -2746,6 +3520,29 @@ impl WriterExpr {
         self.unary_op(UnaryOperator::Pack4x8unorm)
     }
 
+    /// Apply the "simplex noise" operator to the current float vector
+    /// expression.
+    ///
+    /// This is a unary operator, which applies to `vec2<f32>` or `vec3<f32>`
+    /// operand expressions to produce a float scalar in \[-1:1\].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec3;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec3<f32>(1., 1., 1.);`.
+    /// let x = w.lit(Vec3::ONE);
+    ///
+    /// // Simplex noise: `y = simplex_noise_vec3(x);`
+    /// let y = x.simplex_noise();
+    /// ```
+    #[inline]
+    pub fn simplex_noise(self) -> Self {
+        self.unary_op(UnaryOperator::SimplexNoise)
+    }
+
     /// Apply the "sign" operator to the current float scalar or vector
     /// expression.
     ///
@@ -2890,6 +3687,53 @@ impl WriterExpr {
         self.unary_op(UnaryOperator::Unpack4x8unorm)
     }
 
+    /// Apply the "value noise" operator to the current float vector
+    /// expression.
+    ///
+    /// This is a unary operator, which applies to `vec2<f32>` or `vec3<f32>`
+    /// operand expressions to produce a float scalar in \[-1:1\].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec3;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec3<f32>(1., 1., 1.);`.
+    /// let x = w.lit(Vec3::ONE);
+    ///
+    /// // Value noise: `y = value_noise_vec3(x);`
+    /// let y = x.value_noise();
+    /// ```
+    #[inline]
+    pub fn value_noise(self) -> Self {
+        self.unary_op(UnaryOperator::ValueNoise)
+    }
+
+    /// Apply the "round" operator to the current float scalar or vector
+    /// expression.
+    ///
+    /// This is a unary operator, which applies to float scalar or vector
+    /// operand expressions to produce a float scalar or vector. It applies
+    /// component-wise to vector operand expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec3;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec3<f32>(1., 1., 1.);`.
+    /// let x = w.lit(Vec3::ONE);
+    ///
+    /// // Round: `y = round(x);`
+    /// let y = x.round();
+    /// ```
+    #[inline]
+    pub fn round(self) -> Self {
+        self.unary_op(UnaryOperator::Round)
+    }
+
     /// Apply the "saturate" operator to the current float scalar or vector
     /// expression.
     ///
@@ -3035,6 +3879,57 @@ impl WriterExpr {
         self.binary_op(other, BinaryOperator::Add)
     }
 
+    /// Apply the logical operator "and" to this expression and another
+    /// expression.
+    ///
+    /// This is a binary operator, which only applies to boolean scalar operand
+    /// expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = true;`.
+    /// let x = w.lit(true);
+    ///
+    /// // Another literal expression `y = false;`.
+    /// let y = w.lit(false);
+    ///
+    /// // The logical "and" of both values `z = x && y;`.
+    /// let z = x.and(y); // == false
+    /// ```
+    #[inline]
+    pub fn and(self, other: Self) -> Self {
+        self.binary_op(other, BinaryOperator::And)
+    }
+
+    /// Calculate the two-argument arctangent of the current expression and
+    /// another expression.
+    ///
+    /// This is a binary operator, which applies component-wise to vector
+    /// operand expressions. The current expression is the `y` coordinate, and
+    /// the other expression the `x` coordinate. The result is in radians.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `y = 1.;`.
+    /// let y = w.lit(1.);
+    ///
+    /// // Another literal expression `x = -1.;`.
+    /// let x = w.lit(-1.);
+    ///
+    /// // The angle of the point (x, y) `a = atan2(y, x);`.
+    /// let a = y.atan2(x); // == 3π/4
+    /// ```
+    #[inline]
+    pub fn atan2(self, x: Self) -> Self {
+        self.binary_op(x, BinaryOperator::Atan2)
+    }
+
     /// Calculate the cross product of the current expression by another
     /// expression.
     ///
@@ -3143,6 +4038,32 @@ impl WriterExpr {
         self.binary_op(other, BinaryOperator::Div)
     }
 
+    /// Apply the logical operator "equal" to this expression and another
+    /// expression.
+    ///
+    /// This is a binary operator, which applies component-wise to vector
+    /// operand expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec3;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec3<f32>(3., -2., 7.);`.
+    /// let x = w.lit(Vec3::new(3., -2., 7.));
+    ///
+    /// // Another literal expression `y = vec3<f32>(1., 5., 7.);`.
+    /// let y = w.lit(Vec3::new(1., 5., 7.));
+    ///
+    /// // The boolean result of the equal operation `z = (x == y);`.
+    /// let z = x.eq(y); // == vec3<bool>(false, false, true)
+    /// ```
+    #[inline]
+    pub fn eq(self, other: Self) -> Self {
+        self.binary_op(other, BinaryOperator::Equal)
+    }
+
     /// Apply the logical operator "greater than or equal" to this expression
     /// and another expression.
     ///
@@ -3328,6 +4249,81 @@ impl WriterExpr {
         self.binary_op(other, BinaryOperator::Mul)
     }
 
+    /// Apply the logical operator "or" to this expression and another
+    /// expression.
+    ///
+    /// This is a binary operator, which only applies to boolean scalar operand
+    /// expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = true;`.
+    /// let x = w.lit(true);
+    ///
+    /// // Another literal expression `y = false;`.
+    /// let y = w.lit(false);
+    ///
+    /// // The logical "or" of both values `z = x || y;`.
+    /// let z = x.or(y); // == true
+    /// ```
+    #[inline]
+    pub fn or(self, other: Self) -> Self {
+        self.binary_op(other, BinaryOperator::Or)
+    }
+
+    /// Raise the current expression to the power of another expression.
+    ///
+    /// This is a binary operator, which applies component-wise to vector
+    /// operand expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec2;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec2<f32>(3., 2.);`.
+    /// let x = w.lit(Vec2::new(3., 2.));
+    ///
+    /// // Another literal expression `y = vec2<f32>(2., 3.);`.
+    /// let y = w.lit(Vec2::new(2., 3.));
+    ///
+    /// // The component-wise power `z = pow(x, y);`.
+    /// let z = x.pow(y); // == vec2<f32>(9., 8.)
+    /// ```
+    #[inline]
+    pub fn pow(self, other: Self) -> Self {
+        self.binary_op(other, BinaryOperator::Pow)
+    }
+
+    /// Reflect the current expression against a surface with the given normal.
+    ///
+    /// This is a binary operator. The current expression is the incident
+    /// vector, and `normal` is assumed to be normalized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec3;
+    /// # let mut w = ExprWriter::new();
+    /// // An incident vector `i = vec3<f32>(1., -1., 0.);`.
+    /// let i = w.lit(Vec3::new(1., -1., 0.));
+    ///
+    /// // The normal of a horizontal surface `n = vec3<f32>(0., 1., 0.);`.
+    /// let n = w.lit(Vec3::Y);
+    ///
+    /// // The reflected vector `r = reflect(i, n);`.
+    /// let r = i.reflect(n); // == vec3<f32>(1., 1., 0.)
+    /// ```
+    #[inline]
+    pub fn reflect(self, normal: Self) -> Self {
+        self.binary_op(normal, BinaryOperator::Reflect)
+    }
+
     /// Calculate the remainder of the division of the current expression by
     /// another expression.
     ///
@@ -3487,6 +4483,32 @@ impl WriterExpr {
         }
     }
 
+    /// Clamp the current expression between two other expressions.
+    ///
+    /// This is a ternary operator, which applies component-wise to vector
+    /// operand expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec2;
+    /// # let mut w = ExprWriter::new();
+    /// // A literal expression `x = vec2<f32>(3., -2.);`.
+    /// let x = w.lit(Vec2::new(3., -2.));
+    ///
+    /// // Literal expressions for the range bounds.
+    /// let low = w.lit(Vec2::ZERO);
+    /// let high = w.lit(Vec2::ONE);
+    ///
+    /// // The clamped value `z = clamp(x, low, high);`.
+    /// let z = x.clamp(low, high); // == vec2<f32>(1., 0.)
+    /// ```
+    #[inline]
+    pub fn clamp(self, low: Self, high: Self) -> Self {
+        self.ternary_op(low, high, TernaryOperator::Clamp)
+    }
+
     /// Blending linearly ("mix") two expressions with the fraction provided by
     /// a third expression.
     ///
@@ -3516,6 +4538,35 @@ impl WriterExpr {
         self.ternary_op(other, fraction, TernaryOperator::Mix)
     }
 
+    /// Refract the current expression through a surface with the given normal.
+    ///
+    /// This is a ternary operator. The current expression is the incident
+    /// vector, and `eta` is the ratio of the indices of refraction. The
+    /// incident vector and `normal` are assumed to be normalized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// # use bevy::math::Vec3;
+    /// # let mut w = ExprWriter::new();
+    /// // An incident vector `i = vec3<f32>(0., -1., 0.);`.
+    /// let i = w.lit(Vec3::NEG_Y);
+    ///
+    /// // The normal of a horizontal surface `n = vec3<f32>(0., 1., 0.);`.
+    /// let n = w.lit(Vec3::Y);
+    ///
+    /// // The ratio of the indices of refraction from air to water.
+    /// let eta = w.lit(1. / 1.33);
+    ///
+    /// // The refracted vector `r = refract(i, n, eta);`.
+    /// let r = i.refract(n, eta);
+    /// ```
+    #[inline]
+    pub fn refract(self, normal: Self, eta: Self) -> Self {
+        self.ternary_op(normal, eta, TernaryOperator::Refract)
+    }
+
     /// Calculate the smooth Hermite interpolation in \[0:1\] of the current
     /// value taken between the given bounds.
     ///
@@ -3715,6 +4766,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn curve_sample() {
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut m = Module::default();
+
+        // Single key: constant value
+        let curve = m.add_curve(Gradient::constant(Vec3::X));
+        assert_eq!(
+            m.get_curve(curve).unwrap().value_type(),
+            VectorType::VEC3F.into()
+        );
+        let t = m.lit(0.5);
+        let x = m.sample_curve(curve, t);
+        assert!(m.is_const(x));
+        let mut ctx =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert_eq!(ctx.eval(&m, x).unwrap(), "(vec3<f32>(1.,0.,0.))");
+        assert!(ctx.main_code.is_empty());
+
+        // Keys with the same ratio produce a step
+        let curve = m.add_curve(Gradient::from_keys([(0.5, Vec2::ZERO), (0.5, Vec2::ONE)]));
+        let t = m.attr(Attribute::AGE);
+        let x = m.sample_curve(curve, t);
+        assert!(!m.is_const(x));
+        assert_eq!(m.used_attributes(x), vec![Attribute::AGE]);
+        let mut ctx =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert_eq!(
+            ctx.eval(&m, x).unwrap(),
+            "(vec2<f32>(0.,0.) + vec2<f32>(1.,1.) * step(0.5, var0))"
+        );
+        assert_eq!(ctx.main_code, "let var0 = particle.age;\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn curve_empty() {
+        let mut m = Module::default();
+        m.add_curve(Gradient::<f32>::new());
+    }
+
     #[test]
     fn make_fn() {
         let property_layout = PropertyLayout::default();
@@ -3812,6 +4905,7 @@ mod tests {
         let le = m.le(x, y);
         let gt = m.gt(x, y);
         let ge = m.ge(x, y);
+        let eq = m.equal(x, y);
 
         let property_layout = PropertyLayout::default();
         let particle_layout = ParticleLayout::default();
@@ -3828,6 +4922,7 @@ mod tests {
             (le, "<="),
             (gt, ">"),
             (ge, ">="),
+            (eq, "=="),
         ] {
             let expr = ctx.eval(&m, expr);
             assert!(expr.is_ok());
@@ -3884,6 +4979,50 @@ mod tests {
             assert_eq!(expr, "is_alive");
         }
 
+        // Particle counts
+        for (op, name) in [
+            (BuiltInOperator::ParticleCount, "particle_count"),
+            (BuiltInOperator::AliveCount, "alive_count"),
+        ] {
+            let value = m.builtin(op);
+            assert_eq!(op.value_type(), ValueType::Scalar(ScalarType::Uint));
+
+            let property_layout = PropertyLayout::default();
+            let particle_layout = ParticleLayout::default();
+            let mut ctx =
+                ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+
+            let expr = ctx.eval(&m, value);
+            assert!(expr.is_ok());
+            let expr = expr.unwrap();
+            assert_eq!(expr, name);
+        }
+
+        // Effect origin and camera position
+        for (op, code) in [
+            (
+                BuiltInOperator::EffectOrigin,
+                "vec3<f32>(spawner.transform[0].w, spawner.transform[1].w, spawner.transform[2].w)",
+            ),
+            (
+                BuiltInOperator::CameraPosition,
+                "view.world_from_view[3].xyz",
+            ),
+        ] {
+            let value = m.builtin(op);
+            assert_eq!(op.value_type(), ValueType::Vector(VectorType::VEC3F));
+
+            let property_layout = PropertyLayout::default();
+            let particle_layout = ParticleLayout::default();
+            let mut ctx =
+                ShaderWriter::new(ModifierContext::Render, &property_layout, &particle_layout);
+
+            let expr = ctx.eval(&m, value);
+            assert!(expr.is_ok());
+            let expr = expr.unwrap();
+            assert_eq!(expr, code);
+        }
+
         // BuiltInOperator::Rand (which has side effect)
         for (scalar_type, prefix) in [
             (ScalarType::Bool, "b"),
@@ -3945,26 +5084,32 @@ mod tests {
         let abs = m.abs(x);
         let all = m.all(z);
         let any = m.any(z);
+        let atan = m.atan(y);
         let ceil = m.ceil(y);
         let cos = m.cos(y);
         let exp = m.exp(y);
         let exp2 = m.exp2(y);
         let floor = m.floor(y);
         let fract = m.fract(y);
+        let hash = m.hash(uu);
         let inv_sqrt = m.inverse_sqrt(y);
         let length = m.length(y);
         let log = m.log(y);
         let log2 = m.log2(y);
         let norm = m.normalize(y);
+        let not = m.not(z);
         let pack4x8snorm = m.pack4x8snorm(v);
         let pack4x8unorm = m.pack4x8unorm(v);
+        let round = m.round(y);
         let saturate = m.saturate(y);
+        let simplex_noise = m.simplex_noise(y);
         let sign = m.sign(y);
         let sin = m.sin(y);
         let sqrt = m.sqrt(y);
         let tan = m.tan(y);
         let unpack4x8snorm = m.unpack4x8snorm(us);
         let unpack4x8unorm = m.unpack4x8unorm(uu);
+        let value_noise = m.value_noise(y);
         let comp_x = m.x(w);
         let comp_y = m.y(w);
         let comp_z = m.z(w);
@@ -3983,26 +5128,36 @@ mod tests {
             ),
             (all, "all", "vec3<bool>(false,true,false)"),
             (any, "any", "vec3<bool>(false,true,false)"),
+            (atan, "atan", "vec3<f32>(1.,-3.1,6.99)"),
             (ceil, "ceil", "vec3<f32>(1.,-3.1,6.99)"),
             (cos, "cos", "vec3<f32>(1.,-3.1,6.99)"),
             (exp, "exp", "vec3<f32>(1.,-3.1,6.99)"),
             (exp2, "exp2", "vec3<f32>(1.,-3.1,6.99)"),
             (floor, "floor", "vec3<f32>(1.,-3.1,6.99)"),
             (fract, "fract", "vec3<f32>(1.,-3.1,6.99)"),
+            (hash, "hash_float01", "0u"),
             (inv_sqrt, "inverseSqrt", "vec3<f32>(1.,-3.1,6.99)"),
             (length, "length", "vec3<f32>(1.,-3.1,6.99)"),
             (log, "log", "vec3<f32>(1.,-3.1,6.99)"),
             (log2, "log2", "vec3<f32>(1.,-3.1,6.99)"),
             (norm, "normalize", "vec3<f32>(1.,-3.1,6.99)"),
+            (not, "!", "vec3<bool>(false,true,false)"),
             (pack4x8snorm, "pack4x8snorm", "vec4<f32>(-1.,1.,0.,7.2)"),
             (pack4x8unorm, "pack4x8unorm", "vec4<f32>(-1.,1.,0.,7.2)"),
+            (round, "round", "vec3<f32>(1.,-3.1,6.99)"),
             (saturate, "saturate", "vec3<f32>(1.,-3.1,6.99)"),
+            (
+                simplex_noise,
+                "simplex_noise_vec3",
+                "vec3<f32>(1.,-3.1,6.99)",
+            ),
             (sign, "sign", "vec3<f32>(1.,-3.1,6.99)"),
             (sin, "sin", "vec3<f32>(1.,-3.1,6.99)"),
             (sqrt, "sqrt", "vec3<f32>(1.,-3.1,6.99)"),
             (tan, "tan", "vec3<f32>(1.,-3.1,6.99)"),
             (unpack4x8snorm, "unpack4x8snorm", "0u"),
             (unpack4x8unorm, "unpack4x8unorm", "0u"),
+            (value_noise, "value_noise_vec3", "vec3<f32>(1.,-3.1,6.99)"),
         ] {
             let expr = ctx.eval(&m, expr);
             assert!(expr.is_ok());
@@ -4030,11 +5185,13 @@ mod tests {
         let x = m.attr(Attribute::POSITION);
         let y = m.lit(Vec3::ONE);
 
+        let atan2 = m.atan2(x, y);
         let cross = m.cross(x, y);
         let dist = m.distance(x, y);
         let dot = m.dot(x, y);
         let min = m.min(x, y);
         let max = m.max(x, y);
+        let pow = m.pow(x, y);
         let step = m.step(x, y);
 
         let property_layout = PropertyLayout::default();
@@ -4043,11 +5200,13 @@ mod tests {
             ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
 
         for (expr, op) in [
+            (atan2, "atan2"),
             (cross, "cross"),
             (dist, "distance"),
             (dot, "dot"),
             (min, "min"),
             (max, "max"),
+            (pow, "pow"),
             (step, "step"),
         ] {
             let expr = ctx.eval(&m, expr);
@@ -4072,7 +5231,9 @@ mod tests {
         let y = m.lit(Vec3::ONE);
         let t = m.lit(0.3);
 
+        let clamp = m.clamp(x, y, x);
         let mix = m.mix(x, y, t);
+        let select = m.select(x, y, t);
         let smoothstep = m.smoothstep(x, y, x);
 
         let property_layout = PropertyLayout::default();
@@ -4080,7 +5241,12 @@ mod tests {
         let mut ctx =
             ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
 
-        for (expr, op, third) in [(mix, "mix", t), (smoothstep, "smoothstep", x)] {
+        for (expr, op, third) in [
+            (clamp, "clamp", x),
+            (mix, "mix", t),
+            (select, "select", t),
+            (smoothstep, "smoothstep", x),
+        ] {
             let expr = ctx.eval(&m, expr);
             assert!(expr.is_ok());
             let expr = expr.unwrap();
@@ -4220,6 +5386,59 @@ mod tests {
         assert_eq!(c.is_valid(&m), None); // properties' value_type() is unknown
     }
 
+    #[test]
+    fn swizzle_expr() {
+        let mut m = Module::default();
+        let x = m.attr(Attribute::POSITION);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut ctx =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+
+        for mask in ["x", "zy", "zyx", "xxxx", "wzyx"] {
+            let s = SwizzleExpr::new(x, mask).unwrap();
+            assert_eq!(s.inner(), x);
+            assert_eq!(s.mask(), mask);
+
+            let s = m.swizzle(x, mask).unwrap();
+            let str = ctx.eval(&m, s).unwrap();
+            assert_eq!(str, format!("(particle.position).{}", mask));
+        }
+
+        for mask in ["", "xyzwx", "xq", "X", "rgb"] {
+            assert!(matches!(
+                SwizzleExpr::new(x, mask),
+                Err(ExprError::SyntaxError(_))
+            ));
+            assert!(m.swizzle(x, mask).is_err());
+        }
+    }
+
+    #[test]
+    fn used_attributes() {
+        let mut m = Module::default();
+
+        let pos = m.attr(Attribute::POSITION);
+        let vel = m.attr(Attribute::VELOCITY);
+        let one = m.lit(1.);
+        let a = m.add(pos, vel);
+        let b = m.length(a);
+        let c = m.clamp(b, one, b);
+        let d = m.swizzle(pos, "xy").unwrap();
+        let prop = m.add_property("my_prop", 3.0.into());
+        let prop = m.prop(prop);
+
+        assert!(m.used_attributes(one).is_empty());
+        assert!(m.used_attributes(prop).is_empty());
+        assert_eq!(m.used_attributes(pos), vec![Attribute::POSITION]);
+        assert_eq!(
+            m.used_attributes(c),
+            vec![Attribute::POSITION, Attribute::VELOCITY]
+        );
+        assert_eq!(m.used_attributes(d), vec![Attribute::POSITION]);
+    }
+
     #[test]
     fn side_effect() {
         let mut m = Module::default();
@@ -56,12 +56,23 @@ pub mod expr;
 pub mod node;
 
 pub use expr::{
-    AttributeExpr, BinaryOperator, BuiltInExpr, BuiltInOperator, EvalContext, Expr, ExprError,
-    ExprHandle, ExprWriter, LiteralExpr, Module, PropertyExpr, UnaryOperator, WriterExpr,
+    AttributeExpr, BinaryOperator, BuiltInExpr, BuiltInOperator, Curve, EvalContext, Expr,
+    ExprError, ExprHandle, ExprWriter, LiteralExpr, Module, PropertyExpr, UnaryOperator,
+    WriterExpr,
 };
 pub use node::{
-    AddNode, AttributeNode, DivNode, Graph, MulNode, Node, NormalizeNode, Slot, SlotDir, SlotId,
-    SubNode, TimeNode,
+    AddNode, AggregateNode, AggregateOp, AndNode, AsAny, Atan2Node, AtanNode, AttributeNode,
+    BoxedNode, CartesianToPolarNode, CeilNode, ClampNode, ColorRampNode, CombineVec3Node,
+    ConstantNode, CosNode, CountNode, CrossNode, CurveNode, DebugTapNode, DerivativeNode,
+    DistanceNode, DivNode, DotNode, EqualNode, Exp2Node, ExpNode, FloorNode, FractNode,
+    FresnelNode, GlobalNode, GlobalType, Graph, GraphError, GreaterThanNode, HashNode,
+    HsvToRgbNode, InverseSqrtNode, LengthNode, LerpNode, LessThanNode, Log2Node, LogNode, MaxNode,
+    MinNode, ModNode, MulNode, Node, NoiseNode, NoiseType, NormalizeNode, NotNode, OrNode,
+    OscillatorNode, PhaseNode, PingPongNode, PolarToCartesianNode, PowNode, PropertyNode,
+    QuantizeNode, RandomNode, RandomRangeNode, RealTimeNode, ReflectNode, RefractNode, RemapNode,
+    RotateVec3Node, RoundNode, SaturateNode, SelectNode, SinNode, Slot, SlotDir, SlotId,
+    SmoothMinNode, SmoothStepNode, SpherizeNode, SplitVec3Node, SqrtNode, StepNode, SubNode,
+    SwizzleNode, TanNode, TimeNode, TriplanarNoiseNode,
 };
 
 /// Variant storage for a scalar value.
@@ -22,12 +22,33 @@
 //! [`EffectAsset`]: crate::EffectAsset
 //! [`ParticleEffect`]: crate::ParticleEffect
 
-use std::num::NonZeroU32;
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+    num::NonZeroU32,
+};
 
-use crate::{Attribute, BuiltInOperator, ExprError, ExprHandle, Module, ValueType};
+use bevy::math::{Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    graph::{
+        expr::{PropertyHandle, SwizzleExpr},
+        ScalarValue,
+    },
+    Attribute, BinaryOperator, BuiltInOperator, Curve, Expr, ExprError, ExprHandle, Module,
+    ScalarType, SetAttributeModifier, Value, ValueType, VectorType,
+};
 
 /// Identifier of a node in a graph.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// The identifier serializes as its plain one-based integer value, so that
+/// serialized graphs remain readable and can easily be referenced by external
+/// tools. Deserializing a zero value fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct NodeId(NonZeroU32);
 
 impl NodeId {
@@ -48,7 +69,12 @@ impl NodeId {
 }
 
 /// Identifier of a slot in a graph.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// The identifier serializes as its plain one-based integer value, so that
+/// serialized graphs remain readable and can easily be referenced by external
+/// tools. Deserializing a zero value fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct SlotId(NonZeroU32);
 
 impl SlotId {
@@ -69,7 +95,7 @@ impl SlotId {
 }
 
 /// Node slot direction.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SlotDir {
     /// Input slot receiving data from outside the node.
     Input,
@@ -78,7 +104,7 @@ pub enum SlotDir {
 }
 
 /// Definition of a slot of a node.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SlotDef {
     /// Slot name.
     name: String,
@@ -87,6 +113,11 @@ pub struct SlotDef {
     /// Type of values accepted by the slot. This may be `None` for variant
     /// slots, if the type depends on the inputs of the node during evaluation.
     value_type: Option<ValueType>,
+    /// Allow multiple output slots to link to this input slot.
+    allow_multi_input: bool,
+    /// Default value of an input slot, used when the slot is not linked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_value: Option<Value>,
 }
 
 impl SlotDef {
@@ -96,6 +127,35 @@ impl SlotDef {
             name: name.into(),
             dir: SlotDir::Input,
             value_type,
+            allow_multi_input: false,
+            default_value: None,
+        }
+    }
+
+    /// Create a new input slot with a default value.
+    ///
+    /// When the slot is not linked to any output slot, the default value is
+    /// used as a literal expression during [`Graph::evaluate()`], and the slot
+    /// is not reported as unlinked by [`Graph::validate()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value_type` is not `None` and doesn't match the type of the
+    /// default value.
+    pub fn input_with_default(
+        name: impl Into<String>,
+        value_type: Option<ValueType>,
+        value: Value,
+    ) -> Self {
+        if let Some(value_type) = value_type {
+            assert_eq!(value_type, value.value_type());
+        }
+        Self {
+            name: name.into(),
+            dir: SlotDir::Input,
+            value_type,
+            allow_multi_input: false,
+            default_value: Some(value),
         }
     }
 
@@ -105,9 +165,27 @@ impl SlotDef {
             name: name.into(),
             dir: SlotDir::Output,
             value_type,
+            allow_multi_input: false,
+            default_value: None,
         }
     }
 
+    /// Allow multiple output slots to link to this input slot.
+    ///
+    /// By default an input slot accepts a single link, and linking another
+    /// output slot replaces the existing link. With this enabled, new links
+    /// are appended instead, which allows variadic nodes to receive any number
+    /// of values through a single input slot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this slot is an output slot.
+    pub fn with_multi_input(mut self) -> Self {
+        assert!(self.is_input());
+        self.allow_multi_input = true;
+        self
+    }
+
     /// Get the slot name.
     #[inline]
     pub fn name(&self) -> &str {
@@ -137,10 +215,22 @@ impl SlotDef {
     pub fn value_type(&self) -> Option<ValueType> {
         self.value_type
     }
+
+    /// Does this input slot accept links from multiple output slots?
+    #[inline]
+    pub fn allow_multi_input(&self) -> bool {
+        self.allow_multi_input
+    }
+
+    /// Get the default value of this input slot, if any.
+    #[inline]
+    pub fn default_value(&self) -> Option<Value> {
+        self.default_value
+    }
 }
 
 /// Single slot of a node.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Slot {
     /// Owner node identifier.
     node_id: NodeId,
@@ -219,21 +309,137 @@ impl Slot {
         }
     }
 
-    fn link_input(&mut self, output: SlotId) {
+    /// Link this input slot to an output slot.
+    ///
+    /// If the slot accepts multiple inputs, the link is appended to the
+    /// existing ones. Otherwise it replaces any existing link, which is
+    /// returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this slot's direction is `SlotDir::Output`.
+    fn link_input(&mut self, output: SlotId) -> Option<SlotId> {
         assert!(self.is_input());
-        if self.linked_slots.is_empty() {
+        if self.def.allow_multi_input() {
+            if !self.linked_slots.contains(&output) {
+                self.linked_slots.push(output);
+            }
+            None
+        } else if self.linked_slots.is_empty() {
             self.linked_slots.push(output);
+            None
         } else {
-            self.linked_slots[0] = output;
+            Some(std::mem::replace(&mut self.linked_slots[0], output))
         }
     }
 
-    fn unlink_input(&mut self) {
+    fn unlink_input(&mut self, output: SlotId) {
         assert!(self.is_input());
-        self.linked_slots.clear();
+        self.linked_slots.retain(|&s| s != output);
     }
 }
 
+/// Errors raised when editing, validating, or evaluating a [`Graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum GraphError {
+    /// A link from an output slot to an input slot introduces a cycle in the
+    /// graph.
+    #[error("The link from output slot #{} to input slot #{} introduces a cycle.", .output.id(), .input.id())]
+    Cycle {
+        /// The output slot of the rejected link.
+        output: SlotId,
+        /// The input slot of the rejected link.
+        input: SlotId,
+    },
+
+    /// A link doesn't connect an output slot to an input slot.
+    #[error("Invalid link from slot #{} to slot #{}, expected an output slot linked to an input slot.", .output.id(), .input.id())]
+    InvalidLink {
+        /// The upstream slot of the link, expected to be an output slot.
+        output: SlotId,
+        /// The downstream slot of the link, expected to be an input slot.
+        input: SlotId,
+    },
+
+    /// An input slot which requires a link is not linked to any output slot.
+    #[error("Input slot #{} is not linked.", .input.id())]
+    UnlinkedInput {
+        /// The unlinked input slot.
+        input: SlotId,
+    },
+
+    /// A link connects two slots with different value types.
+    #[error("Output slot #{} of type {output_type} is linked to input slot #{} of type {input_type}.", .output.id(), .input.id())]
+    TypeMismatch {
+        /// The output slot of the link.
+        output: SlotId,
+        /// The input slot of the link.
+        input: SlotId,
+        /// The value type of the output slot.
+        output_type: ValueType,
+        /// The value type of the input slot.
+        input_type: ValueType,
+    },
+
+    /// A node is evaluated with a number of input expressions different from
+    /// the number of its input slots.
+    #[error("Expected {expected} input(s), got {got}{}.", .node.map_or(String::new(), |node| format!(" for node #{}", node.id())))]
+    WrongInputCount {
+        /// The evaluated node. Nodes don't know their own identifier, so this
+        /// is `None` when returned by [`Node::eval()`], and set by the
+        /// [`Graph`] evaluating the node.
+        node: Option<NodeId>,
+        /// The number of input slots of the node.
+        expected: usize,
+        /// The number of input expressions.
+        got: usize,
+    },
+
+    /// A node produced a number of output expressions different from the
+    /// number of its output slots.
+    #[error("Node #{} expected {expected} output(s), got {got}.", .node.id())]
+    WrongOutputCount {
+        /// The evaluated node.
+        node: NodeId,
+        /// The number of output slots of the node.
+        expected: usize,
+        /// The number of output expressions produced by the node.
+        got: usize,
+    },
+
+    /// The value types of two inputs of a node are not compatible with the
+    /// operation performed by the node.
+    #[error("Incompatible input types {lhs} and {rhs}{}.", .node.map_or(String::new(), |node| format!(" for node #{}", node.id())))]
+    IncompatibleTypes {
+        /// The node with the incompatible inputs. Nodes don't know their own
+        /// identifier, so this is `None` when returned by
+        /// [`Node::infer_output_types()`], and set by [`Graph::validate()`].
+        node: Option<NodeId>,
+        /// The value type of the first conflicting input.
+        lhs: ValueType,
+        /// The value type of the second conflicting input.
+        rhs: ValueType,
+    },
+
+    /// A slot can't be renamed because another slot of the same node already
+    /// has the new name.
+    #[error("Slot #{} can't be renamed, slot #{} of the same node already has that name.", .slot.id(), .existing.id())]
+    DuplicateSlotName {
+        /// The slot to rename.
+        slot: SlotId,
+        /// The other slot of the same node already using the name.
+        existing: SlotId,
+    },
+
+    /// A disabled node can't be bypassed during evaluation, because it doesn't
+    /// have exactly one input and one output to forward the input to.
+    #[error("Node #{} is disabled but can't be bypassed, expected a single input and a single output.", .node.id())]
+    NoPassthrough {
+        /// The disabled node.
+        node: NodeId,
+    },
+}
+
 /// Effect graph.
 ///
 /// An effect graph represents an editable version of an [`EffectAsset`]. The
@@ -245,10 +451,22 @@ impl Slot {
 /// [`EffectAsset`]: crate::EffectAsset
 /// [`Modifier`]: crate::Modifier
 /// [`Expr`]: crate::graph::Expr
-#[derive(Default)]
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Graph {
-    nodes: Vec<Box<dyn Node>>,
-    slots: Vec<Slot>,
+    /// Nodes, indexed by [`NodeId::index()`]. Removed nodes are left as `None`
+    /// so that the identifiers of the other nodes remain valid.
+    nodes: Vec<Option<Box<dyn Node>>>,
+    /// Slots of all nodes, indexed by [`SlotId::index()`]. Slots of removed
+    /// nodes are left as `None` so that the other identifiers remain valid.
+    slots: Vec<Option<Slot>>,
+    /// Nodes disabled with [`Graph::set_node_enabled()`], bypassed during
+    /// evaluation.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "HashSet::is_empty")
+    )]
+    disabled_nodes: HashSet<NodeId>,
 }
 
 impl std::fmt::Debug for Graph {
@@ -295,29 +513,130 @@ impl Graph {
         for slot_def in node.slots() {
             let slot_id = SlotId::new(NonZeroU32::new(self.slots.len() as u32 + 1).unwrap());
             let slot = Slot::new(node_id, slot_id, slot_def.clone());
-            self.slots.push(slot);
+            self.slots.push(Some(slot));
         }
 
-        self.nodes.push(node);
+        self.nodes.push(Some(node));
 
         node_id
     }
 
+    /// Add a node for a binary operation, and link its inputs.
+    ///
+    /// This adds a new node computing the binary operation `op`, then links
+    /// the `lhs` and `rhs` output slots to its left and right input slots.
+    /// Returns the output slot of the new node, which can be used to chain
+    /// other operations.
+    ///
+    /// Each operator adds the node computing the same operation, like an
+    /// [`AddNode`] for [`BinaryOperator::Add`] or a [`ModNode`] for
+    /// [`BinaryOperator::Remainder`]. Operators without such a node, like the
+    /// logical operators or the random number generators, are not supported.
+    /// For those, this returns `None` and leaves the graph unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lhs` or `rhs` doesn't reference an output slot of an
+    /// existing node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let a = graph.add_node(ConstantNode::scalar(2.));
+    /// let b = graph.add_node(ConstantNode::scalar(3.));
+    /// let c = graph.add_node(ConstantNode::scalar(4.));
+    ///
+    /// // a * b + c
+    /// let a_b = graph
+    ///     .add_binary(
+    ///         BinaryOperator::Mul,
+    ///         graph.output_slots(a)[0],
+    ///         graph.output_slots(b)[0],
+    ///     )
+    ///     .unwrap();
+    /// let a_b_c = graph
+    ///     .add_binary(BinaryOperator::Add, a_b, graph.output_slots(c)[0])
+    ///     .unwrap();
+    ///
+    /// // Not supported
+    /// assert!(graph
+    ///     .add_binary(BinaryOperator::And, a_b, a_b_c)
+    ///     .is_none());
+    /// ```
+    pub fn add_binary(&mut self, op: BinaryOperator, lhs: SlotId, rhs: SlotId) -> Option<SlotId> {
+        let node: Box<dyn Node> = match op {
+            BinaryOperator::Add => Box::new(AddNode::default()),
+            BinaryOperator::Atan2 => Box::new(Atan2Node::default()),
+            BinaryOperator::Cross => Box::new(CrossNode::default()),
+            BinaryOperator::Distance => Box::new(DistanceNode::default()),
+            BinaryOperator::Div => Box::new(DivNode::default()),
+            BinaryOperator::Dot => Box::new(DotNode::default()),
+            BinaryOperator::Equal => Box::new(EqualNode::default()),
+            BinaryOperator::GreaterThan => Box::new(GreaterThanNode::default()),
+            BinaryOperator::LessThan => Box::new(LessThanNode::default()),
+            BinaryOperator::Max => Box::new(MaxNode::default()),
+            BinaryOperator::Min => Box::new(MinNode::default()),
+            BinaryOperator::Mul => Box::new(MulNode::default()),
+            BinaryOperator::Pow => Box::new(PowNode::default()),
+            BinaryOperator::Remainder => Box::new(ModNode::default()),
+            BinaryOperator::Step => Box::new(StepNode::default()),
+            BinaryOperator::Sub => Box::new(SubNode::default()),
+            _ => return None,
+        };
+        let node_id = self.add_node_impl(node);
+        let inputs = self.defined_slots(node_id, SlotDir::Input);
+        // The node was just added, so can't be upstream of any other node.
+        self.link_unchecked(lhs, inputs[0]);
+        self.link_unchecked(rhs, inputs[1]);
+        Some(self.defined_slots(node_id, SlotDir::Output)[0])
+    }
+
     /// Link an output slot of a node to an input slot of another node.
     ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::Cycle`] if the link would introduce a cycle in the
+    /// graph, that is if the node owning the `output` slot is already
+    /// downstream of the node owning the `input` slot. In that case the graph
+    /// is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `output` argument doesn't reference an output slot of an
+    /// existing node, or the `input` argument doesn't reference an input slot
+    /// of an existing node.
+    pub fn link(&mut self, output: SlotId, input: SlotId) -> Result<(), GraphError> {
+        let out_node = self.get_slot(output).node_id();
+        let in_node = self.get_slot(input).node_id();
+        if self.is_downstream_of(out_node, in_node) {
+            return Err(GraphError::Cycle { output, input });
+        }
+        self.link_unchecked(output, input);
+        Ok(())
+    }
+
+    /// Link an output slot of a node to an input slot of another node, without
+    /// checking for cycles.
+    ///
     /// # Panics
     ///
     /// Panics if the `output` argument doesn't reference an output slot of an
     /// existing node, or the `input` argument doesn't reference an input slot
     /// of an existing node.
-    pub fn link(&mut self, output: SlotId, input: SlotId) {
+    pub(crate) fn link_unchecked(&mut self, output: SlotId, input: SlotId) {
         let out_slot = self.get_slot_mut(output);
         assert!(out_slot.is_output());
         out_slot.link_to(input);
 
         let in_slot = self.get_slot_mut(input);
         assert!(in_slot.is_input());
-        in_slot.link_input(output);
+        if let Some(replaced) = in_slot.link_input(output) {
+            if replaced != output {
+                self.get_slot_mut(replaced).unlink_from(input);
+            }
+        }
     }
 
     /// Unlink an output slot of a node from an input slot of another node.
@@ -333,7 +652,7 @@ impl Graph {
         if out_slot.unlink_from(input) {
             let in_slot = self.get_slot_mut(input);
             assert!(in_slot.is_input());
-            in_slot.unlink_input();
+            in_slot.unlink_input(output);
         }
     }
 
@@ -344,17 +663,428 @@ impl Graph {
         for remote_id in &linked_slots {
             let remote_slot = self.get_slot_mut(*remote_id);
             if remote_slot.is_input() {
-                remote_slot.unlink_input();
+                remote_slot.unlink_input(slot_id);
             } else {
                 remote_slot.unlink_from(slot_id);
             }
         }
     }
 
+    /// Unlink all the slots of a node.
+    ///
+    /// This calls [`unlink_all()`] on all the input and output slots of the
+    /// node, which also updates the slots of the other nodes the node was
+    /// linked to. The node itself and its slots remain in the graph, and can be
+    /// linked again later. This allows isolating a node temporarily without
+    /// removing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node_id` doesn't reference an existing node.
+    ///
+    /// [`unlink_all()`]: crate::graph::Graph::unlink_all
+    pub fn disconnect_node(&mut self, node_id: NodeId) {
+        let index = node_id.index();
+        assert!(index < self.nodes.len() && self.nodes[index].is_some());
+
+        for slot_id in self.slots(node_id) {
+            self.unlink_all(slot_id);
+        }
+    }
+
+    /// Remove a node from the graph.
+    ///
+    /// All the slots of the node are first unlinked from any other slot with
+    /// [`unlink_all()`], then the node and its slots are removed.
+    ///
+    /// Node and slot identifiers are never reused. Instead, the removed node
+    /// and its slots leave an empty entry (a _tombstone_) in the graph, so that
+    /// all existing [`NodeId`] and [`SlotId`] values of the other nodes and
+    /// slots remain valid. The identifiers of the removed node and its slots
+    /// must not be used anymore.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node_id` doesn't reference an existing node.
+    ///
+    /// [`unlink_all()`]: crate::graph::Graph::unlink_all
+    pub fn remove_node(&mut self, node_id: NodeId) {
+        let index = node_id.index();
+        assert!(index < self.nodes.len() && self.nodes[index].is_some());
+
+        for slot_id in self.slots(node_id) {
+            self.unlink_all(slot_id);
+            self.slots[slot_id.index()] = None;
+        }
+
+        self.nodes[index] = None;
+        self.disabled_nodes.remove(&node_id);
+    }
+
+    /// Replace a node with another one, preserving the compatible links.
+    ///
+    /// The node keeps its identifier and its enabled state, but all its slots
+    /// are replaced by the slots of the new `node`, which receive new
+    /// identifiers. The links of an old slot are transferred to the new slot
+    /// with the same name and direction, if any, while the links of the other
+    /// old slots are removed. If a single-link input slot replaces a slot with
+    /// multiple links, only the first link is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::TypeMismatch`] if a transferred link would
+    /// connect two slots with different declared value types. In that case
+    /// the graph is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node_id` doesn't reference an existing node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let a = graph.add_node(ConstantNode::scalar(2.));
+    /// let b = graph.add_node(ConstantNode::scalar(3.));
+    /// let op = graph.add_node(AddNode::default());
+    /// for (node, name) in [(a, "lhs"), (b, "rhs")] {
+    ///     graph
+    ///         .link(
+    ///             graph.output_slots(node)[0],
+    ///             graph.input_slot(op, name).unwrap(),
+    ///         )
+    ///         .unwrap();
+    /// }
+    ///
+    /// // Multiply instead of adding, keeping the input links.
+    /// graph.replace_node(op, Box::new(MulNode::default())).unwrap();
+    /// assert!(graph.validate().is_ok());
+    /// ```
+    pub fn replace_node(&mut self, node_id: NodeId, node: Box<dyn Node>) -> Result<(), GraphError> {
+        let index = node_id.index();
+        assert!(index < self.nodes.len() && self.nodes[index].is_some());
+
+        // Find the links to transfer to each new slot, before changing anything
+        // so the graph is left unchanged on error.
+        let old_slots = self.slots(node_id);
+        let mut transferred = Vec::with_capacity(node.slots().len());
+        for def in node.slots() {
+            let Some(old_id) = old_slots.iter().copied().find(|&id| {
+                let slot = self.get_slot(id);
+                slot.dir() == def.dir() && slot.def().name() == def.name()
+            }) else {
+                transferred.push(vec![]);
+                continue;
+            };
+            let mut links = self.get_slot(old_id).linked_slots.clone();
+            if def.is_input() && !def.allow_multi_input() {
+                links.truncate(1);
+            }
+            if let Some(value_type) = def.value_type() {
+                for &remote_id in &links {
+                    let Some(remote_type) = self.get_slot(remote_id).def().value_type() else {
+                        continue;
+                    };
+                    if remote_type != value_type {
+                        return Err(if def.is_input() {
+                            GraphError::TypeMismatch {
+                                output: remote_id,
+                                input: old_id,
+                                output_type: remote_type,
+                                input_type: value_type,
+                            }
+                        } else {
+                            GraphError::TypeMismatch {
+                                output: old_id,
+                                input: remote_id,
+                                output_type: value_type,
+                                input_type: remote_type,
+                            }
+                        });
+                    }
+                }
+            }
+            transferred.push(links);
+        }
+
+        for slot_id in old_slots {
+            self.unlink_all(slot_id);
+            self.slots[slot_id.index()] = None;
+        }
+
+        // Allocate the new slots in definition order, like add_node() does.
+        for (slot_def, links) in node.slots().iter().zip(transferred) {
+            let slot_id = SlotId::new(NonZeroU32::new(self.slots.len() as u32 + 1).unwrap());
+            self.slots
+                .push(Some(Slot::new(node_id, slot_id, slot_def.clone())));
+            // The same nodes were linked before, so no cycle can be introduced.
+            for remote_id in links {
+                if slot_def.is_input() {
+                    self.link_unchecked(remote_id, slot_id);
+                } else {
+                    self.link_unchecked(slot_id, remote_id);
+                }
+            }
+        }
+        self.nodes[index] = Some(node);
+
+        Ok(())
+    }
+
+    /// Append all the nodes of another graph into this graph.
+    ///
+    /// All the nodes and slots of `other` are moved into this graph, and
+    /// receive new identifiers. The links between the nodes of `other` are
+    /// preserved. Removed nodes of `other` are not appended.
+    ///
+    /// Returns the mapping from the identifiers of the nodes in `other` to
+    /// their new identifiers in this graph, which allows linking the appended
+    /// nodes with the existing ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    ///
+    /// let mut other = Graph::new();
+    /// let sin = other.add_node(SinNode::default());
+    ///
+    /// let node_map = graph.append(other);
+    /// let sin = node_map[&sin];
+    /// graph
+    ///     .link(
+    ///         graph.output_slot(time, "time").unwrap(),
+    ///         graph.input_slot(sin, "in").unwrap(),
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn append(&mut self, other: Graph) -> HashMap<NodeId, NodeId> {
+        let mut node_map = HashMap::new();
+        for (index, node) in other.nodes.into_iter().enumerate() {
+            let Some(node) = node else {
+                continue;
+            };
+            let old_id = NodeId::new(NonZeroU32::new(index as u32 + 1).unwrap());
+            let new_id = NodeId::new(NonZeroU32::new(self.nodes.len() as u32 + 1).unwrap());
+            self.nodes.push(Some(node));
+            node_map.insert(old_id, new_id);
+        }
+        self.disabled_nodes
+            .extend(other.disabled_nodes.iter().map(|id| node_map[id]));
+
+        // Slots are appended in the same order, skipping removed ones, so their
+        // new identifiers are known before the links are remapped.
+        let mut slot_map = HashMap::new();
+        let mut next_index = self.slots.len() as u32;
+        for slot in other.slots.iter().flatten() {
+            next_index += 1;
+            slot_map.insert(slot.id(), SlotId::new(NonZeroU32::new(next_index).unwrap()));
+        }
+        for slot in other.slots.into_iter().flatten() {
+            self.slots.push(Some(Slot {
+                node_id: node_map[&slot.node_id],
+                id: slot_map[&slot.id],
+                def: slot.def,
+                linked_slots: slot.linked_slots.iter().map(|id| slot_map[id]).collect(),
+            }));
+        }
+
+        node_map
+    }
+
+    /// Enable or disable a node.
+    ///
+    /// A disabled node is bypassed during evaluation: instead of evaluating the
+    /// node, the expression of its single input is forwarded as is to the nodes
+    /// linked to its single output. This allows quickly comparing variations of
+    /// an effect without editing the links of the graph. Nodes are enabled by
+    /// default.
+    ///
+    /// Only nodes with exactly one input and one output can be bypassed.
+    /// Evaluating a graph with any other disabled node fails with
+    /// [`GraphError::NoPassthrough`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node_id` doesn't reference an existing node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let node_id = graph.add_node(NormalizeNode::default());
+    /// graph.set_node_enabled(node_id, false);
+    /// assert!(!graph.is_node_enabled(node_id));
+    /// ```
+    pub fn set_node_enabled(&mut self, node_id: NodeId, enabled: bool) {
+        let index = node_id.index();
+        assert!(index < self.nodes.len() && self.nodes[index].is_some());
+
+        if enabled {
+            self.disabled_nodes.remove(&node_id);
+        } else {
+            self.disabled_nodes.insert(node_id);
+        }
+    }
+
+    /// Check if a node is enabled.
+    ///
+    /// See [`set_node_enabled()`] for details.
+    ///
+    /// [`set_node_enabled()`]: Graph::set_node_enabled
+    pub fn is_node_enabled(&self, node_id: NodeId) -> bool {
+        !self.disabled_nodes.contains(&node_id)
+    }
+
+    /// Get the number of nodes in the graph.
+    ///
+    /// Removed nodes are not counted.
+    pub fn node_count(&self) -> usize {
+        self.nodes.iter().flatten().count()
+    }
+
+    /// Get the number of slots of all the nodes in the graph.
+    ///
+    /// The slots of removed nodes are not counted.
+    pub fn slot_count(&self) -> usize {
+        self.live_slots().count()
+    }
+
+    /// Iterate over the identifiers of all the nodes of the graph, in
+    /// increasing identifier order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    /// let add = graph.add_node(AddNode::default());
+    /// assert_eq!(graph.nodes().collect::<Vec<_>>(), vec![time, add]);
+    /// ```
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.is_some())
+            .map(|(index, _)| NodeId::new(NonZeroU32::new(index as u32 + 1).unwrap()))
+    }
+
+    /// Get all the links of the graph.
+    ///
+    /// Each link is returned as an `(output, input)` pair of the slots it
+    /// connects. The links are sorted by output slot identifier, then by link
+    /// order for links from the same output slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    /// let sin = graph.add_node(SinNode::default());
+    /// let output = graph.output_slot(time, "time").unwrap();
+    /// let input = graph.input_slot(sin, "in").unwrap();
+    /// graph.link(output, input).unwrap();
+    /// assert_eq!(graph.links(), vec![(output, input)]);
+    /// ```
+    pub fn links(&self) -> Vec<(SlotId, SlotId)> {
+        self.live_slots()
+            .filter(|s| s.is_output())
+            .flat_map(|s| s.linked_slots.iter().map(|&input| (s.id(), input)))
+            .collect()
+    }
+
+    /// Get a node of the graph.
+    ///
+    /// Returns `None` if `node_id` doesn't reference an existing node, for
+    /// example because it was removed. The concrete node type can be recovered
+    /// with `downcast_ref()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let node_id = graph.add_node(TimeNode::default());
+    /// let node = graph.get_node(node_id).unwrap();
+    /// assert!(node.downcast_ref::<TimeNode>().is_some());
+    /// ```
+    pub fn get_node(&self, node_id: NodeId) -> Option<&dyn Node> {
+        self.nodes.get(node_id.index())?.as_deref()
+    }
+
+    /// Get a node of the graph mutably.
+    ///
+    /// Returns `None` if `node_id` doesn't reference an existing node, for
+    /// example because it was removed. The concrete node type can be recovered
+    /// with `downcast_mut()`.
+    ///
+    /// The slots of a node are created when the node is added to the graph, so
+    /// modifying the node doesn't change the slots of the graph.
+    pub fn get_node_mut(&mut self, node_id: NodeId) -> Option<&mut dyn Node> {
+        // Note: can't use as_deref_mut() due to the 'static object lifetime.
+        match self.nodes.get_mut(node_id.index())? {
+            Some(node) => Some(node.as_mut()),
+            None => None,
+        }
+    }
+
+    /// Find all the nodes of a given concrete type.
+    ///
+    /// Returns the identifiers of all the nodes of type `N`, in increasing
+    /// identifier order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let pos = graph.add_node(AttributeNode::new(Attribute::POSITION));
+    /// graph.add_node(TimeNode::default());
+    /// assert_eq!(graph.find_nodes::<AttributeNode>(), vec![pos]);
+    /// ```
+    pub fn find_nodes<N: Node>(&self) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| {
+                let node = node.as_deref()?;
+                node.downcast_ref::<N>()?;
+                Some(NodeId::new(NonZeroU32::new(index as u32 + 1).unwrap()))
+            })
+            .collect()
+    }
+
+    /// Find all the nodes of a given concrete type, for mutation.
+    ///
+    /// Returns the identifiers of all the nodes of type `N` along with a
+    /// mutable reference to each node, in increasing identifier order. Like
+    /// with [`get_node_mut()`], modifying a node doesn't change its slots in
+    /// the graph.
+    ///
+    /// [`get_node_mut()`]: Graph::get_node_mut
+    pub fn find_nodes_mut<N: Node>(&mut self) -> Vec<(NodeId, &mut N)> {
+        self.nodes
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, node)| {
+                let node = node.as_mut()?.downcast_mut::<N>()?;
+                Some((
+                    NodeId::new(NonZeroU32::new(index as u32 + 1).unwrap()),
+                    node,
+                ))
+            })
+            .collect()
+    }
+
     /// Get all slots of a node.
     pub fn slots(&self, node_id: NodeId) -> Vec<SlotId> {
-        self.slots
-            .iter()
+        self.live_slots()
             .filter_map(|s| {
                 if s.node_id() == node_id {
                     Some(s.id())
@@ -372,24 +1102,17 @@ impl Graph {
         name: S,
     ) -> Option<SlotId> {
         let name = name.into();
-        self.slots
-            .iter()
+        self.live_slots()
             .find(|s| s.node_id() == node_id && s.is_input() && s.def().name() == name)
             .map(|s| s.id)
     }
 
     /// Get all input slots of a node.
+    ///
+    /// The slots are returned in the display order of the node, as defined by
+    /// [`Node::slot_order()`].
     pub fn input_slots(&self, node_id: NodeId) -> Vec<SlotId> {
-        self.slots
-            .iter()
-            .filter_map(|s| {
-                if s.node_id() == node_id && s.is_input() {
-                    Some(s.id())
-                } else {
-                    None
-                }
-            })
-            .collect()
+        self.ordered_slots(node_id, SlotDir::Input)
     }
 
     /// Get a given output slot of a node by name.
@@ -399,570 +1122,10849 @@ impl Graph {
         name: S,
     ) -> Option<SlotId> {
         let name = name.into();
-        self.slots
-            .iter()
+        self.live_slots()
             .find(|s| s.node_id() == node_id && s.is_output() && s.def().name() == name)
             .map(|s| s.id)
     }
 
     /// Get all output slots of a node.
+    ///
+    /// The slots are returned in the display order of the node, as defined by
+    /// [`Node::slot_order()`].
     pub fn output_slots(&self, node_id: NodeId) -> Vec<SlotId> {
-        self.slots
-            .iter()
-            .filter_map(|s| {
-                if s.node_id() == node_id && s.is_output() {
-                    Some(s.id())
-                } else {
-                    None
-                }
-            })
-            .collect()
+        self.ordered_slots(node_id, SlotDir::Output)
     }
 
     /// Find a slot ID by slot name.
+    ///
+    /// The search spans the slots of all nodes of the graph, in order of slot
+    /// creation, and returns the first slot found with the given name. Slot
+    /// names are only unique per node, so if several nodes have a slot with
+    /// that name, like two [`AddNode`] both having an "lhs" slot, the slot of
+    /// the node added first is returned. Prefer [`get_slot_id_on()`] to look
+    /// up a slot of a specific node.
+    ///
+    /// [`get_slot_id_on()`]: Graph::get_slot_id_on
     pub fn get_slot_id<'a, 'b: 'a, S: Into<&'b str>>(&'a self, name: S) -> Option<SlotId> {
         let name = name.into();
-        self.slots
-            .iter()
+        self.live_slots()
             .find(|&s| s.def().name() == name)
             .map(|s| s.id)
     }
 
-    #[allow(dead_code)] // TEMP
-    fn get_slot(&self, id: SlotId) -> &Slot {
-        let index = id.index();
-        assert!(index < self.slots.len());
-        &self.slots[index]
+    /// Find a slot ID of a given node by slot name.
+    ///
+    /// Unlike [`get_slot_id()`], the search is restricted to the slots of the
+    /// node `node_id`, either input or output. Returns `None` if the node
+    /// doesn't exist or has no slot with that name.
+    ///
+    /// [`get_slot_id()`]: Graph::get_slot_id
+    pub fn get_slot_id_on(&self, node_id: NodeId, name: &str) -> Option<SlotId> {
+        self.live_slots()
+            .find(|s| s.node_id() == node_id && s.def().name() == name)
+            .map(|s| s.id)
     }
 
-    fn get_slot_mut(&mut self, id: SlotId) -> &mut Slot {
-        let index = id.index();
-        assert!(index < self.slots.len());
-        &mut self.slots[index]
-    }
-}
+    /// Get the definition of a slot.
+    ///
+    /// Returns `None` if `id` doesn't reference an existing slot, for example
+    /// because its node was removed. The definition is the one of the slot in
+    /// the graph, including any change made with [`rename_slot()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let node_id = graph.add_node(TimeNode::default());
+    /// let slot_id = graph.output_slot(node_id, "time").unwrap();
+    /// let slot_def = graph.slot_def(slot_id).unwrap();
+    /// assert!(slot_def.is_output());
+    /// ```
+    ///
+    /// [`rename_slot()`]: Graph::rename_slot
+    pub fn slot_def(&self, id: SlotId) -> Option<&SlotDef> {
+        self.slots.get(id.index())?.as_ref().map(Slot::def)
+    }
 
-/// Generic graph node.
-pub trait Node {
-    /// Get the list of slots of this node.
+    /// Check if a slot is linked to any other slot.
     ///
-    /// The list contains both input and output slots, without any guaranteed
-    /// order.
-    fn slots(&self) -> &[SlotDef];
+    /// This is a convenience helper for
+    /// `!self.connections(slot_id).is_empty()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot_id` doesn't reference an existing slot.
+    pub fn is_connected(&self, slot_id: SlotId) -> bool {
+        !self.connections(slot_id).is_empty()
+    }
 
-    /// Evaluate the node from the given input expressions, and optionally
-    /// produce output expression(s).
+    /// Get the slots linked to a slot.
     ///
-    /// The expressions themselves are not evaluated (that is, _e.g._ "3 + 2" is
-    /// _not_ reduced to "5").
-    fn eval(
-        &self,
-        module: &mut Module,
-        inputs: Vec<ExprHandle>,
-    ) -> Result<Vec<ExprHandle>, ExprError>;
-}
+    /// For an input slot, these are the output slots it's linked from, which
+    /// is at most one slot unless the input slot accepts multiple links. For
+    /// an output slot, these are the input slots it's linked to. The slots are
+    /// listed in link order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot_id` doesn't reference an existing slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    /// let sin = graph.add_node(SinNode::default());
+    /// let output = graph.output_slot(time, "time").unwrap();
+    /// let input = graph.input_slot(sin, "in").unwrap();
+    /// assert!(!graph.is_connected(input));
+    ///
+    /// graph.link(output, input).unwrap();
+    /// assert!(graph.is_connected(input));
+    /// assert_eq!(graph.connections(input), &[output]);
+    /// ```
+    pub fn connections(&self, slot_id: SlotId) -> &[SlotId] {
+        &self.get_slot(slot_id).linked_slots
+    }
 
-/// Graph node to add two values.
-#[derive(Debug, Clone)]
-pub struct AddNode {
-    slots: [SlotDef; 3],
-}
+    /// Get all the input slots which need a link but are not linked.
+    ///
+    /// These are the input slots with no default value and not accepting
+    /// multiple inputs, which [`validate()`] reports as
+    /// [`GraphError::UnlinkedInput`], and which prevent the graph from being
+    /// evaluated. This allows for example an editor to warn about missing
+    /// links. The slots are returned in slot identifier order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    /// let add = graph.add_node(AddNode::default());
+    /// let lhs = graph.input_slot(add, "lhs").unwrap();
+    /// let rhs = graph.input_slot(add, "rhs").unwrap();
+    /// graph
+    ///     .link(graph.output_slot(time, "time").unwrap(), lhs)
+    ///     .unwrap();
+    /// assert_eq!(graph.unconnected_inputs(), vec![rhs]);
+    /// ```
+    ///
+    /// [`validate()`]: Graph::validate
+    pub fn unconnected_inputs(&self) -> Vec<SlotId> {
+        self.live_slots()
+            .filter(|s| {
+                s.is_input()
+                    && s.linked_slots.is_empty()
+                    && s.def().default_value().is_none()
+                    && !s.def().allow_multi_input()
+            })
+            .map(|s| s.id())
+            .collect()
+    }
 
-impl Default for AddNode {
-    fn default() -> Self {
-        Self {
-            slots: [
-                SlotDef::input("lhs", None),
-                SlotDef::input("rhs", None),
-                SlotDef::output("result", None),
-            ],
+    /// Rename a slot.
+    ///
+    /// The new name is used by all the functions finding a slot by name, like
+    /// [`get_slot_id()`] or [`input_slot()`]. Only the slot of the graph is
+    /// renamed; the slot definitions returned by [`Node::slots()`] are not
+    /// modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::DuplicateSlotName`] if another slot of the same
+    /// node already has the new name. The slot is not renamed in that case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot_id` doesn't reference an existing slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let node_id = graph.add_node(AddNode::default());
+    /// let lhs = graph.input_slot(node_id, "lhs").unwrap();
+    /// graph.rename_slot(lhs, "base").unwrap();
+    /// assert_eq!(graph.input_slot(node_id, "base"), Some(lhs));
+    /// assert!(graph.rename_slot(lhs, "rhs").is_err());
+    /// ```
+    ///
+    /// [`get_slot_id()`]: Graph::get_slot_id
+    /// [`input_slot()`]: Graph::input_slot
+    pub fn rename_slot(
+        &mut self,
+        slot_id: SlotId,
+        new_name: impl Into<String>,
+    ) -> Result<(), GraphError> {
+        let new_name = new_name.into();
+        let node_id = self.get_slot(slot_id).node_id();
+        if let Some(existing) = self
+            .live_slots()
+            .find(|s| s.node_id() == node_id && s.id() != slot_id && s.def().name() == new_name)
+        {
+            return Err(GraphError::DuplicateSlotName {
+                slot: slot_id,
+                existing: existing.id(),
+            });
         }
+        self.get_slot_mut(slot_id).def.name = new_name;
+        Ok(())
     }
-}
 
-impl Node for AddNode {
-    fn slots(&self) -> &[SlotDef] {
-        &self.slots
-    }
+    /// Validate the graph.
+    ///
+    /// This checks that:
+    /// - each link connects an output slot to an input slot;
+    /// - each input slot is linked, unless it has a default value or accepts
+    ///   multiple inputs, in which case it can be left unlinked;
+    /// - the value types of the inputs of each node are compatible, as
+    ///   reported by [`Node::infer_output_types()`];
+    /// - the value types of the two slots of each link match, when both are
+    ///   known. The type of an output slot with a variant type (`None`) is
+    ///   inferred from the types of the inputs of its node, propagating
+    ///   concrete types through the graph. Input slots with a variant type
+    ///   accept any type. Array types match only if both their element type
+    ///   and their element count match.
+    ///
+    /// # Errors
+    ///
+    /// Returns all the problems found in the graph, instead of only the first
+    /// one.
+    pub fn validate(&self) -> Result<(), Vec<GraphError>> {
+        let mut errors = vec![];
+        let slot_types = self.infer_slot_types(&mut errors);
+        for slot in self.live_slots() {
+            if slot.is_input() {
+                if slot.linked_slots.is_empty()
+                    && slot.def().default_value().is_none()
+                    && !slot.def().allow_multi_input()
+                {
+                    errors.push(GraphError::UnlinkedInput { input: slot.id() });
+                }
+                continue;
+            }
 
-    fn eval(
-        &self,
-        module: &mut Module,
-        inputs: Vec<ExprHandle>,
-    ) -> Result<Vec<ExprHandle>, ExprError> {
-        if inputs.len() != 2 {
-            return Err(ExprError::GraphEvalError(format!(
-                "Unexpected input count to AddNode::eval(): expected 2, got {}",
-                inputs.len()
-            )));
+            let output = slot.id();
+            for &input in &slot.linked_slots {
+                let Some(in_slot) = self
+                    .slots
+                    .get(input.index())
+                    .and_then(Option::as_ref)
+                    .filter(|s| s.is_input())
+                else {
+                    errors.push(GraphError::InvalidLink { output, input });
+                    continue;
+                };
+                if let (Some(output_type), Some(input_type)) =
+                    (slot_types[slot.id().index()], in_slot.def().value_type())
+                {
+                    if output_type != input_type {
+                        errors.push(GraphError::TypeMismatch {
+                            output,
+                            input,
+                            output_type,
+                            input_type,
+                        });
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
-        let mut inputs = inputs.into_iter();
-        let left = inputs.next().unwrap();
-        let right = inputs.next().unwrap();
-        let add = module.add(left, right);
-        Ok(vec![add])
     }
-}
 
-/// Graph node to subtract two values.
-#[derive(Debug, Clone)]
-pub struct SubNode {
-    slots: [SlotDef; 3],
-}
+    /// Get the nodes of the graph in topological order.
+    ///
+    /// Each node appears in the returned list after all the nodes linked to
+    /// its input slots. Nodes which don't depend on each other are ordered by
+    /// their identifier, so the order is deterministic.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::Cycle`] with one of the links forming the cycle if
+    /// the graph is not acyclic. This can only happen if the graph was
+    /// deserialized, or built with links not checked by [`link()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let norm = graph.add_node(NormalizeNode::default());
+    /// let pos = graph.add_node(AttributeNode::new(Attribute::POSITION));
+    /// graph.link(
+    ///     graph.output_slots(pos)[0],
+    ///     graph.input_slot(norm, "in").unwrap(),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(graph.topological_order(), Ok(vec![pos, norm]));
+    /// ```
+    ///
+    /// [`link()`]: crate::graph::Graph::link
+    pub fn topological_order(&self) -> Result<Vec<NodeId>, GraphError> {
+        // Count the number of links into each node; the node can be ordered
+        // once all the upstream nodes feeding those links were ordered.
+        let mut pending_links = vec![0; self.nodes.len()];
+        for slot in self.live_slots().filter(|s| s.is_input()) {
+            pending_links[slot.node_id().index()] += slot.linked_slots.len();
+        }
 
-impl Default for SubNode {
-    fn default() -> Self {
-        Self {
-            slots: [
-                SlotDef::input("lhs", None),
-                SlotDef::input("rhs", None),
-                SlotDef::output("result", None),
-            ],
+        let mut queue: VecDeque<NodeId> = pending_links
+            .iter()
+            .enumerate()
+            .filter(|&(index, &count)| count == 0 && self.nodes[index].is_some())
+            .map(|(index, _)| NodeId::new(NonZeroU32::new(index as u32 + 1).unwrap()))
+            .collect();
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(node_id) = queue.pop_front() {
+            order.push(node_id);
+            for slot_id in self.output_slots(node_id) {
+                for input in &self.get_slot(slot_id).linked_slots {
+                    let downstream = self.get_slot(*input).node_id();
+                    pending_links[downstream.index()] -= 1;
+                    if pending_links[downstream.index()] == 0 {
+                        queue.push_back(downstream);
+                    }
+                }
+            }
+        }
+
+        if order.len() == self.nodes.iter().flatten().count() {
+            return Ok(order);
+        }
+
+        // Any node not ordered is part of, or downstream of, a cycle. Walk
+        // upstream through those nodes until one is visited twice, which
+        // finds a link on the cycle itself.
+        let mut visited = vec![false; self.nodes.len()];
+        let mut index = pending_links.iter().position(|&count| count > 0).unwrap();
+        loop {
+            visited[index] = true;
+            let node_id = NodeId::new(NonZeroU32::new(index as u32 + 1).unwrap());
+            let (output, input) = self
+                .input_slots(node_id)
+                .into_iter()
+                .flat_map(|input| {
+                    self.get_slot(input)
+                        .linked_slots
+                        .iter()
+                        .map(move |&output| (output, input))
+                })
+                .find(|(output, _)| pending_links[self.get_slot(*output).node_id().index()] > 0)
+                .unwrap();
+            index = self.get_slot(output).node_id().index();
+            if visited[index] {
+                return Err(GraphError::Cycle { output, input });
+            }
         }
     }
-}
 
-impl Node for SubNode {
-    fn slots(&self) -> &[SlotDef] {
-        &self.slots
+    /// Evaluate the graph into a set of expressions.
+    ///
+    /// The nodes are evaluated in topological order, starting from the source
+    /// nodes which have no input linked. Each node is evaluated with the
+    /// expressions produced by the output slots linked to its input slots,
+    /// ordered by the definition order of those input slots (and not by the
+    /// order the links were created in). An input slot accepting multiple
+    /// inputs contributes one expression per link, in link order. An input slot
+    /// not linked contributes a literal expression of its default value if
+    /// any, or otherwise no expression if it accepts multiple inputs.
+    ///
+    /// Each node is evaluated only once. An output slot linked to several input
+    /// slots shares the same expression among all of them, so that expensive
+    /// expressions are not duplicated in the module.
+    ///
+    /// The function returns the expressions produced by all sink nodes, that is
+    /// the nodes with no output slot linked to another node. The expressions
+    /// are ordered by node, then by output slot definition order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ExprError::GraphError`] if the graph contains a cycle
+    /// ([`GraphError::Cycle`]), if an input slot without a default value is not
+    /// linked ([`GraphError::UnlinkedInput`]), or if a node is evaluated with,
+    /// or produces, a number of expressions different from its number of slots
+    /// ([`GraphError::WrongInputCount`] and [`GraphError::WrongOutputCount`]).
+    /// Any error returned by [`Node::eval()`] is forwarded, with
+    /// [`ExprError::GraphEvalError`] messages prefixed by the name and label of
+    /// the failing node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let pos = graph.add_node(AttributeNode::new(Attribute::POSITION));
+    /// let norm = graph.add_node(NormalizeNode::default());
+    /// graph.link(
+    ///     graph.output_slots(pos)[0],
+    ///     graph.input_slot(norm, "in").unwrap(),
+    /// )
+    /// .unwrap();
+    ///
+    /// let mut module = Module::default();
+    /// let exprs = graph.evaluate(&mut module).unwrap();
+    /// assert_eq!(exprs.len(), 1);
+    /// ```
+    pub fn evaluate(&self, module: &mut Module) -> Result<Vec<ExprHandle>, ExprError> {
+        self.evaluate_sinks(module, false)
     }
 
-    fn eval(
+    /// Evaluate the graph into a set of expressions, folding constant
+    /// arithmetic.
+    ///
+    /// This evaluates the graph like [`evaluate()`], but additionally folds
+    /// each addition, subtraction, multiplication, or division of two literal
+    /// expressions produced by a node into a single literal expression holding
+    /// the pre-computed result. Because the folded expressions are in turn
+    /// literals, this propagates through chains of nodes, which reduces the
+    /// size of the shader code generated for static parts of a graph.
+    ///
+    /// Only floating-point scalar and vector literals are folded. Divisions by
+    /// a literal with any zero component are not folded, and left to the
+    /// shader.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`evaluate()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let a = graph.add_node(ConstantNode::scalar(3.));
+    /// let b = graph.add_node(ConstantNode::scalar(2.));
+    /// graph
+    ///     .add_binary(
+    ///         BinaryOperator::Add,
+    ///         graph.output_slots(a)[0],
+    ///         graph.output_slots(b)[0],
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let mut module = Module::default();
+    /// let exprs = graph.evaluate_folded(&mut module).unwrap();
+    /// let literal = module.get(exprs[0]).unwrap().as_literal().unwrap();
+    /// assert_eq!(literal.value(), graph::Value::Scalar(5_f32.into()));
+    /// ```
+    ///
+    /// [`evaluate()`]: Graph::evaluate
+    pub fn evaluate_folded(&self, module: &mut Module) -> Result<Vec<ExprHandle>, ExprError> {
+        self.evaluate_sinks(module, true)
+    }
+
+    /// Evaluate all nodes of the graph, and return the expressions produced by
+    /// the sink nodes.
+    fn evaluate_sinks(
         &self,
         module: &mut Module,
-        inputs: Vec<ExprHandle>,
+        fold: bool,
     ) -> Result<Vec<ExprHandle>, ExprError> {
-        if inputs.len() != 2 {
-            return Err(ExprError::GraphEvalError(format!(
-                "Unexpected input count to SubNode::eval(): expected 2, got
-{}",
-                inputs.len()
-            )));
+        let values = self.evaluate_slots(module, fold)?;
+        Ok(self
+            .live_slots()
+            .filter(|s| {
+                s.is_output()
+                    && self
+                        .output_slots(s.node_id())
+                        .iter()
+                        .all(|&id| self.get_slot(id).linked_slots.is_empty())
+            })
+            .filter_map(|s| values[s.id().index()])
+            .collect())
+    }
+
+    /// Compile the graph into a module, and return the expressions of some
+    /// output slots.
+    ///
+    /// This evaluates the graph exactly like [`evaluate()`] does, writing all
+    /// the expressions produced by the nodes into the given module. Instead of
+    /// returning the expressions of the sink nodes, it returns the expression
+    /// of each of the given output slots, in the same order. Those can then be
+    /// used like any other expression of the module, for example to build a
+    /// modifier.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`evaluate()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the slots is invalid or is not an output slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    /// let sin = graph.add_node(SinNode::default());
+    /// let out = graph.output_slot(sin, "out").unwrap();
+    /// graph
+    ///     .link(
+    ///         graph.output_slot(time, "time").unwrap(),
+    ///         graph.input_slot(sin, "in").unwrap(),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let mut module = Module::default();
+    /// let exprs = graph.compile(&mut module, &[out]).unwrap();
+    /// let modifier = SetAttributeModifier::new(Attribute::F32_0, exprs[0]);
+    /// ```
+    ///
+    /// [`evaluate()`]: Graph::evaluate
+    pub fn compile(
+        &self,
+        module: &mut Module,
+        outputs: &[SlotId],
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        for &slot_id in outputs {
+            assert!(
+                self.get_slot(slot_id).is_output(),
+                "Slot #{} is not an output slot.",
+                slot_id.id()
+            );
         }
-        let mut inputs = inputs.into_iter();
-        let left = inputs.next().unwrap();
-        let right = inputs.next().unwrap();
-        let sub = module.sub(left, right);
-        Ok(vec![sub])
+        let values = self.evaluate_slots(module, false)?;
+        // All nodes are evaluated, so all output slots have a value.
+        Ok(outputs
+            .iter()
+            .map(|slot_id| values[slot_id.index()].unwrap())
+            .collect())
     }
-}
 
-/// Graph node to multiply two values.
-#[derive(Debug, Clone)]
-pub struct MulNode {
-    slots: [SlotDef; 3],
-}
+    /// Get the modifiers writing the values tapped by the [`DebugTapNode`]s of
+    /// the graph into their attribute.
+    ///
+    /// This compiles the graph into the given module like [`compile()`], and
+    /// returns for each enabled [`DebugTapNode`] a [`SetAttributeModifier`]
+    /// writing the value of its output slot into the attribute of the node.
+    /// The modifiers are typically added to the update modifiers of the effect
+    /// using the graph. Disabled taps are skipped.
+    ///
+    /// When the `debug` feature is not enabled, this doesn't compile the graph
+    /// and always returns an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`evaluate()`].
+    ///
+    /// [`compile()`]: Graph::compile
+    /// [`evaluate()`]: Graph::evaluate
+    pub fn debug_tap_modifiers(
+        &self,
+        module: &mut Module,
+    ) -> Result<Vec<SetAttributeModifier>, ExprError> {
+        if !cfg!(feature = "debug") {
+            return Ok(vec![]);
+        }
+        let (outputs, attributes): (Vec<SlotId>, Vec<Attribute>) = self
+            .find_nodes::<DebugTapNode>()
+            .into_iter()
+            .filter(|&node_id| self.is_node_enabled(node_id))
+            .map(|node_id| {
+                let node = self.get_node(node_id).expect("Node was removed.");
+                let node = node.downcast_ref::<DebugTapNode>().unwrap();
+                (self.output_slots(node_id)[0], node.attribute())
+            })
+            .unzip();
+        if outputs.is_empty() {
+            return Ok(vec![]);
+        }
+        let values = self.compile(module, &outputs)?;
+        Ok(attributes
+            .into_iter()
+            .zip(values)
+            .map(|(attribute, value)| SetAttributeModifier::new(attribute, value))
+            .collect())
+    }
 
-impl Default for MulNode {
-    fn default() -> Self {
-        Self {
-            slots: [
-                SlotDef::input("lhs", None),
-                SlotDef::input("rhs", None),
-                SlotDef::output("result", None),
-            ],
+    /// Evaluate all nodes of the graph, and return the expression produced by
+    /// each output slot, indexed like the slots. If `fold` is `true`, constant
+    /// arithmetic expressions produced by the nodes are folded.
+    fn evaluate_slots(
+        &self,
+        module: &mut Module,
+        fold: bool,
+    ) -> Result<Vec<Option<ExprHandle>>, ExprError> {
+        let order = self.topological_order()?;
+
+        // Expression produced by each output slot, indexed like the slots.
+        let mut values: Vec<Option<ExprHandle>> = vec![None; self.slots.len()];
+        for node_id in order {
+            let mut inputs = vec![];
+            for slot_id in self.defined_slots(node_id, SlotDir::Input) {
+                let slot = self.get_slot(slot_id);
+                if slot.linked_slots.is_empty() {
+                    if let Some(value) = slot.def().default_value() {
+                        inputs.push(module.lit(value));
+                        continue;
+                    }
+                }
+                if slot.linked_slots.is_empty() && !slot.def().allow_multi_input() {
+                    return Err(GraphError::UnlinkedInput { input: slot_id }.into());
+                }
+                // All upstream nodes were evaluated, so all values are known.
+                inputs.extend(
+                    slot.linked_slots
+                        .iter()
+                        .map(|output| values[output.index()].unwrap()),
+                );
+            }
+
+            // The slots of the graph are created from the node when it's added,
+            // but the node may have changed since, for example if it was
+            // modified with get_node_mut() or deserialized.
+            let node = self.get_node(node_id).expect("Node was removed.");
+            let node_slots = node.slots();
+            if node_slots.iter().all(|s| !s.allow_multi_input()) {
+                let expected = node_slots.iter().filter(|s| s.is_input()).count();
+                if inputs.len() != expected {
+                    return Err(GraphError::WrongInputCount {
+                        node: Some(node_id),
+                        expected,
+                        got: inputs.len(),
+                    }
+                    .into());
+                }
+            }
+
+            let output_slots = self.defined_slots(node_id, SlotDir::Output);
+            let outputs = if self.is_node_enabled(node_id) {
+                node.eval(module, inputs).map_err(|err| match err {
+                    ExprError::GraphEvalError(msg) => ExprError::GraphEvalError(format!(
+                        "Node {}: {}",
+                        self.node_display_name(node_id),
+                        msg
+                    )),
+                    ExprError::GraphError(GraphError::WrongInputCount {
+                        node: None,
+                        expected,
+                        got,
+                    }) => GraphError::WrongInputCount {
+                        node: Some(node_id),
+                        expected,
+                        got,
+                    }
+                    .into(),
+                    err => err,
+                })?
+            } else {
+                // Bypass the node by forwarding its input to its output.
+                if inputs.len() != 1 || output_slots.len() != 1 {
+                    return Err(GraphError::NoPassthrough { node: node_id }.into());
+                }
+                inputs
+            };
+            if outputs.len() != output_slots.len() {
+                return Err(GraphError::WrongOutputCount {
+                    node: node_id,
+                    expected: output_slots.len(),
+                    got: outputs.len(),
+                }
+                .into());
+            }
+
+            for (slot_id, expr) in output_slots.into_iter().zip(outputs) {
+                let expr = if fold {
+                    fold_constant(module, expr)
+                } else {
+                    expr
+                };
+                values[slot_id.index()] = Some(expr);
+            }
         }
+
+        Ok(values)
     }
-}
 
-impl Node for MulNode {
-    fn slots(&self) -> &[SlotDef] {
-        &self.slots
+    /// Get all the particle attributes read by the graph.
+    ///
+    /// This collects the attribute of each [`AttributeNode`], and the
+    /// attributes of each [`DerivativeNode`], which contribute to the
+    /// expressions returned by [`evaluate()`]. That is, the nodes visited are
+    /// the sink nodes and all the nodes they're linked from, directly or not.
+    /// Disabled nodes are bypassed during evaluation, so don't read any
+    /// attribute themselves.
+    ///
+    /// This allows ensuring an effect allocates all the attributes the graph
+    /// needs before compiling it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let age = graph.add_node(AttributeNode::new(Attribute::AGE));
+    /// let sin = graph.add_node(SinNode::default());
+    /// graph
+    ///     .link(
+    ///         graph.output_slots(age)[0],
+    ///         graph.input_slot(sin, "in").unwrap(),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let attributes = graph.referenced_attributes();
+    /// assert!(attributes.contains(&Attribute::AGE));
+    /// ```
+    ///
+    /// [`evaluate()`]: Graph::evaluate
+    pub fn referenced_attributes(&self) -> HashSet<Attribute> {
+        // Walk the links upstream, starting from the sinks like evaluate().
+        let mut queue: VecDeque<NodeId> = self
+            .nodes()
+            .filter(|&node_id| {
+                self.output_slots(node_id)
+                    .iter()
+                    .all(|&id| !self.is_connected(id))
+            })
+            .collect();
+        let mut visited: HashSet<NodeId> = queue.iter().copied().collect();
+        let mut attributes = HashSet::new();
+        while let Some(node_id) = queue.pop_front() {
+            if self.is_node_enabled(node_id) {
+                let node = self.get_node(node_id).expect("Node was removed.");
+                if let Some(node) = node.downcast_ref::<AttributeNode>() {
+                    attributes.insert(node.attr());
+                } else if let Some(node) = node.downcast_ref::<DerivativeNode>() {
+                    attributes.extend(node.attributes().iter().copied());
+                }
+            }
+            for input in self.input_slots(node_id) {
+                for &output in self.connections(input) {
+                    let upstream = self.get_slot(output).node_id();
+                    if visited.insert(upstream) {
+                        queue.push_back(upstream);
+                    }
+                }
+            }
+        }
+        attributes
+    }
+
+    /// Export the graph to the Graphviz DOT format.
+    ///
+    /// Each node is emitted as a record labeled with the node label if any, or
+    /// the node name otherwise, with its
+    /// input slots on one side and its output slots on the other side. Each
+    /// link is emitted as an edge from an output slot to an input slot. This is
+    /// mainly intended for debugging, by rendering the graph with _e.g._ `dot
+    /// -Tsvg`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    /// let sin = graph.add_node(SinNode::default());
+    /// graph.link(
+    ///     graph.output_slot(time, "time").unwrap(),
+    ///     graph.input_slot(sin, "in").unwrap(),
+    /// )
+    /// .unwrap();
+    ///
+    /// let dot = graph.to_dot();
+    /// assert!(dot.starts_with("digraph {"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        fn escape(s: &str) -> String {
+            s.chars()
+                .flat_map(|c| {
+                    let escaped = matches!(c, '"' | '\\' | '{' | '}' | '|' | '<' | '>' | ' ');
+                    escaped
+                        .then_some('\\')
+                        .into_iter()
+                        .chain(std::iter::once(c))
+                })
+                .collect()
+        }
+
+        let ports = |slots: Vec<SlotId>| {
+            slots
+                .iter()
+                .map(|&id| {
+                    let slot = self.get_slot(id);
+                    format!("<s{}> {}", id.id(), escape(slot.def().name()))
+                })
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+
+        let mut dot = "digraph {\n    node [shape=record];\n".to_string();
+        for (index, node) in self.nodes.iter().enumerate() {
+            let Some(node) = node else {
+                continue;
+            };
+            let node_id = NodeId::new(NonZeroU32::new(index as u32 + 1).unwrap());
+            let mut fields = vec![];
+            let inputs = ports(self.input_slots(node_id));
+            if !inputs.is_empty() {
+                fields.push(format!("{{{}}}", inputs));
+            }
+            fields.push(escape(node.label().unwrap_or(node.name())));
+            let outputs = ports(self.output_slots(node_id));
+            if !outputs.is_empty() {
+                fields.push(format!("{{{}}}", outputs));
+            }
+            let _ = writeln!(
+                dot,
+                "    n{} [label=\"{{{}}}\"];",
+                node_id.id(),
+                fields.join("|")
+            );
+        }
+        for slot in self.live_slots().filter(|s| s.is_output()) {
+            for input in &slot.linked_slots {
+                let _ = writeln!(
+                    dot,
+                    "    n{}:s{} -> n{}:s{};",
+                    slot.node_id().id(),
+                    slot.id().id(),
+                    self.get_slot(*input).node_id().id(),
+                    input.id()
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Describe the graph in a human-readable text format.
+    ///
+    /// Each node is listed with its identifier and name, followed by its label
+    /// if any, and whether it's disabled. Below each node, its input and
+    /// output slots are listed in display order, with the value type of the
+    /// slot if declared. Each input slot lists the output slots it's linked from
+    /// (`<-`), and each output slot lists the input slots it's linked to
+    /// (`->`). This is mainly intended for debugging, as an alternative to
+    /// [`to_dot()`] which doesn't require any external tool.
+    ///
+    /// The exact format is not stable, and may change between versions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_hanabi::*;
+    /// let mut graph = Graph::new();
+    /// let time = graph.add_node(TimeNode::default());
+    /// let sin = graph.add_node(SinNode::default());
+    /// graph.link(
+    ///     graph.output_slot(time, "time").unwrap(),
+    ///     graph.input_slot(sin, "in").unwrap(),
+    /// )
+    /// .unwrap();
+    ///
+    /// println!("{}", graph.describe());
+    /// ```
+    ///
+    /// [`to_dot()`]: Graph::to_dot
+    pub fn describe(&self) -> String {
+        let node_ref = |node_id: NodeId| {
+            let node = self.get_node(node_id).unwrap();
+            format!("#{} {}", node_id.id(), node.label().unwrap_or(node.name()))
+        };
+        let slot_ref = |slot_id: SlotId| {
+            let slot = self.get_slot(slot_id);
+            format!("{}.{}", node_ref(slot.node_id()), slot.def().name())
+        };
+
+        let mut desc = String::new();
+        for node_id in self.nodes() {
+            let node = self.get_node(node_id).unwrap();
+            let _ = write!(desc, "#{} {}", node_id.id(), node.name());
+            if let Some(label) = node.label() {
+                let _ = write!(desc, " {:?}", label);
+            }
+            if !self.is_node_enabled(node_id) {
+                desc.push_str(" (disabled)");
+            }
+            desc.push('\n');
+            for (dir, arrow, slots) in [
+                ("in", "<-", self.input_slots(node_id)),
+                ("out", "->", self.output_slots(node_id)),
+            ] {
+                for slot_id in slots {
+                    let slot = self.get_slot(slot_id);
+                    let _ = write!(desc, "    {} {}", dir, slot.def().name());
+                    if let Some(value_type) = slot.def().value_type() {
+                        let _ = write!(desc, ": {}", value_type);
+                    }
+                    for &remote_id in &slot.linked_slots {
+                        let _ = write!(desc, " {} {}", arrow, slot_ref(remote_id));
+                    }
+                    desc.push('\n');
+                }
+            }
+        }
+        desc
+    }
+
+    /// Check if a node is reachable from another one by following links
+    /// downstream. A node is considered downstream of itself.
+    fn is_downstream_of(&self, node_id: NodeId, upstream_id: NodeId) -> bool {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![upstream_id];
+        while let Some(current) = stack.pop() {
+            if current == node_id {
+                return true;
+            }
+            if std::mem::replace(&mut visited[current.index()], true) {
+                continue;
+            }
+            for slot_id in self.output_slots(current) {
+                for input in &self.get_slot(slot_id).linked_slots {
+                    stack.push(self.get_slot(*input).node_id());
+                }
+            }
+        }
+        false
+    }
+
+    /// Get the name of a node for diagnostic messages, including its label if
+    /// any.
+    fn node_display_name(&self, node_id: NodeId) -> String {
+        let node = self.get_node(node_id).expect("Node was removed.");
+        match node.label() {
+            Some(label) => format!("'{}' ({} #{})", label, node.name(), node_id.id()),
+            None => format!("{} #{}", node.name(), node_id.id()),
+        }
+    }
+
+    /// Infer the value type of all slots, indexed by slot index.
+    ///
+    /// Nodes are visited in topological order, so that the types of the output
+    /// slots linked to the inputs of a node are known before inferring the
+    /// types of its own output slots. If the graph contains a cycle, only the
+    /// declared slot types are returned.
+    fn infer_slot_types(&self, errors: &mut Vec<GraphError>) -> Vec<Option<ValueType>> {
+        let mut slot_types: Vec<Option<ValueType>> = self
+            .slots
+            .iter()
+            .map(|slot| slot.as_ref().and_then(|s| s.def().value_type()))
+            .collect();
+        let Ok(order) = self.topological_order() else {
+            return slot_types;
+        };
+
+        for node_id in order {
+            let node = self.get_node(node_id).unwrap();
+            let input_types: Vec<Option<ValueType>> = self
+                .defined_slots(node_id, SlotDir::Input)
+                .into_iter()
+                .map(|id| {
+                    let slot = self.get_slot(id);
+                    match slot.linked_slots.first() {
+                        Some(output) => slot_types[output.index()],
+                        None => slot
+                            .def()
+                            .value_type()
+                            .or(slot.def().default_value().map(|v| v.value_type())),
+                    }
+                })
+                .collect();
+            let outputs = self.defined_slots(node_id, SlotDir::Output);
+            match node.infer_output_types(&input_types) {
+                Ok(output_types) if output_types.len() == outputs.len() => {
+                    for (id, value_type) in outputs.into_iter().zip(output_types) {
+                        slot_types[id.index()] = value_type;
+                    }
+                }
+                Ok(output_types) => errors.push(GraphError::WrongOutputCount {
+                    node: node_id,
+                    expected: outputs.len(),
+                    got: output_types.len(),
+                }),
+                Err(GraphError::IncompatibleTypes { lhs, rhs, .. }) => {
+                    errors.push(GraphError::IncompatibleTypes {
+                        node: Some(node_id),
+                        lhs,
+                        rhs,
+                    });
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+
+        slot_types
+    }
+
+    /// Iterate over all the slots not removed from the graph.
+    fn live_slots(&self) -> impl Iterator<Item = &Slot> {
+        self.slots.iter().flatten()
+    }
+
+    /// Get the slots of a node in a given direction, in definition order.
+    fn defined_slots(&self, node_id: NodeId, dir: SlotDir) -> Vec<SlotId> {
+        self.live_slots()
+            .filter(|s| s.node_id() == node_id && s.dir() == dir)
+            .map(|s| s.id())
+            .collect()
+    }
+
+    /// Get the slots of a node in a given direction, in display order.
+    fn ordered_slots(&self, node_id: NodeId, dir: SlotDir) -> Vec<SlotId> {
+        let Some(node) = self.get_node(node_id) else {
+            return vec![];
+        };
+        let slots = self.slots(node_id);
+        node.slot_order()
+            .into_iter()
+            // Ignore invalid indices, in case the node changed since it was
+            // added to the graph.
+            .filter_map(|index| slots.get(index).copied())
+            .filter(|&id| self.get_slot(id).dir() == dir)
+            .collect()
+    }
+
+    fn get_slot(&self, id: SlotId) -> &Slot {
+        let index = id.index();
+        assert!(index < self.slots.len());
+        self.slots[index].as_ref().expect("Slot was removed.")
+    }
+
+    fn get_slot_mut(&mut self, id: SlotId) -> &mut Slot {
+        let index = id.index();
+        assert!(index < self.slots.len());
+        self.slots[index].as_mut().expect("Slot was removed.")
+    }
+}
+
+/// Fold a binary arithmetic expression of two literals into a single literal.
+///
+/// Returns the original expression if it can't be folded.
+fn fold_constant(module: &mut Module, expr: ExprHandle) -> ExprHandle {
+    let Some(&Expr::Binary { op, left, right }) = module.get(expr) else {
+        return expr;
+    };
+    let literal = |expr| {
+        module
+            .get(expr)
+            .and_then(Expr::as_literal)
+            .map(|lit| lit.value())
+    };
+    let (Some(left), Some(right)) = (literal(left), literal(right)) else {
+        return expr;
+    };
+    match fold_binary(op, &left, &right) {
+        Some(value) => module.lit(value),
+        None => expr,
+    }
+}
+
+/// Compute the result of a binary arithmetic operator on two floating-point
+/// scalar or vector values.
+///
+/// A scalar operand is applied to all the components of a vector operand, like
+/// in WGSL. Returns `None` if the operator or the operand types are not
+/// supported, or for a division by zero.
+fn fold_binary(op: BinaryOperator, left: &Value, right: &Value) -> Option<Value> {
+    let func: fn(f32, f32) -> f32 = match op {
+        BinaryOperator::Add => |l, r| l + r,
+        BinaryOperator::Sub => |l, r| l - r,
+        BinaryOperator::Mul => |l, r| l * r,
+        BinaryOperator::Div => |l, r| l / r,
+        _ => return None,
+    };
+    let left = float_components(left)?;
+    let right = float_components(right)?;
+    if op == BinaryOperator::Div && right.contains(&0.) {
+        return None;
+    }
+    let count = match (left.len(), right.len()) {
+        (l, r) if l == r => l,
+        (1, r) => r,
+        (l, 1) => l,
+        _ => return None,
+    };
+    let result: Vec<f32> = (0..count)
+        .map(|i| func(left[i.min(left.len() - 1)], right[i.min(right.len() - 1)]))
+        .collect();
+    Some(match count {
+        1 => Value::Scalar(result[0].into()),
+        2 => Value::Vector(Vec2::from_slice(&result).into()),
+        3 => Value::Vector(Vec3::from_slice(&result).into()),
+        _ => Value::Vector(Vec4::from_slice(&result).into()),
+    })
+}
+
+/// Get the components of a floating-point scalar or vector value.
+fn float_components(value: &Value) -> Option<Vec<f32>> {
+    match value {
+        Value::Scalar(ScalarValue::Float(f)) => Some(vec![*f]),
+        Value::Vector(v) if v.elem_type() == ScalarType::Float => Some(v.get_all::<f32>().to_vec()),
+        _ => None,
+    }
+}
+
+/// Helper trait to access a value as [`Any`].
+///
+/// This is implemented for all `'static` types, and allows downcasting a
+/// `dyn Node` trait object to its concrete type with `downcast_ref()` and
+/// `downcast_mut()`.
+pub trait AsAny: Any {
+    /// Get the value as an [`Any`] reference.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Get the value as a mutable [`Any`] reference.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Generic graph node.
+#[cfg_attr(feature = "serde", typetag::serde)]
+pub trait Node: AsAny {
+    /// Get the display name of this node.
+    ///
+    /// The default implementation returns the name of the node type, without
+    /// its module path nor generic arguments, like `AddNode`.
+    fn name(&self) -> &str {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap();
+        name.rsplit("::").next().unwrap()
+    }
+
+    /// Get the optional label of this node instance.
+    ///
+    /// Labels allow telling apart multiple nodes of the same type, for example
+    /// in error messages or when exporting the graph with
+    /// [`Graph::to_dot()`]. The default implementation returns `None`.
+    fn label(&self) -> Option<&str> {
+        None
+    }
+
+    /// Set the optional label of this node instance.
+    ///
+    /// The default implementation ignores the label. Nodes supporting labels
+    /// must override both this and [`label()`].
+    ///
+    /// [`label()`]: Node::label
+    fn set_label(&mut self, _label: Option<String>) {}
+
+    /// Clone self.
+    fn boxed_clone(&self) -> BoxedNode;
+
+    /// Get the list of slots of this node.
+    ///
+    /// The list contains both input and output slots, without any guaranteed
+    /// order.
+    fn slots(&self) -> &[SlotDef];
+
+    /// Get the display order of the slots of this node.
+    ///
+    /// This returns the indices into [`slots()`] of all the slots of the node,
+    /// in the order they should be presented, for example by an editor. The
+    /// order is only a presentation hint; input expressions are always passed
+    /// to [`eval()`] in the definition order of [`slots()`].
+    ///
+    /// The default implementation returns the definition order.
+    ///
+    /// [`slots()`]: Node::slots
+    /// [`eval()`]: Node::eval
+    fn slot_order(&self) -> Vec<usize> {
+        (0..self.slots().len()).collect()
+    }
+
+    /// Infer the value types of the output slots from the ones of the input
+    /// slots.
+    ///
+    /// This is used by [`Graph::validate()`] to compute the concrete type of
+    /// the output slots with a variant type (`None`), which often depends on
+    /// the type of the inputs. The `input_types` are given in the definition
+    /// order of [`slots()`], and are `None` if unknown. The returned types are
+    /// in the same order as the output slots in [`slots()`], and are `None`
+    /// if they can't be inferred.
+    ///
+    /// The default implementation returns the declared type of each output
+    /// slot, or the type of the first input if the output slot has a variant
+    /// type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphError::IncompatibleTypes`] if the input types are not
+    /// compatible with the operation performed by the node.
+    ///
+    /// [`slots()`]: Node::slots
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        let first = input_types.first().copied().flatten();
+        Ok(self
+            .slots()
+            .iter()
+            .filter(|s| s.is_output())
+            .map(|s| s.value_type().or(first))
+            .collect())
+    }
+
+    /// Evaluate the node from the given input expressions, and optionally
+    /// produce output expression(s).
+    ///
+    /// The expressions themselves are not evaluated (that is, _e.g._ "3 + 2" is
+    /// _not_ reduced to "5").
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError>;
+}
+
+/// Boxed version of [`Node`].
+pub type BoxedNode = Box<dyn Node>;
+
+impl Clone for BoxedNode {
+    fn clone(&self) -> Self {
+        self.boxed_clone()
+    }
+}
+
+impl dyn Node {
+    /// Downcast the node to its concrete type.
+    ///
+    /// Returns `None` if the node is not of type `N`.
+    pub fn downcast_ref<N: Node>(&self) -> Option<&N> {
+        self.as_any().downcast_ref::<N>()
+    }
+
+    /// Downcast the node to its concrete type, mutably.
+    ///
+    /// Returns `None` if the node is not of type `N`.
+    pub fn downcast_mut<N: Node>(&mut self) -> Option<&mut N> {
+        self.as_any_mut().downcast_mut::<N>()
+    }
+}
+
+/// Get the common type of the operands of an arithmetic operator.
+///
+/// All operands must have the same type, except that a scalar operand is
+/// promoted to the type of a vector operand with the same element type, like
+/// in WGSL. Returns `Ok(None)` if the type of any operand is unknown.
+fn promote_types(input_types: &[Option<ValueType>]) -> Result<Option<ValueType>, GraphError> {
+    let mut common = None;
+    for &value_type in input_types {
+        let Some(value_type) = value_type else {
+            return Ok(None);
+        };
+        common = Some(match common {
+            None => value_type,
+            Some(common) if common == value_type => common,
+            Some(ValueType::Scalar(s)) if is_vector_of(value_type, s) => value_type,
+            Some(ValueType::Vector(v)) if value_type == ValueType::Scalar(v.elem_type()) => {
+                ValueType::Vector(v)
+            }
+            Some(common) => {
+                return Err(GraphError::IncompatibleTypes {
+                    node: None,
+                    lhs: common,
+                    rhs: value_type,
+                })
+            }
+        });
+    }
+    Ok(common)
+}
+
+/// Get the common type of operands which must all have the same type.
+///
+/// Unlike [`promote_types()`], a scalar operand is never promoted to the type
+/// of a vector operand, since WGSL requires the operands of `select()` and of
+/// the comparison operators to have the exact same type. Returns `Ok(None)` if
+/// the type of any operand is unknown.
+fn same_types(input_types: &[Option<ValueType>]) -> Result<Option<ValueType>, GraphError> {
+    let mut common = None;
+    for &value_type in input_types {
+        let Some(value_type) = value_type else {
+            return Ok(None);
+        };
+        match common {
+            Some(common) if common != value_type => {
+                return Err(GraphError::IncompatibleTypes {
+                    node: None,
+                    lhs: common,
+                    rhs: value_type,
+                })
+            }
+            _ => common = Some(value_type),
+        }
+    }
+    Ok(common)
+}
+
+fn is_vector_of(value_type: ValueType, elem_type: ScalarType) -> bool {
+    matches!(value_type, ValueType::Vector(v) if v.elem_type() == elem_type)
+}
+
+/// Check if a type is a floating-point scalar or vector type.
+fn is_float_type(value_type: ValueType) -> bool {
+    value_type == ValueType::Scalar(ScalarType::Float)
+        || is_vector_of(value_type, ScalarType::Float)
+}
+
+/// Get the type of the result of a component-wise comparison of two values of
+/// the given type.
+fn comparison_type(value_type: ValueType) -> Option<ValueType> {
+    match value_type {
+        ValueType::Scalar(_) => Some(ValueType::Scalar(ScalarType::Bool)),
+        ValueType::Vector(v) => Some(ValueType::Vector(VectorType::new(
+            ScalarType::Bool,
+            v.count() as u8,
+        ))),
+        _ => None,
+    }
+}
+
+/// Graph node to add two values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for AddNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for AddNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        Ok(vec![promote_types(input_types)?])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let add = module.add(left, right);
+        Ok(vec![add])
+    }
+}
+
+/// Graph node to subtract two values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SubNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SubNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        Ok(vec![promote_types(input_types)?])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let sub = module.sub(left, right);
+        Ok(vec![sub])
+    }
+}
+
+/// Graph node to multiply two values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MulNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for MulNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for MulNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        // Matrix products change the shape of their operands; don't try to
+        // infer their type.
+        if input_types.iter().flatten().any(ValueType::is_matrix) {
+            return Ok(vec![None]);
+        }
+        Ok(vec![promote_types(input_types)?])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let mul = module.mul(left, right);
+        Ok(vec![mul])
+    }
+}
+
+/// Graph node to divide two values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for DivNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for DivNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        Ok(vec![promote_types(input_types)?])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let div = module.div(left, right);
+        Ok(vec![div])
+    }
+}
+
+/// Graph node to raise a value to the power of another value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for PowNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("base", None),
+                SlotDef::input("exponent", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for PowNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let base = inputs.next().unwrap();
+        let exponent = inputs.next().unwrap();
+        let pow = module.pow(base, exponent);
+        Ok(vec![pow])
+    }
+}
+
+/// Graph node to get any single particle attribute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeNode {
+    /// The attribute to get.
+    attr: Attribute,
+    /// The output slot corresponding to the get value.
+    slots: [SlotDef; 1],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for AttributeNode {
+    fn default() -> Self {
+        Self::new(Attribute::POSITION)
+    }
+}
+
+impl AttributeNode {
+    /// Create a new attribute node for the given [`Attribute`].
+    pub fn new(attr: Attribute) -> Self {
+        Self {
+            label: None,
+            attr,
+            slots: [SlotDef::output(attr.name(), Some(attr.value_type()))],
+        }
+    }
+}
+
+impl AttributeNode {
+    /// Get the attribute this node reads.
+    pub fn attr(&self) -> Attribute {
+        self.attr
+    }
+
+    /// Set the attribute this node reads.
+    pub fn set_attr(&mut self, attr: Attribute) {
+        self.attr = attr;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for AttributeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let attr = module.attr(self.attr);
+        Ok(vec![attr])
+    }
+}
+
+/// Graph node to get the rate of change of a particle attribute.
+///
+/// The output is the change of the attribute value since the previous frame,
+/// divided by the simulation delta time:
+///
+/// ```txt
+/// (particle.attribute - particle.storage) / sim_params.delta_time
+/// ```
+///
+/// This allows for example deriving a velocity from a position driven by other
+/// modifiers, or an acceleration from a velocity.
+///
+/// Graph nodes can't write particle attributes, so the previous value is read
+/// from a separate storage attribute, of the same value type, which the effect
+/// must update. The [`store_modifier()`] creates the modifier copying the
+/// current value into the storage attribute. It must be added to the effect
+/// as an init modifier, after the attribute is initialized, so the rate of
+/// change is zero on the first update, and as an update modifier, after all
+/// the modifiers using the rate of change. The storage attribute is reserved
+/// for this node, and can't be used for anything else.
+///
+/// [`store_modifier()`]: DerivativeNode::store_modifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivativeNode {
+    /// The attribute to get the rate of change of, and the attribute storing
+    /// its previous value.
+    attributes: [Attribute; 2],
+    /// The output slot corresponding to the rate of change.
+    slots: [SlotDef; 1],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl DerivativeNode {
+    /// Create a new node for the rate of change of `attribute`, storing its
+    /// previous value into `storage`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both attributes are the same, or have different value types.
+    pub fn new(attribute: Attribute, storage: Attribute) -> Self {
+        assert_ne!(attribute, storage);
+        assert_eq!(attribute.value_type(), storage.value_type());
+        Self {
+            attributes: [attribute, storage],
+            slots: [SlotDef::output("derivative", Some(attribute.value_type()))],
+            label: None,
+        }
+    }
+
+    /// Get the attribute this node calculates the rate of change of.
+    pub fn attribute(&self) -> Attribute {
+        self.attributes[0]
+    }
+
+    /// Get the attribute storing the previous value of [`attribute()`].
+    ///
+    /// [`attribute()`]: DerivativeNode::attribute
+    pub fn storage(&self) -> Attribute {
+        self.attributes[1]
+    }
+
+    /// Get the attributes used by this node, that is [`attribute()`] and
+    /// [`storage()`].
+    ///
+    /// [`attribute()`]: DerivativeNode::attribute
+    /// [`storage()`]: DerivativeNode::storage
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    /// Create the modifier storing the current value of [`attribute()`] into
+    /// [`storage()`].
+    ///
+    /// See [`DerivativeNode`] for details on how to use it.
+    ///
+    /// [`attribute()`]: DerivativeNode::attribute
+    /// [`storage()`]: DerivativeNode::storage
+    pub fn store_modifier(&self, module: &mut Module) -> SetAttributeModifier {
+        SetAttributeModifier::new(self.storage(), module.attr(self.attribute()))
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for DerivativeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let current = module.attr(self.attribute());
+        let previous = module.attr(self.storage());
+        let delta = module.sub(current, previous);
+        let dt = module.builtin(BuiltInOperator::DeltaTime);
+        let derivative = module.div(delta, dt);
+        Ok(vec![derivative])
+    }
+}
+
+/// Graph node to write an intermediate value into a particle attribute for
+/// debugging.
+///
+/// The node forwards its input expression unchanged to its output, so can be
+/// inserted on any link without changing the graph result. When the `debug`
+/// feature is enabled, [`Graph::debug_tap_modifiers()`] additionally returns
+/// for each tap a [`SetAttributeModifier`] writing the tapped value into the
+/// attribute of the node, which can then be read back or visualized, for
+/// example by driving the particle color. The attribute must be a spare
+/// attribute not otherwise used by the effect.
+///
+/// Without the `debug` feature, the node is a passthrough, and no modifier is
+/// produced. This allows leaving taps in a graph without any cost in release
+/// builds.
+///
+/// The input and output slots have the type of the attribute, so that
+/// [`Graph::validate()`] reports tapping a value of another type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugTapNode {
+    /// The attribute the tapped value is written into.
+    attribute: Attribute,
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl DebugTapNode {
+    /// Create a new node writing the tapped value into `attribute`.
+    pub fn new(attribute: Attribute) -> Self {
+        let value_type = Some(attribute.value_type());
+        Self {
+            attribute,
+            slots: [
+                SlotDef::input("in", value_type),
+                SlotDef::output("out", value_type),
+            ],
+            label: None,
+        }
+    }
+
+    /// Get the attribute the tapped value is written into.
+    pub fn attribute(&self) -> Attribute {
+        self.attribute
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for DebugTapNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        _module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        Ok(inputs)
+    }
+}
+
+/// Graph node to get various time values related to the effect system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeNode {
+    /// Output slots corresponding to the various time-related quantities.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for TimeNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [BuiltInOperator::Time, BuiltInOperator::DeltaTime]
+                .map(|op| SlotDef::output(op.name(), Some(op.value_type()))),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for TimeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        Ok([BuiltInOperator::Time, BuiltInOperator::DeltaTime]
+            .map(|op| module.builtin(op))
+            .to_vec())
+    }
+}
+
+/// Graph node to get the particle counts of the particle group being updated.
+///
+/// The node outputs the capacity of the group
+/// ([`BuiltInOperator::ParticleCount`]) and the number of particles alive at
+/// the beginning of the update pass ([`BuiltInOperator::AliveCount`]). It can
+/// only be used in the update pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountNode {
+    /// Output slots corresponding to the various particle counts.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for CountNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [BuiltInOperator::ParticleCount, BuiltInOperator::AliveCount]
+                .map(|op| SlotDef::output(op.name(), Some(op.value_type()))),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for CountNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        Ok(
+            [BuiltInOperator::ParticleCount, BuiltInOperator::AliveCount]
+                .map(|op| module.builtin(op))
+                .to_vec(),
+        )
+    }
+}
+
+/// Global value read by a [`GlobalNode`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GlobalType {
+    /// Origin of the effect, in world space. See
+    /// [`BuiltInOperator::EffectOrigin`].
+    #[default]
+    EffectOrigin,
+    /// Position of the camera, in world space. Only available in the render
+    /// pass. See [`BuiltInOperator::CameraPosition`].
+    CameraPosition,
+}
+
+impl GlobalType {
+    /// Get the built-in operator producing the global value.
+    pub fn operator(&self) -> BuiltInOperator {
+        match self {
+            GlobalType::EffectOrigin => BuiltInOperator::EffectOrigin,
+            GlobalType::CameraPosition => BuiltInOperator::CameraPosition,
+        }
+    }
+}
+
+/// Graph node to read a global value shared by all particles.
+///
+/// The node has a single output, whose name and type depend on the global value
+/// read, as selected by a [`GlobalType`]. Some global values are only
+/// available in some passes; see each [`GlobalType`] variant for details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalNode {
+    /// Output slot corresponding to the global value.
+    slots: [SlotDef; 1],
+    /// Global value read by the node.
+    global_type: GlobalType,
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for GlobalNode {
+    fn default() -> Self {
+        Self::new(GlobalType::default())
+    }
+}
+
+impl GlobalNode {
+    /// Create a new node reading the given global value.
+    pub fn new(global_type: GlobalType) -> Self {
+        let op = global_type.operator();
+        Self {
+            slots: [SlotDef::output(op.name(), Some(op.value_type()))],
+            global_type,
+            label: None,
+        }
+    }
+
+    /// Get the global value read by the node.
+    pub fn global_type(&self) -> GlobalType {
+        self.global_type
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for GlobalNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        Ok(vec![module.builtin(self.global_type.operator())])
+    }
+}
+
+/// Graph node to produce a sine wave from the simulation time.
+///
+/// The output value is `amplitude * sin(time * frequency + phase)`, where
+/// `time` is the simulation time in seconds. The frequency is an angular
+/// frequency, in radians per second, and the phase is in radians. This is a
+/// convenience node for periodic motion, which avoids linking a [`TimeNode`] to
+/// some math nodes. All inputs have a default value if not linked: a frequency
+/// and an amplitude of one, and a phase of zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscillatorNode {
+    /// Frequency, amplitude, and phase inputs, and value output.
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for OscillatorNode {
+    fn default() -> Self {
+        let float = ValueType::Scalar(ScalarType::Float);
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input_with_default("frequency", Some(float), 1_f32.into()),
+                SlotDef::input_with_default("amplitude", Some(float), 1_f32.into()),
+                SlotDef::input_with_default("phase", Some(float), 0_f32.into()),
+                SlotDef::output("value", Some(float)),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for OscillatorNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let frequency = inputs.next().unwrap();
+        let amplitude = inputs.next().unwrap();
+        let phase = inputs.next().unwrap();
+        let time = module.builtin(BuiltInOperator::Time);
+        let angle = module.mul(time, frequency);
+        let angle = module.add(angle, phase);
+        let sin = module.sin(angle);
+        let value = module.mul(amplitude, sin);
+        Ok(vec![value])
+    }
+}
+
+/// Graph node to get a looping phase of the simulation time.
+///
+/// The phase is `fract(time / period)`, which loops from 0 to 1 every `period`
+/// seconds, and can be used to drive looping animations like day-night cycles.
+/// The period is an input slot defaulting to one second if not linked; link
+/// it to a [`PropertyNode`] to control it at runtime. The period must not be
+/// zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseNode {
+    /// Input period and output phase.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for PhaseNode {
+    fn default() -> Self {
+        let float = ValueType::Scalar(ScalarType::Float);
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input_with_default("period", Some(float), 1_f32.into()),
+                SlotDef::output("phase", Some(float)),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for PhaseNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let period = inputs[0];
+        let time = module.builtin(BuiltInOperator::Time);
+        let cycles = module.div(time, period);
+        let phase = module.fract(cycles);
+        Ok(vec![phase])
+    }
+}
+
+/// Graph node to get various real time values related to the effect system.
+///
+/// Unlike [`TimeNode`], the real time is not affected by the relative speed of
+/// the simulation time, nor by pausing it. This is useful for effects which
+/// need to keep animating independently of the game time, like UI effects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealTimeNode {
+    /// Output slots corresponding to the various real time-related quantities.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for RealTimeNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [BuiltInOperator::RealTime, BuiltInOperator::RealDeltaTime]
+                .map(|op| SlotDef::output(op.name(), Some(op.value_type()))),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for RealTimeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        Ok([BuiltInOperator::RealTime, BuiltInOperator::RealDeltaTime]
+            .map(|op| module.builtin(op))
+            .to_vec())
+    }
+}
+
+/// Graph node to generate a random value.
+///
+/// The random value is uniformly distributed in `[0:1)`, and is drawn from the
+/// per-particle random number generator, like [`BuiltInOperator::Rand`]. For
+/// vector types, each component is sampled separately. The random number
+/// generator is advanced each time the node is evaluated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RandomNode {
+    /// Output slot of the random value.
+    slots: [SlotDef; 1],
+    /// Type of the random value.
+    value_type: ValueType,
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for RandomNode {
+    fn default() -> Self {
+        Self::new(ScalarType::Float)
+    }
+}
+
+impl RandomNode {
+    /// Create a new node generating a random value of the given type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value type is not a floating-point scalar or vector type.
+    /// The shaders only support generating floating-point random values.
+    pub fn new(value_type: impl Into<ValueType>) -> Self {
+        let value_type = value_type.into();
+        assert!(
+            is_float_type(value_type),
+            "Invalid non floating-point type for RandomNode."
+        );
+        Self {
+            slots: [SlotDef::output("value", Some(value_type))],
+            value_type,
+            label: None,
+        }
+    }
+
+    /// Get the type of the random value.
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for RandomNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let value = module.builtin(BuiltInOperator::Rand(self.value_type));
+        Ok(vec![value])
+    }
+}
+
+/// Graph node to generate a random value between a minimum and a maximum.
+///
+/// The random value is uniformly distributed between the `min` and `max`
+/// inputs, and is drawn from the per-particle random number generator, like
+/// [`RandomNode`]. For vector types, each component is sampled separately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RandomRangeNode {
+    /// Input bounds and output random value.
+    slots: [SlotDef; 3],
+    /// Type of the random value.
+    value_type: ValueType,
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for RandomRangeNode {
+    fn default() -> Self {
+        Self::new(ScalarType::Float)
+    }
+}
+
+impl RandomRangeNode {
+    /// Create a new node generating a random value of the given type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value type is not a floating-point scalar or vector type.
+    /// The shaders only support generating floating-point random values.
+    pub fn new(value_type: impl Into<ValueType>) -> Self {
+        let value_type = value_type.into();
+        assert!(
+            is_float_type(value_type),
+            "Invalid non floating-point type for RandomRangeNode."
+        );
+        Self {
+            slots: [
+                SlotDef::input("min", Some(value_type)),
+                SlotDef::input("max", Some(value_type)),
+                SlotDef::output("value", Some(value_type)),
+            ],
+            value_type,
+            label: None,
+        }
+    }
+
+    /// Get the type of the random value.
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for RandomRangeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let min = inputs.next().unwrap();
+        let max = inputs.next().unwrap();
+        let t = module.builtin(BuiltInOperator::Rand(self.value_type));
+        let value = module.mix(min, max, t);
+        Ok(vec![value])
+    }
+}
+
+/// Graph node to generate a deterministic pseudo-random value from a seed.
+///
+/// The output is a floating point scalar in \[0:1\) obtained by hashing the
+/// `u32` seed. Unlike [`RandomNode`], which draws a new value each time it's
+/// evaluated, the output only depends on the seed, so the same seed always
+/// produces the same value. Hashing a per-particle seed, like a `u32` value
+/// stored in a particle attribute on spawn, gives a stable variation per
+/// particle across frames.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashNode {
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for HashNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("seed", Some(ValueType::Scalar(ScalarType::Uint))),
+                SlotDef::output("value", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for HashNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let hash = module.hash(input);
+        Ok(vec![hash])
+    }
+}
+
+/// Graph node to combine three scalars into a 3-element vector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombineVec3Node {
+    /// Input components and output vector.
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for CombineVec3Node {
+    fn default() -> Self {
+        let float = Some(ValueType::Scalar(ScalarType::Float));
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("x", float),
+                SlotDef::input("y", float),
+                SlotDef::input("z", float),
+                SlotDef::output("xyz", Some(ValueType::Vector(VectorType::VEC3F))),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for CombineVec3Node {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let x = inputs.next().unwrap();
+        let y = inputs.next().unwrap();
+        let z = inputs.next().unwrap();
+        let xyz = module.vec3(x, y, z);
+        Ok(vec![xyz])
+    }
+}
+
+/// Graph node to split a 3-element vector into its three scalar components.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitVec3Node {
+    /// Input vector and output components.
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SplitVec3Node {
+    fn default() -> Self {
+        let float = Some(ValueType::Scalar(ScalarType::Float));
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("xyz", Some(ValueType::Vector(VectorType::VEC3F))),
+                SlotDef::output("x", float),
+                SlotDef::output("y", float),
+                SlotDef::output("z", float),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SplitVec3Node {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let xyz = inputs[0];
+        Ok(vec![module.x(xyz), module.y(xyz), module.z(xyz)])
+    }
+}
+
+/// Graph node to normalize a vector value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizeNode {
+    /// Input and output vectors.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for NormalizeNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for NormalizeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let norm = module.normalize(input);
+        Ok(vec![norm])
+    }
+}
+
+/// Graph node to map a point of a cube onto a sphere.
+///
+/// The input is a point inside the cube \[-1:1\] on all axes, which is
+/// mapped to a point inside the unit sphere. Points on the faces of the cube
+/// are mapped onto the surface of the sphere. The mapping is:
+///
+/// ```txt
+/// sphere.x = p.x * sqrt(1 - p.y² / 2 - p.z² / 2 + p.y² * p.z² / 3)
+/// sphere.y = p.y * sqrt(1 - p.z² / 2 - p.x² / 2 + p.z² * p.x² / 3)
+/// sphere.z = p.z * sqrt(1 - p.x² / 2 - p.y² / 2 + p.x² * p.y² / 3)
+/// ```
+///
+/// Unlike normalizing the point, this spreads points uniformly distributed in
+/// the cube almost uniformly over the sphere, without clustering them along
+/// the directions of the corners of the cube.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpherizeNode {
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SpherizeNode {
+    fn default() -> Self {
+        let vec3 = Some(ValueType::Vector(VectorType::VEC3F));
+        Self {
+            slots: [SlotDef::input("p", vec3), SlotDef::output("sphere", vec3)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SpherizeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        // Calculate all components at once, by rotating the squared
+        // components so each output component uses the two other ones.
+        let p = inputs[0];
+        let p2 = module.mul(p, p);
+        let a = module.swizzle(p2, "yzx")?;
+        let b = module.swizzle(p2, "zxy")?;
+        let one = module.lit(1.);
+        let half = module.lit(0.5);
+        let three = module.lit(3.);
+        let sum = module.add(a, b);
+        let sum = module.mul(sum, half);
+        let prod = module.mul(a, b);
+        let prod = module.div(prod, three);
+        let scale = module.sub(one, sum);
+        let scale = module.add(scale, prod);
+        let scale = module.sqrt(scale);
+        let sphere = module.mul(p, scale);
+        Ok(vec![sphere])
+    }
+}
+
+/// Graph node to compute the sine of a value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SinNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SinNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let sin = module.sin(input);
+        Ok(vec![sin])
+    }
+}
+
+/// Graph node to compute the cosine of a value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for CosNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for CosNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let cos = module.cos(input);
+        Ok(vec![cos])
+    }
+}
+
+/// Graph node to compute the tangent of a value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TanNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for TanNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for TanNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let tan = module.tan(input);
+        Ok(vec![tan])
+    }
+}
+
+/// Graph node to calculate the arctangent of a value.
+///
+/// The output is in radians in \[-π/2:π/2\]. For vectors, the arctangent of
+/// each component is calculated separately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtanNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for AtanNode {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("x", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for AtanNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let atan = module.atan(input);
+        Ok(vec![atan])
+    }
+}
+
+/// Graph node to calculate the two-argument arctangent of a point.
+///
+/// The output is the angle in radians in \[-π:π\] between the positive X axis
+/// and the point `(x, y)`, for example to orient a particle along a 2D
+/// direction. Unlike `atan(y / x)`, this handles all quadrants, and a zero `x`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Atan2Node {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for Atan2Node {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("y", None),
+                SlotDef::input("x", None),
+                SlotDef::output("angle", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for Atan2Node {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let y = inputs.next().unwrap();
+        let x = inputs.next().unwrap();
+        let atan2 = module.atan2(y, x);
+        Ok(vec![atan2])
+    }
+}
+
+/// Graph node to convert polar coordinates to 2D cartesian coordinates.
+///
+/// The output is the point at distance `radius` from the origin, in the
+/// direction making an angle `angle` in radians with the positive X axis:
+///
+/// ```txt
+/// xy = vec2<f32>(radius * cos(angle), radius * sin(angle))
+/// ```
+///
+/// This is the inverse of [`CartesianToPolarNode`], and is useful for example
+/// to place particles on a circle or a spiral.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolarToCartesianNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for PolarToCartesianNode {
+    fn default() -> Self {
+        let float = Some(ValueType::Scalar(ScalarType::Float));
+        Self {
+            slots: [
+                SlotDef::input("radius", float),
+                SlotDef::input("angle", float),
+                SlotDef::output("xy", Some(ValueType::Vector(VectorType::VEC2F))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for PolarToCartesianNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let radius = inputs.next().unwrap();
+        let angle = inputs.next().unwrap();
+        let cos = module.cos(angle);
+        let sin = module.sin(angle);
+        let x = module.mul(radius, cos);
+        let y = module.mul(radius, sin);
+        let xy = module.vec2(x, y);
+        Ok(vec![xy])
+    }
+}
+
+/// Graph node to convert 2D cartesian coordinates to polar coordinates.
+///
+/// The outputs are the distance of the point `xy` from the origin, and the
+/// angle in radians in \[-π:π\] between the positive X axis and the point:
+///
+/// ```txt
+/// radius = length(xy)
+/// angle = atan2(xy.y, xy.x)
+/// ```
+///
+/// This is the inverse of [`PolarToCartesianNode`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CartesianToPolarNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for CartesianToPolarNode {
+    fn default() -> Self {
+        let float = Some(ValueType::Scalar(ScalarType::Float));
+        Self {
+            slots: [
+                SlotDef::input("xy", Some(ValueType::Vector(VectorType::VEC2F))),
+                SlotDef::output("radius", float),
+                SlotDef::output("angle", float),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for CartesianToPolarNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let xy = inputs[0];
+        let radius = module.length(xy);
+        let x = module.x(xy);
+        let y = module.y(xy);
+        let angle = module.atan2(y, x);
+        Ok(vec![radius, angle])
+    }
+}
+
+/// Graph node to clamp a value between a minimum and a maximum.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClampNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for ClampNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("value", None),
+                SlotDef::input("min", None),
+                SlotDef::input("max", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for ClampNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let value = inputs.next().unwrap();
+        let min = inputs.next().unwrap();
+        let max = inputs.next().unwrap();
+        let clamp = module.clamp(value, min, max);
+        Ok(vec![clamp])
+    }
+}
+
+/// Graph node to linearly interpolate between two values.
+///
+/// The interpolation fraction `t` can be either a scalar, or a vector of the
+/// same rank as `a` and `b` to interpolate component-wise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LerpNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for LerpNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("a", None),
+                SlotDef::input("b", None),
+                SlotDef::input("t", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for LerpNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let a = inputs.next().unwrap();
+        let b = inputs.next().unwrap();
+        let t = inputs.next().unwrap();
+        let mix = module.mix(a, b, t);
+        Ok(vec![mix])
+    }
+}
+
+/// Graph node to calculate the dot product of two vectors.
+///
+/// The output is always a floating point scalar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DotNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for DotNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for DotNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let dot = module.dot(left, right);
+        Ok(vec![dot])
+    }
+}
+
+/// Graph node to calculate the cross product of two vectors.
+///
+/// Only valid for vectors of rank 3.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for CrossNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for CrossNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let cross = module.cross(left, right);
+        Ok(vec![cross])
+    }
+}
+
+/// Graph node to reflect a vector against a surface.
+///
+/// The output is the reflection of the incident vector against a surface with
+/// the given normal, that is `incident - 2 * dot(normal, incident) * normal`.
+/// The normal is assumed to be normalized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReflectNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for ReflectNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("incident", None),
+                SlotDef::input("normal", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for ReflectNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let incident = inputs.next().unwrap();
+        let normal = inputs.next().unwrap();
+        let reflect = module.reflect(incident, normal);
+        Ok(vec![reflect])
+    }
+}
+
+/// Graph node to refract a vector through a surface.
+///
+/// The output is the refraction of the incident vector through a surface with
+/// the given normal, for the ratio `eta` of the indices of refraction. The
+/// incident vector and the normal are assumed to be normalized. In case of
+/// total internal reflection, the output is a zero vector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefractNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for RefractNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("incident", None),
+                SlotDef::input("normal", None),
+                SlotDef::input("eta", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for RefractNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let incident = inputs.next().unwrap();
+        let normal = inputs.next().unwrap();
+        let eta = inputs.next().unwrap();
+        let refract = module.refract(incident, normal, eta);
+        Ok(vec![refract])
+    }
+}
+
+/// Graph node to calculate a Fresnel term, for rim lighting effects.
+///
+/// The output is the scalar intensity:
+///
+/// ```txt
+/// pow(1.0 - saturate(dot(view_dir, normal)), power)
+/// ```
+///
+/// which is 0 where the surface faces the view direction, and increases toward
+/// 1 where the surface is seen at a grazing angle. Higher values of `power`
+/// concentrate the intensity on the rim. The view direction and the normal are
+/// assumed to be normalized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FresnelNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for FresnelNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("view_dir", None),
+                SlotDef::input("normal", None),
+                SlotDef::input("power", None),
+                SlotDef::output("intensity", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for FresnelNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let view_dir = inputs.next().unwrap();
+        let normal = inputs.next().unwrap();
+        let power = inputs.next().unwrap();
+        let dot = module.dot(view_dir, normal);
+        let cos = module.saturate(dot);
+        let one = module.lit(1.);
+        let base = module.sub(one, cos);
+        let intensity = module.pow(base, power);
+        Ok(vec![intensity])
+    }
+}
+
+/// Graph node to rotate a 3-element vector around an axis.
+///
+/// The vector `v` is rotated by `angle` radians around the `axis`, with
+/// [Rodrigues' rotation formula]:
+///
+/// ```txt
+/// v * cos(angle) + cross(axis, v) * sin(angle)
+///   + axis * dot(axis, v) * (1 - cos(angle))
+/// ```
+///
+/// The axis is assumed to be normalized; the result is undefined otherwise.
+///
+/// [Rodrigues' rotation formula]: https://en.wikipedia.org/wiki/Rodrigues%27_rotation_formula
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotateVec3Node {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for RotateVec3Node {
+    fn default() -> Self {
+        let vec3 = Some(ValueType::Vector(VectorType::VEC3F));
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("v", vec3),
+                SlotDef::input("axis", vec3),
+                SlotDef::input("angle", Some(ValueType::Scalar(ScalarType::Float))),
+                SlotDef::output("result", vec3),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for RotateVec3Node {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let v = inputs.next().unwrap();
+        let axis = inputs.next().unwrap();
+        let angle = inputs.next().unwrap();
+        let cos = module.cos(angle);
+        let sin = module.sin(angle);
+        let v_cos = module.mul(v, cos);
+        let cross = module.cross(axis, v);
+        let cross_sin = module.mul(cross, sin);
+        let dot = module.dot(axis, v);
+        let one = module.lit(1.);
+        let one_minus_cos = module.sub(one, cos);
+        let dot_one_minus_cos = module.mul(dot, one_minus_cos);
+        let axis_dot = module.mul(axis, dot_one_minus_cos);
+        let sum = module.add(v_cos, cross_sin);
+        let result = module.add(sum, axis_dot);
+        Ok(vec![result])
+    }
+}
+
+/// Graph node to calculate the distance between two points.
+///
+/// The output is always a floating point scalar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistanceNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for DistanceNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("a", None),
+                SlotDef::input("b", None),
+                SlotDef::output("result", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for DistanceNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let a = inputs.next().unwrap();
+        let b = inputs.next().unwrap();
+        let distance = module.distance(a, b);
+        Ok(vec![distance])
+    }
+}
+
+/// Graph node to calculate the length of a vector.
+///
+/// The output is always a floating point scalar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LengthNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for LengthNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("in", None),
+                SlotDef::output("out", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for LengthNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let length = module.length(input);
+        Ok(vec![length])
+    }
+}
+
+/// Graph node to get the minimum of two values, component-wise for vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for MinNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for MinNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let min = module.min(left, right);
+        Ok(vec![min])
+    }
+}
+
+/// Graph node to get the maximum of two values, component-wise for vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaxNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for MaxNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for MaxNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let max = module.max(left, right);
+        Ok(vec![max])
+    }
+}
+
+/// Graph node to get the smooth minimum of two values.
+///
+/// The smooth minimum blends the two values where they are close to each
+/// other, instead of switching abruptly from one to the other like
+/// [`MinNode`]. This is commonly used to smoothly merge distance fields, for
+/// example for metaball-like shapes. The output is the polynomial smooth
+/// minimum:
+///
+/// ```txt
+/// let h = clamp(0.5 + 0.5 * (b - a) / k, 0.0, 1.0);
+/// result = mix(b, a, h) - k * h * (1.0 - h);
+/// ```
+///
+/// where `k` is the blending radius; larger values produce a softer blend.
+/// `k` must be strictly positive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmoothMinNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SmoothMinNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("a", None),
+                SlotDef::input("b", None),
+                SlotDef::input("k", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SmoothMinNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let a = inputs.next().unwrap();
+        let b = inputs.next().unwrap();
+        let k = inputs.next().unwrap();
+        let zero = module.lit(0.);
+        let half = module.lit(0.5);
+        let one = module.lit(1.);
+        let diff = module.sub(b, a);
+        let scaled = module.mul(half, diff);
+        let ratio = module.div(scaled, k);
+        let t = module.add(half, ratio);
+        let h = module.clamp(t, zero, one);
+        let blend = module.mix(b, a, h);
+        let one_minus_h = module.sub(one, h);
+        let k_h = module.mul(k, h);
+        let correction = module.mul(k_h, one_minus_h);
+        let result = module.sub(blend, correction);
+        Ok(vec![result])
+    }
+}
+
+/// Reduction operation applied by an [`AggregateNode`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AggregateOp {
+    /// Sum of all input values.
+    #[default]
+    Sum,
+    /// Product of all input values.
+    Product,
+    /// Minimum of all input values, component-wise for vectors.
+    Min,
+    /// Maximum of all input values, component-wise for vectors.
+    Max,
+}
+
+impl AggregateOp {
+    /// Get the binary operator combining two values of the reduction.
+    pub fn operator(&self) -> BinaryOperator {
+        match self {
+            AggregateOp::Sum => BinaryOperator::Add,
+            AggregateOp::Product => BinaryOperator::Mul,
+            AggregateOp::Min => BinaryOperator::Min,
+            AggregateOp::Max => BinaryOperator::Max,
+        }
+    }
+}
+
+/// Graph node to reduce any number of values into a single one.
+///
+/// The node has a single input slot accepting multiple links, and reduces all
+/// the linked values in link order with an [`AggregateOp`]. For example,
+/// summing three values `a`, `b`, and `c` produces `((a) + (b)) + (c)`. At
+/// least one value must be linked; a single value is passed through unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateNode {
+    /// Multi-link input and result output.
+    slots: [SlotDef; 2],
+    /// Reduction operation applied to the input values.
+    op: AggregateOp,
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for AggregateNode {
+    fn default() -> Self {
+        Self::new(AggregateOp::default())
+    }
+}
+
+impl AggregateNode {
+    /// Create a new node reducing its input values with the given operation.
+    pub fn new(op: AggregateOp) -> Self {
+        Self {
+            slots: [
+                SlotDef::input("in", None).with_multi_input(),
+                SlotDef::output("result", None),
+            ],
+            op,
+            label: None,
+        }
+    }
+
+    /// Get the reduction operation applied by the node.
+    pub fn op(&self) -> AggregateOp {
+        self.op
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for AggregateNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        let op = self.op.operator();
+        inputs
+            .into_iter()
+            .reduce(|acc, input| module.binary(op, acc, input))
+            .map(|result| vec![result])
+            .ok_or_else(|| {
+                GraphError::WrongInputCount {
+                    node: None,
+                    expected: 1,
+                    got: 0,
+                }
+                .into()
+            })
+    }
+}
+
+/// Graph node to compare a value against an edge.
+///
+/// The output is `1.0` if the "edge" input is less than or equal to the "x"
+/// input, and `0.0` otherwise, component-wise for vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for StepNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("edge", None),
+                SlotDef::input("x", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for StepNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let edge = inputs.next().unwrap();
+        let x = inputs.next().unwrap();
+        let step = module.step(edge, x);
+        Ok(vec![step])
+    }
+}
+
+/// Graph node to smoothly step a value between two edges.
+///
+/// The output is the Hermite interpolation of the "x" input between the "edge0"
+/// and "edge1" inputs, clamped to \[0:1\].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmoothStepNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SmoothStepNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("edge0", None),
+                SlotDef::input("edge1", None),
+                SlotDef::input("x", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SmoothStepNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let edge0 = inputs.next().unwrap();
+        let edge1 = inputs.next().unwrap();
+        let x = inputs.next().unwrap();
+        let smoothstep = module.smoothstep(edge0, edge1, x);
+        Ok(vec![smoothstep])
+    }
+}
+
+/// Graph node to select between two values based on a condition.
+///
+/// The output is the "if_true" input if the "cond" input is `true`, or the
+/// "if_false" input otherwise. If "cond" is a boolean vector, the selection
+/// applies component-wise.
+///
+/// The "cond" input is expected to be a boolean expression, and the "if_true"
+/// and "if_false" inputs to share the same type, which is also the type of the
+/// output. [`Graph::validate()`] infers that type, and reports an error if the
+/// "if_true" and "if_false" inputs have different types. Unlike arithmetic
+/// nodes, a scalar input is not promoted to the type of a vector input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SelectNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("cond", None),
+                SlotDef::input("if_true", None),
+                SlotDef::input("if_false", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SelectNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        // The condition doesn't contribute to the type of the result.
+        Ok(vec![same_types(input_types.get(1..).unwrap_or_default())?])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let cond = inputs.next().unwrap();
+        let if_true = inputs.next().unwrap();
+        let if_false = inputs.next().unwrap();
+        // WGSL's select() takes the false value first, and the condition last.
+        let select = module.select(if_false, if_true, cond);
+        Ok(vec![select])
+    }
+}
+
+/// Graph node to rearrange the components of a vector value.
+///
+/// The node is created with a swizzle mask like `xyz` or `wzyx`, which
+/// determines the components of the output value. See [`SwizzleExpr`] for
+/// details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwizzleNode {
+    /// The swizzle mask.
+    mask: String,
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl SwizzleNode {
+    /// Create a new swizzle node with the given mask.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ExprError::SyntaxError`] if the mask is invalid. See
+    /// [`SwizzleExpr::validate_mask()`] for details.
+    pub fn new(mask: impl Into<String>) -> Result<Self, ExprError> {
+        let mask = mask.into();
+        SwizzleExpr::validate_mask(&mask)?;
+        Ok(Self {
+            label: None,
+            mask,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        })
+    }
+
+    /// Get the swizzle mask of this node.
+    pub fn mask(&self) -> &str {
+        &self.mask
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SwizzleNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        let elem_type = match input_types.first().copied().flatten() {
+            Some(ValueType::Scalar(s)) => s,
+            Some(ValueType::Vector(v)) => v.elem_type(),
+            _ => return Ok(vec![None]),
+        };
+        Ok(vec![match self.mask.len() {
+            1 => Some(ValueType::Scalar(elem_type)),
+            count @ 2..=4 => Some(ValueType::Vector(VectorType::new(elem_type, count as u8))),
+            _ => None,
+        }])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let swizzle = module.swizzle(input, &self.mask)?;
+        Ok(vec![swizzle])
+    }
+}
+
+/// Graph node to linearly remap a value from one range to another.
+///
+/// The output is `out_min + (value - in_min) * (out_max - out_min) / (in_max -
+/// in_min)`. The value is not clamped, so values outside of the input range are
+/// remapped outside of the output range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemapNode {
+    slots: [SlotDef; 6],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for RemapNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("value", None),
+                SlotDef::input("in_min", None),
+                SlotDef::input("in_max", None),
+                SlotDef::input("out_min", None),
+                SlotDef::input("out_max", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for RemapNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 5 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 5,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let value = inputs.next().unwrap();
+        let in_min = inputs.next().unwrap();
+        let in_max = inputs.next().unwrap();
+        let out_min = inputs.next().unwrap();
+        let out_max = inputs.next().unwrap();
+        let offset = module.sub(value, in_min);
+        let out_range = module.sub(out_max, out_min);
+        let scaled = module.mul(offset, out_range);
+        let in_range = module.sub(in_max, in_min);
+        let ratio = module.div(scaled, in_range);
+        let remap = module.add(out_min, ratio);
+        Ok(vec![remap])
+    }
+}
+
+/// Graph node to get the fractional part of a value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FractNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for FractNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for FractNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let fract = module.fract(input);
+        Ok(vec![fract])
+    }
+}
+
+/// Graph node to round a value down to the closest integral number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FloorNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for FloorNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for FloorNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let floor = module.floor(input);
+        Ok(vec![floor])
+    }
+}
+
+/// Graph node to round a value up to the closest integral number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeilNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for CeilNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for CeilNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let ceil = module.ceil(input);
+        Ok(vec![ceil])
+    }
+}
+
+/// Graph node to round a value to the closest integral number.
+///
+/// Halfway cases are rounded to the nearest even integral number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for RoundNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for RoundNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let round = module.round(input);
+        Ok(vec![round])
+    }
+}
+
+/// Graph node to quantize a value into a number of discrete steps.
+///
+/// The output is `floor(value * steps) / steps`, which snaps the value down to
+/// the closest multiple of `1 / steps`. For example with 4 steps, values in
+/// \[0:1\) are mapped to 0, 0.25, 0.5, or 0.75. This gives a stepped look to
+/// smooth animations. For vectors, each component is quantized separately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizeNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for QuantizeNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("value", None),
+                SlotDef::input("steps", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for QuantizeNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let value = inputs.next().unwrap();
+        let steps = inputs.next().unwrap();
+        let scaled = module.mul(value, steps);
+        let floor = module.floor(scaled);
+        let result = module.div(floor, steps);
+        Ok(vec![result])
+    }
+}
+
+/// Graph node to clamp a value between 0 and 1.
+///
+/// This is equivalent to a [`ClampNode`] with bounds 0 and 1, without the need
+/// to link the bounds. For vectors, each component is clamped separately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaturateNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SaturateNode {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SaturateNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let saturate = module.saturate(input);
+        Ok(vec![saturate])
+    }
+}
+
+/// Graph node to calculate the remainder of the division of two values.
+///
+/// This uses the WGSL `%` operator, which for floating-point values is a truncated
+/// remainder and takes the sign of the "lhs" input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for ModNode {
+    fn default() -> Self {
+        Self {
+            label: None,
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for ModNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        Ok(vec![promote_types(input_types)?])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let rem = module.rem(left, right);
+        Ok(vec![rem])
+    }
+}
+
+/// Graph node to produce a constant value.
+///
+/// The node has a single output slot "value", whose type is the type of the
+/// constant value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstantNode {
+    /// The constant value.
+    value: Value,
+    /// The output slot corresponding to the constant value.
+    slots: [SlotDef; 1],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for ConstantNode {
+    fn default() -> Self {
+        Self::new(0.)
+    }
+}
+
+impl ConstantNode {
+    /// Create a new constant node for the given value.
+    pub fn new(value: impl Into<Value>) -> Self {
+        let value = value.into();
+        Self {
+            label: None,
+            value,
+            slots: [SlotDef::output("value", Some(value.value_type()))],
+        }
+    }
+
+    /// Create a new constant node for a floating-point scalar value.
+    pub fn scalar(value: f32) -> Self {
+        Self::new(value)
+    }
+
+    /// Create a new constant node for a 2-component floating-point vector.
+    pub fn vec2(value: Vec2) -> Self {
+        Self::new(value)
+    }
+
+    /// Create a new constant node for a 3-component floating-point vector.
+    pub fn vec3(value: Vec3) -> Self {
+        Self::new(value)
+    }
+
+    /// Create a new constant node for a 4-component floating-point vector.
+    pub fn vec4(value: Vec4) -> Self {
+        Self::new(value)
+    }
+
+    /// Get the constant value of this node.
+    pub fn value(&self) -> Value {
+        self.value
+    }
+
+    /// Set the constant value of this node.
+    ///
+    /// This also updates the type of the output slot of the node. Note that if
+    /// the node was already added to a [`Graph`], the graph keeps the slot
+    /// type the node had when added.
+    pub fn set_value(&mut self, value: impl Into<Value>) {
+        let value = value.into();
+        self.value = value;
+        self.slots = [SlotDef::output("value", Some(value.value_type()))];
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for ConstantNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let value = module.lit(self.value);
+        Ok(vec![value])
+    }
+}
+
+/// Graph node to read the value of an effect property.
+///
+/// Properties are declared on the [`Module`] with [`Module::add_property()`],
+/// and can be updated at runtime. The node has a single output slot "value"
+/// with the type of the property.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyNode {
+    /// The property to read.
+    property: PropertyHandle,
+    /// The output slot corresponding to the property value.
+    slots: [SlotDef; 1],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl PropertyNode {
+    /// Create a new property node for the given property and its value type.
+    pub fn new(property: PropertyHandle, value_type: ValueType) -> Self {
+        Self {
+            label: None,
+            property,
+            slots: [SlotDef::output("value", Some(value_type))],
+        }
+    }
+
+    /// Create a new property node for a property of the given module.
+    ///
+    /// The value type of the node is the type of the default value of the
+    /// property. Returns `None` if the property doesn't exist in the module.
+    pub fn from_module(module: &Module, property: PropertyHandle) -> Option<Self> {
+        module
+            .get_property(property)
+            .map(|prop| Self::new(property, prop.value_type()))
+    }
+
+    /// Get the property this node reads.
+    pub fn property(&self) -> PropertyHandle {
+        self.property
+    }
+
+    /// Get the value type of the property this node reads.
+    pub fn value_type(&self) -> ValueType {
+        self.slots[0].value_type().unwrap()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for PropertyNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if !inputs.is_empty() {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 0,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let prop = module.prop(self.property);
+        Ok(vec![prop])
+    }
+}
+
+/// Graph node to check if a value is less than another value.
+///
+/// For vectors, the values are compared component-wise, and the output is a
+/// bool vector of the same rank. Both inputs must have the same type; a scalar
+/// is not promoted to a vector. The output type is left variant (`None`) since
+/// it depends on the type of the inputs, and is inferred and checked by
+/// [`Graph::validate()`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LessThanNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for LessThanNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for LessThanNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        Ok(vec![same_types(input_types)?.and_then(comparison_type)])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let lt = module.lt(left, right);
+        Ok(vec![lt])
+    }
+}
+
+/// Graph node to check if a value is greater than another value.
+///
+/// For vectors, the values are compared component-wise, and the output is a
+/// bool vector of the same rank. Both inputs must have the same type; a scalar
+/// is not promoted to a vector. The output type is left variant (`None`) since
+/// it depends on the type of the inputs, and is inferred and checked by
+/// [`Graph::validate()`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GreaterThanNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for GreaterThanNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for GreaterThanNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        Ok(vec![same_types(input_types)?.and_then(comparison_type)])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let gt = module.gt(left, right);
+        Ok(vec![gt])
+    }
+}
+
+/// Graph node to check if a value is equal to another value.
+///
+/// For vectors, the values are compared component-wise, and the output is a
+/// bool vector of the same rank. Both inputs must have the same type; a scalar
+/// is not promoted to a vector. The output type is left variant (`None`) since
+/// it depends on the type of the inputs, and is inferred and checked by
+/// [`Graph::validate()`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EqualNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for EqualNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for EqualNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn infer_output_types(
+        &self,
+        input_types: &[Option<ValueType>],
+    ) -> Result<Vec<Option<ValueType>>, GraphError> {
+        Ok(vec![same_types(input_types)?.and_then(comparison_type)])
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let equal = module.equal(left, right);
+        Ok(vec![equal])
+    }
+}
+
+/// Graph node to calculate the logical "and" of two boolean values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AndNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for AndNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("lhs", Some(ValueType::Scalar(ScalarType::Bool))),
+                SlotDef::input("rhs", Some(ValueType::Scalar(ScalarType::Bool))),
+                SlotDef::output("result", Some(ValueType::Scalar(ScalarType::Bool))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for AndNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let and = module.and(left, right);
+        Ok(vec![and])
+    }
+}
+
+/// Graph node to calculate the logical "or" of two boolean values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for OrNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("lhs", Some(ValueType::Scalar(ScalarType::Bool))),
+                SlotDef::input("rhs", Some(ValueType::Scalar(ScalarType::Bool))),
+                SlotDef::output("result", Some(ValueType::Scalar(ScalarType::Bool))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for OrNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let left = inputs.next().unwrap();
+        let right = inputs.next().unwrap();
+        let or = module.or(left, right);
+        Ok(vec![or])
+    }
+}
+
+/// Graph node to calculate the logical negation of a boolean value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for NotNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("in", Some(ValueType::Scalar(ScalarType::Bool))),
+                SlotDef::output("out", Some(ValueType::Scalar(ScalarType::Bool))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for NotNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let not = module.not(input);
+        Ok(vec![not])
+    }
+}
+
+/// Graph node to blend between two colors.
+///
+/// The output is the linear interpolation of the two colors `color_a` and
+/// `color_b` by the blend factor `t`:
+///
+/// ```txt
+/// mix(color_a, color_b, saturate(t))
+/// ```
+///
+/// The blend factor is clamped to \[0:1\], so the output is always between
+/// the two colors, unlike with a [`LerpNode`] which extrapolates. This is a
+/// lightweight alternative to a [`CurveNode`] sampling a color gradient with
+/// only two keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorRampNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for ColorRampNode {
+    fn default() -> Self {
+        let color = Some(ValueType::Vector(VectorType::VEC4F));
+        Self {
+            slots: [
+                SlotDef::input("color_a", color),
+                SlotDef::input("color_b", color),
+                SlotDef::input("t", Some(ValueType::Scalar(ScalarType::Float))),
+                SlotDef::output("color", color),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for ColorRampNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let color_a = inputs.next().unwrap();
+        let color_b = inputs.next().unwrap();
+        let t = inputs.next().unwrap();
+        let t = module.saturate(t);
+        let color = module.mix(color_a, color_b, t);
+        Ok(vec![color])
+    }
+}
+
+/// Graph node to convert a color from HSV to RGB.
+///
+/// The hue `h`, saturation `s`, and value `v` inputs are all in \[0:1\], with a
+/// hue of 0 or 1 corresponding to red. The output is the RGB color, with each
+/// component in \[0:1\]:
+///
+/// ```txt
+/// let c = saturate(abs((h * 6 + vec3(0, 4, 2)) % 6 - 3) - 1);
+/// rgb = v * mix(vec3(1), c, s);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HsvToRgbNode {
+    slots: [SlotDef; 4],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for HsvToRgbNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("h", Some(ValueType::Scalar(ScalarType::Float))),
+                SlotDef::input("s", Some(ValueType::Scalar(ScalarType::Float))),
+                SlotDef::input("v", Some(ValueType::Scalar(ScalarType::Float))),
+                SlotDef::output("rgb", Some(ValueType::Vector(VectorType::VEC3F))),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for HsvToRgbNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 3 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 3,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let h = inputs.next().unwrap();
+        let s = inputs.next().unwrap();
+        let v = inputs.next().unwrap();
+        let six = module.lit(6.);
+        let h6 = module.mul(h, six);
+        let offset = module.lit(Vec3::new(0., 4., 2.));
+        let h6 = module.add(h6, offset);
+        let h6 = module.rem(h6, six);
+        let three = module.lit(3.);
+        let c = module.sub(h6, three);
+        let c = module.abs(c);
+        let one = module.lit(1.);
+        let c = module.sub(c, one);
+        let c = module.saturate(c);
+        let white = module.lit(Vec3::ONE);
+        let c = module.mix(white, c, s);
+        let rgb = module.mul(v, c);
+        Ok(vec![rgb])
+    }
+}
+
+/// Type of noise generated by a [`NoiseNode`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NoiseType {
+    /// Value noise, interpolating random values defined on an integer
+    /// lattice. See [`UnaryOperator::ValueNoise`].
+    ///
+    /// [`UnaryOperator::ValueNoise`]: crate::graph::UnaryOperator::ValueNoise
+    #[default]
+    Value,
+    /// Simplex noise, a gradient noise with fewer directional artifacts than
+    /// value noise. See [`UnaryOperator::SimplexNoise`].
+    ///
+    /// [`UnaryOperator::SimplexNoise`]: crate::graph::UnaryOperator::SimplexNoise
+    Simplex,
+}
+
+/// Graph node to sample a noise at a given position.
+///
+/// The node takes a `vec2<f32>` or `vec3<f32>` position as input, and outputs
+/// the scalar value of the noise at that position, in \[-1:1\]. The WGSL
+/// noise functions are defined by Hanabi, and available in all shaders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoiseNode {
+    /// Input position and output noise value.
+    slots: [SlotDef; 2],
+    /// Type of noise.
+    noise_type: NoiseType,
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for NoiseNode {
+    fn default() -> Self {
+        Self::new(NoiseType::default())
+    }
+}
+
+impl NoiseNode {
+    /// Create a new node sampling the given type of noise.
+    pub fn new(noise_type: NoiseType) -> Self {
+        Self {
+            slots: [
+                SlotDef::input("pos", None),
+                SlotDef::output("value", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+            noise_type,
+            label: None,
+        }
+    }
+
+    /// Get the type of noise sampled by the node.
+    pub fn noise_type(&self) -> NoiseType {
+        self.noise_type
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for NoiseNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let pos = inputs[0];
+        let value = match self.noise_type {
+            NoiseType::Value => module.value_noise(pos),
+            NoiseType::Simplex => module.simplex_noise(pos),
+        };
+        Ok(vec![value])
+    }
+}
+
+/// Graph node to sample a noise by triplanar projection.
+///
+/// The node samples a 2D noise three times, with the position projected onto
+/// the YZ, ZX, and XY planes, and blends the three samples with weights
+/// derived from the normal of the surface at that position:
+///
+/// ```txt
+/// let w = abs(normal) / (abs(normal.x) + abs(normal.y) + abs(normal.z));
+/// value = noise(pos.yz) * w.x + noise(pos.zx) * w.y + noise(pos.xy) * w.z
+/// ```
+///
+/// Each projection is mostly used where the normal faces its plane, which
+/// avoids the stretching a single projection produces on the faces parallel
+/// to it. This gives volumetric-looking variations, for example for smoke.
+/// The normal doesn't need to be normalized, but must not be zero. The output
+/// value is in \[-1:1\], like for a [`NoiseNode`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriplanarNoiseNode {
+    /// Input position and normal, and output noise value.
+    slots: [SlotDef; 3],
+    /// Type of noise.
+    noise_type: NoiseType,
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for TriplanarNoiseNode {
+    fn default() -> Self {
+        Self::new(NoiseType::default())
+    }
+}
+
+impl TriplanarNoiseNode {
+    /// Create a new node sampling the given type of noise.
+    pub fn new(noise_type: NoiseType) -> Self {
+        let vec3 = Some(ValueType::Vector(VectorType::VEC3F));
+        Self {
+            slots: [
+                SlotDef::input("pos", vec3),
+                SlotDef::input("normal", vec3),
+                SlotDef::output("value", Some(ValueType::Scalar(ScalarType::Float))),
+            ],
+            noise_type,
+            label: None,
+        }
+    }
+
+    /// Get the type of noise sampled by the node.
+    pub fn noise_type(&self) -> NoiseType {
+        self.noise_type
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for TriplanarNoiseNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let pos = inputs.next().unwrap();
+        let normal = inputs.next().unwrap();
+
+        let abs_normal = module.abs(normal);
+        let x = module.x(abs_normal);
+        let y = module.y(abs_normal);
+        let z = module.z(abs_normal);
+        let sum = module.add(x, y);
+        let sum = module.add(sum, z);
+        let weights = module.div(abs_normal, sum);
+
+        let mut value = None;
+        for (mask, component) in [("yz", "x"), ("zx", "y"), ("xy", "z")] {
+            // Swizzles don't have a static type, which the noise operators
+            // need to select their overload, so cast to an explicit vec2.
+            let plane_pos = module.swizzle(pos, mask)?;
+            let plane_pos = module.cast(plane_pos, VectorType::VEC2F);
+            let sample = match self.noise_type {
+                NoiseType::Value => module.value_noise(plane_pos),
+                NoiseType::Simplex => module.simplex_noise(plane_pos),
+            };
+            let weight = module.swizzle(weights, component)?;
+            let weighted = module.mul(sample, weight);
+            value = Some(match value {
+                Some(value) => module.add(value, weighted),
+                None => weighted,
+            });
+        }
+        Ok(vec![value.unwrap()])
+    }
+}
+
+/// Graph node to compute the natural exponentiation of a value, `e^x`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for ExpNode {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for ExpNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let exp = module.exp(input);
+        Ok(vec![exp])
+    }
+}
+
+/// Graph node to compute the base-2 exponentiation of a value, `2^x`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exp2Node {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for Exp2Node {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for Exp2Node {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let exp2 = module.exp2(input);
+        Ok(vec![exp2])
+    }
+}
+
+/// Graph node to compute the natural logarithm of a value.
+///
+/// The value must be strictly positive; the result is undefined otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for LogNode {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for LogNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let log = module.log(input);
+        Ok(vec![log])
+    }
+}
+
+/// Graph node to compute the base-2 logarithm of a value.
+///
+/// The value must be strictly positive; the result is undefined otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Log2Node {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for Log2Node {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for Log2Node {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let log2 = module.log2(input);
+        Ok(vec![log2])
+    }
+}
+
+/// Graph node to compute the square root of a value.
+///
+/// The value must be positive or zero; the result is undefined otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqrtNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for SqrtNode {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for SqrtNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let sqrt = module.sqrt(input);
+        Ok(vec![sqrt])
+    }
+}
+
+/// Graph node to compute the inverse square root of a value, `1 / sqrt(x)`.
+///
+/// The value must be strictly positive; the result is undefined otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InverseSqrtNode {
+    /// Input and output values.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for InverseSqrtNode {
+    fn default() -> Self {
+        Self {
+            slots: [SlotDef::input("in", None), SlotDef::output("out", None)],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for InverseSqrtNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let input = inputs.into_iter().next().unwrap();
+        let inverse_sqrt = module.inverse_sqrt(input);
+        Ok(vec![inverse_sqrt])
+    }
+}
+
+/// Graph node to oscillate a value back and forth.
+///
+/// The output is a triangle wave of the input `t`, ramping up from 0 to
+/// `length` then back down to 0 every `2 * length`:
+///
+/// ```txt
+/// length - abs((t % (2 * length)) - length)
+/// ```
+///
+/// The `length` must be strictly positive. The input `t` is typically a time
+/// value; note that the WGSL `%` operator takes the sign of its left operand,
+/// so `t` should be positive too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingPongNode {
+    slots: [SlotDef; 3],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl Default for PingPongNode {
+    fn default() -> Self {
+        Self {
+            slots: [
+                SlotDef::input("t", None),
+                SlotDef::input("length", None),
+                SlotDef::output("result", None),
+            ],
+            label: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for PingPongNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 2 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 2,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let mut inputs = inputs.into_iter();
+        let t = inputs.next().unwrap();
+        let length = inputs.next().unwrap();
+        let two = module.lit(2.);
+        let period = module.mul(two, length);
+        let t = module.rem(t, period);
+        let t = module.sub(t, length);
+        let t = module.abs(t);
+        let result = module.sub(length, t);
+        Ok(vec![result])
+    }
+}
+
+/// Graph node to sample a curve.
+///
+/// The node holds a [`Curve`] of scalar or vector floating-point values, and
+/// samples it at the position given by its `t` input, linearly interpolating
+/// between the keys of the curve. The type of the `value` output is the value
+/// type of the curve. On evaluation, the curve is added to the [`Module`] with
+/// [`Module::add_curve()`], and the node produces a [`CurveSampleExpr`]
+/// referencing it.
+///
+/// [`CurveSampleExpr`]: crate::graph::expr::CurveSampleExpr
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveNode {
+    /// The curve to sample.
+    curve: Curve,
+    /// Sampling position and sampled value.
+    slots: [SlotDef; 2],
+    /// Optional label identifying this node instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl CurveNode {
+    /// Create a new curve node sampling the given curve.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the curve has no key.
+    pub fn new(curve: impl Into<Curve>) -> Self {
+        let curve = curve.into();
+        assert!(!curve.is_empty(), "Cannot sample a curve without any key.");
+        let value_type = curve.value_type();
+        Self {
+            curve,
+            slots: [
+                SlotDef::input("t", Some(ValueType::Scalar(ScalarType::Float))),
+                SlotDef::output("value", Some(value_type)),
+            ],
+            label: None,
+        }
+    }
+
+    /// Get the curve sampled by this node.
+    pub fn curve(&self) -> &Curve {
+        &self.curve
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Node for CurveNode {
+    fn slots(&self) -> &[SlotDef] {
+        &self.slots
+    }
+
+    fn boxed_clone(&self) -> BoxedNode {
+        Box::new(self.clone())
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn eval(
+        &self,
+        module: &mut Module,
+        inputs: Vec<ExprHandle>,
+    ) -> Result<Vec<ExprHandle>, ExprError> {
+        if inputs.len() != 1 {
+            return Err(GraphError::WrongInputCount {
+                node: None,
+                expected: 1,
+                got: inputs.len(),
+            }
+            .into());
+        }
+        let t = inputs.into_iter().next().unwrap();
+        let curve = module.add_curve(self.curve.clone());
+        let value = module.sample_curve(curve, t);
+        Ok(vec![value])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{
+        ArrayType, EvalContext, Expr, Gradient, LiteralExpr, ModifierContext, ParticleLayout,
+        Property, PropertyLayout, ScalarValue, ShaderWriter, ToWgslString, UnaryOperator,
+    };
+
+    #[test]
+    fn add() {
+        let node = AddNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let two = module.lit(2.);
+        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let out = outputs[0];
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, out).unwrap();
+        assert_eq!(str, "(3.) + (2.)".to_string());
+    }
+
+    #[test]
+    fn sub() {
+        let node = SubNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let two = module.lit(2.);
+        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let out = outputs[0];
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, out).unwrap();
+        assert_eq!(str, "(3.) - (2.)".to_string());
+    }
+
+    #[test]
+    fn mul() {
+        let node = MulNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let two = module.lit(2.);
+        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let out = outputs[0];
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, out).unwrap();
+        assert_eq!(str, "(3.) * (2.)".to_string());
+    }
+
+    #[test]
+    fn div() {
+        let node = DivNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let two = module.lit(2.);
+        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let out = outputs[0];
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, out).unwrap();
+        assert_eq!(str, "(3.) / (2.)".to_string());
+    }
+
+    #[test]
+    fn pow() {
+        let node = PowNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let two = module.lit(2.);
+        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let out = outputs[0];
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, out).unwrap();
+        assert_eq!(str, "pow(3., 2.)".to_string());
+    }
+
+    #[test]
+    fn attr() {
+        let node = AttributeNode::new(Attribute::POSITION);
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let out = outputs[0];
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, out).unwrap();
+        assert_eq!(str, format!("particle.{}", Attribute::POSITION.name()));
+    }
+
+    #[test]
+    fn derivative() {
+        let node = DerivativeNode::new(Attribute::POSITION, Attribute::F32X3_3);
+        assert_eq!(node.attribute(), Attribute::POSITION);
+        assert_eq!(node.storage(), Attribute::F32X3_3);
+        assert_eq!(
+            node.attributes(),
+            &[Attribute::POSITION, Attribute::F32X3_3]
+        );
+        assert_eq!(node.slots()[0].name(), "derivative");
+        assert_eq!(
+            node.slots()[0].value_type(),
+            Some(ValueType::Vector(VectorType::VEC3F))
+        );
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "((particle.position) - (particle.f32x3_3)) / (sim_params.delta_time)"
+        );
+
+        // The store modifier copies the current value into the storage.
+        let modifier = node.store_modifier(&mut module);
+        assert_eq!(modifier.attribute, Attribute::F32X3_3);
+        let str = context.eval(&module, modifier.value).unwrap();
+        assert_eq!(str, "particle.position");
+    }
+
+    #[test]
+    fn debug_tap() {
+        let node = DebugTapNode::new(Attribute::F32X3_0);
+        assert_eq!(node.attribute(), Attribute::F32X3_0);
+        assert_eq!(
+            node.slots()[0].value_type(),
+            Some(ValueType::Vector(VectorType::VEC3F))
+        );
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let pos = module.attr(Attribute::POSITION);
+        let outputs = node.eval(&mut module, vec![pos]).unwrap();
+        assert_eq!(outputs, vec![pos]);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "particle.position");
+    }
+
+    #[test]
+    fn time() {
+        let node = TimeNode::default();
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 2);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str0 = context.eval(&module, outputs[0]).unwrap();
+        let str1 = context.eval(&module, outputs[1]).unwrap();
+        assert_eq!(str0, format!("sim_params.{}", BuiltInOperator::Time.name()));
+        assert_eq!(
+            str1,
+            format!("sim_params.{}", BuiltInOperator::DeltaTime.name())
+        );
+    }
+
+    #[test]
+    fn count() {
+        let node = CountNode::default();
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 2);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str0 = context.eval(&module, outputs[0]).unwrap();
+        let str1 = context.eval(&module, outputs[1]).unwrap();
+        assert_eq!(str0, "particle_count");
+        assert_eq!(str1, "alive_count");
+    }
+
+    #[test]
+    fn global() {
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        for (global_type, name, code) in [
+            (
+                GlobalType::EffectOrigin,
+                "effect_origin",
+                "vec3<f32>(spawner.transform[0].w, spawner.transform[1].w, spawner.transform[2].w)",
+            ),
+            (
+                GlobalType::CameraPosition,
+                "camera_position",
+                "view.world_from_view[3].xyz",
+            ),
+        ] {
+            let node = GlobalNode::new(global_type);
+            assert_eq!(node.global_type(), global_type);
+            assert_eq!(node.slots().len(), 1);
+            assert_eq!(node.slots()[0].name(), name);
+            assert_eq!(
+                node.slots()[0].value_type(),
+                Some(ValueType::Vector(VectorType::VEC3F))
+            );
+
+            let mut module = Module::default();
+            let one = module.lit(1.);
+            let ret = node.eval(&mut module, vec![one]);
+            assert!(matches!(
+                ret,
+                Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+            ));
+            let outputs = node.eval(&mut module, vec![]).unwrap();
+            assert_eq!(outputs.len(), 1);
+            let mut context =
+                ShaderWriter::new(ModifierContext::Render, &property_layout, &particle_layout);
+            let str = context.eval(&module, outputs[0]).unwrap();
+            assert_eq!(str, code);
+        }
+    }
+
+    #[test]
+    fn oscillator() {
+        let node = OscillatorNode::default();
+        assert_eq!(node.slots()[0].default_value(), Some(1_f32.into()));
+        assert_eq!(node.slots()[1].default_value(), Some(1_f32.into()));
+        assert_eq!(node.slots()[2].default_value(), Some(0_f32.into()));
+
+        let mut module = Module::default();
+
+        let frequency = module.lit(2.);
+        let amplitude = module.lit(3.);
+        let phase = module.lit(0.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![frequency, amplitude]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node
+            .eval(&mut module, vec![frequency, amplitude, phase])
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(3.) * (sin(((sim_params.time) * (2.)) + (0.5)))");
+
+        // All inputs have a default value when evaluated in a graph.
+        let mut g = Graph::new();
+        g.add_node(OscillatorNode::default());
+        let outputs = g.evaluate(&mut module).unwrap();
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(1.) * (sin(((sim_params.time) * (1.)) + (0.)))");
+    }
+
+    #[test]
+    fn phase() {
+        let node = PhaseNode::default();
+        assert_eq!(node.slots()[0].default_value(), Some(1_f32.into()));
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let period = module.lit(10.);
+        let outputs = node.eval(&mut module, vec![period]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "fract((sim_params.time) / (10.))");
+
+        // The period defaults to one second when evaluated in a graph.
+        let mut g = Graph::new();
+        g.add_node(PhaseNode::default());
+        let outputs = g.evaluate(&mut module).unwrap();
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "fract((sim_params.time) / (1.))");
+    }
+
+    #[test]
+    fn real_time() {
+        let node = RealTimeNode::default();
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 2);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str0 = context.eval(&module, outputs[0]).unwrap();
+        let str1 = context.eval(&module, outputs[1]).unwrap();
+        assert_eq!(
+            str0,
+            format!("sim_params.{}", BuiltInOperator::RealTime.name())
+        );
+        assert_eq!(
+            str1,
+            format!("sim_params.{}", BuiltInOperator::RealDeltaTime.name())
+        );
+    }
+
+    #[test]
+    fn random() {
+        let node = RandomNode::default();
+        assert_eq!(node.value_type(), ValueType::Scalar(ScalarType::Float));
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "var0");
+        assert_eq!(context.main_code, "let var0 = frand();\n");
+
+        let node = RandomNode::new(VectorType::VEC3F);
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "var1");
+        assert_eq!(
+            context.main_code,
+            "let var0 = frand();\nlet var1 = frand3();\n"
+        );
+    }
+
+    #[test]
+    fn random_range() {
+        let node = RandomRangeNode::new(VectorType::VEC3F);
+
+        let mut module = Module::default();
+
+        let min = module.lit(Vec3::ZERO);
+        let max = module.lit(Vec3::ONE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![min]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![min, max]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "mix(vec3<f32>(0.,0.,0.), vec3<f32>(1.,1.,1.), var0)");
+        assert_eq!(context.main_code, "let var0 = frand3();\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_invalid_type() {
+        let _ = RandomNode::new(ScalarType::Int);
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_range_invalid_type() {
+        let _ = RandomRangeNode::new(VectorType::VEC3I);
+    }
+
+    #[test]
+    fn hash() {
+        let node = HashNode::default();
+
+        let mut module = Module::default();
+
+        let seed = module.lit(42u32);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![seed]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "hash_float01(42u)".to_string());
+    }
+
+    #[test]
+    fn combine_vec3() {
+        let node = CombineVec3Node::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(1.);
+        let y = module.lit(2.);
+        let z = module.lit(3.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x, y]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![x, y, z]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "vec3(1., 2., 3.)".to_string());
+    }
+
+    #[test]
+    fn split_vec3() {
+        let node = SplitVec3Node::default();
+
+        let mut module = Module::default();
+
+        let xyz = module.attr(Attribute::POSITION);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![xyz, xyz]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![xyz]).unwrap();
+        assert_eq!(outputs.len(), 3);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str: Vec<_> = outputs
+            .iter()
+            .map(|&expr| context.eval(&module, expr).unwrap())
+            .collect();
+        assert_eq!(
+            str,
+            [
+                "particle.position.x",
+                "particle.position.y",
+                "particle.position.z"
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize() {
+        let node = NormalizeNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let ones = module.lit(Vec3::ONE);
+        let outputs = node.eval(&mut module, vec![ones]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "normalize(vec3<f32>(1.,1.,1.))".to_string());
+    }
+
+    #[test]
+    fn spherize() {
+        let node = SpherizeNode::default();
+
+        let mut module = Module::default();
+
+        let p = module.lit(Vec3::ONE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![p, p]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![p]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        let p = "vec3<f32>(1.,1.,1.)";
+        let p2 = format!("({p}) * ({p})");
+        assert_eq!(
+            str,
+            format!(
+                "({p}) * (sqrt(((1.) - (((({p2}).yzx) + (({p2}).zxy)) * (0.5))) + \
+                 (((({p2}).yzx) * (({p2}).zxy)) / (3.))))"
+            )
+        );
+    }
+
+    #[test]
+    fn sin() {
+        let node = SinNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let one = module.lit(1.);
+        let outputs = node.eval(&mut module, vec![one]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "sin(1.)".to_string());
+    }
+
+    #[test]
+    fn cos() {
+        let node = CosNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let one = module.lit(1.);
+        let outputs = node.eval(&mut module, vec![one]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "cos(1.)".to_string());
+    }
+
+    #[test]
+    fn tan() {
+        let node = TanNode::default();
+
+        let mut module = Module::default();
+
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let one = module.lit(1.);
+        let outputs = node.eval(&mut module, vec![one]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "tan(1.)".to_string());
+    }
+
+    #[test]
+    fn atan() {
+        let node = AtanNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(0.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "atan(0.5)".to_string());
+    }
+
+    #[test]
+    fn atan2() {
+        let node = Atan2Node::default();
+
+        let mut module = Module::default();
+
+        let y = module.lit(1.);
+        let x = module.lit(-1.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![y]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![y, x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "atan2(1., -1.)".to_string());
+    }
+
+    #[test]
+    fn polar_to_cartesian() {
+        let node = PolarToCartesianNode::default();
+
+        let mut module = Module::default();
+
+        let radius = module.lit(2.);
+        let angle = module.lit(0.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![radius]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![radius, angle]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "vec2((2.) * (cos(0.5)), (2.) * (sin(0.5)))");
+    }
+
+    #[test]
+    fn cartesian_to_polar() {
+        let node = CartesianToPolarNode::default();
+
+        let mut module = Module::default();
+
+        let xy = module.lit(Vec2::new(1., 2.));
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![xy, xy]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![xy]).unwrap();
+        assert_eq!(outputs.len(), 2);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "length(vec2<f32>(1.,2.))");
+        let str = context.eval(&module, outputs[1]).unwrap();
+        assert_eq!(str, "atan2(vec2<f32>(1.,2.).y, vec2<f32>(1.,2.).x)");
+    }
+
+    #[test]
+    fn clamp() {
+        let node = ClampNode::default();
+
+        let mut module = Module::default();
+
+        let value = module.lit(0.5);
+        let min = module.lit(0.);
+        let max = module.lit(1.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![value, min]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![value, min, max]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "clamp(0.5, 0., 1.)".to_string());
+    }
+
+    #[test]
+    fn lerp() {
+        let node = LerpNode::default();
+
+        let mut module = Module::default();
+
+        let a = module.lit(Vec3::ZERO);
+        let b = module.lit(Vec3::ONE);
+        let t = module.lit(0.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![a, b]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![a, b, t]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "mix(vec3<f32>(0.,0.,0.), vec3<f32>(1.,1.,1.), 0.5)".to_string()
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let node = DotNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::X);
+        let y = module.lit(Vec3::Y);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let slot = &node.slots()[2];
+        assert_eq!(
+            slot.value_type(),
+            Some(ValueType::Scalar(ScalarType::Float))
+        );
+
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "dot(vec3<f32>(1.,0.,0.), vec3<f32>(0.,1.,0.))".to_string()
+        );
+    }
+
+    #[test]
+    fn cross() {
+        let node = CrossNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::X);
+        let y = module.lit(Vec3::Y);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "cross(vec3<f32>(1.,0.,0.), vec3<f32>(0.,1.,0.))".to_string()
+        );
+    }
+
+    #[test]
+    fn reflect() {
+        let node = ReflectNode::default();
+
+        let mut module = Module::default();
+
+        let i = module.lit(Vec3::new(1., -1., 0.));
+        let n = module.lit(Vec3::Y);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![i]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![i, n]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "reflect(vec3<f32>(1.,-1.,0.), vec3<f32>(0.,1.,0.))".to_string()
+        );
+    }
+
+    #[test]
+    fn refract() {
+        let node = RefractNode::default();
+
+        let mut module = Module::default();
+
+        let i = module.lit(Vec3::NEG_Y);
+        let n = module.lit(Vec3::Y);
+        let eta = module.lit(0.75);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![i, n]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![i, n, eta]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "refract(vec3<f32>(0.,-1.,0.), vec3<f32>(0.,1.,0.), 0.75)".to_string()
+        );
+    }
+
+    #[test]
+    fn fresnel() {
+        let node = FresnelNode::default();
+
+        let mut module = Module::default();
+
+        let v = module.lit(Vec3::Z);
+        let n = module.lit(Vec3::Y);
+        let power = module.lit(5.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![v, n]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![v, n, power]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "pow((1.) - (saturate(dot(vec3<f32>(0.,0.,1.), vec3<f32>(0.,1.,0.)))), 5.)".to_string()
+        );
+    }
+
+    #[test]
+    fn rotate_vec3() {
+        let node = RotateVec3Node::default();
+
+        let mut module = Module::default();
+
+        let v = module.lit(Vec3::X);
+        let axis = module.lit(Vec3::Z);
+        let angle = module.lit(0.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![v, axis]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![v, axis, angle]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert!(str.contains("(cross(vec3<f32>(0.,0.,1.), vec3<f32>(1.,0.,0.))) * (sin(0.5))"));
+        assert!(
+            str.contains("(dot(vec3<f32>(0.,0.,1.), vec3<f32>(1.,0.,0.))) * ((1.) - (cos(0.5)))")
+        );
+        assert_eq!(
+            str,
+            "(((vec3<f32>(1.,0.,0.)) * (cos(0.5))) + ((cross(vec3<f32>(0.,0.,1.), vec3<f32>(1.,0.,0.))) * (sin(0.5)))) + ((vec3<f32>(0.,0.,1.)) * ((dot(vec3<f32>(0.,0.,1.), vec3<f32>(1.,0.,0.))) * ((1.) - (cos(0.5)))))"
+        );
+    }
+
+    #[test]
+    fn distance() {
+        let node = DistanceNode::default();
+
+        let mut module = Module::default();
+
+        let a = module.lit(Vec3::X);
+        let b = module.lit(Vec3::Y);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![a]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![a, b, b]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let slot = &node.slots()[2];
+        assert_eq!(
+            slot.value_type(),
+            Some(ValueType::Scalar(ScalarType::Float))
+        );
+
+        let outputs = node.eval(&mut module, vec![a, b]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "distance(vec3<f32>(1.,0.,0.), vec3<f32>(0.,1.,0.))".to_string()
+        );
+    }
+
+    #[test]
+    fn length() {
+        let node = LengthNode::default();
+
+        let mut module = Module::default();
+
+        let ones = module.lit(Vec3::ONE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let slot = &node.slots()[1];
+        assert_eq!(
+            slot.value_type(),
+            Some(ValueType::Scalar(ScalarType::Float))
+        );
+
+        let outputs = node.eval(&mut module, vec![ones]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "length(vec3<f32>(1.,1.,1.))".to_string());
+    }
+
+    #[test]
+    fn min() {
+        let node = MinNode::default();
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let two = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "min(3., 2.)".to_string());
+    }
+
+    #[test]
+    fn max() {
+        let node = MaxNode::default();
+
+        let mut module = Module::default();
+
+        let three = module.lit(3.);
+        let two = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![three]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "max(3., 2.)".to_string());
+    }
+
+    #[test]
+    fn smooth_min() {
+        let node = SmoothMinNode::default();
+
+        let mut module = Module::default();
+
+        let a = module.lit(1.);
+        let b = module.lit(2.);
+        let k = module.lit(0.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![a, b]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![a, b, k]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        let h = "clamp((0.5) + (((0.5) * ((2.) - (1.))) / (0.5)), 0., 1.)";
+        assert_eq!(
+            str,
+            format!("(mix(2., 1., {h})) - (((0.5) * ({h})) * ((1.) - ({h})))")
+        );
+    }
+
+    #[test]
+    fn aggregate() {
+        let node = AggregateNode::default();
+        assert_eq!(node.op(), AggregateOp::Sum);
+        assert!(node.slots()[0].allow_multi_input());
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        for (op, code) in [
+            (AggregateOp::Sum, "((1.) + (2.)) + (3.)"),
+            (AggregateOp::Product, "((1.) * (2.)) * (3.)"),
+            (AggregateOp::Min, "min(min(1., 2.), 3.)"),
+            (AggregateOp::Max, "max(max(1., 2.), 3.)"),
+        ] {
+            let node = AggregateNode::new(op);
+            let mut module = Module::default();
+            let ret = node.eval(&mut module, vec![]);
+            assert!(matches!(
+                ret,
+                Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+            ));
+            let inputs = vec![module.lit(1.), module.lit(2.), module.lit(3.)];
+            let outputs = node.eval(&mut module, inputs.clone()).unwrap();
+            assert_eq!(outputs.len(), 1);
+            let mut context =
+                ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+            let str = context.eval(&module, outputs[0]).unwrap();
+            assert_eq!(str, code);
+
+            // A single input is passed through.
+            let outputs = node.eval(&mut module, vec![inputs[0]]).unwrap();
+            assert_eq!(outputs, vec![inputs[0]]);
+        }
+    }
+
+    #[test]
+    fn step() {
+        let node = StepNode::default();
+
+        let mut module = Module::default();
+
+        let edge = module.lit(0.5);
+        let x = module.lit(1.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![edge]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![edge, x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "step(0.5, 1.)".to_string());
+    }
+
+    #[test]
+    fn smoothstep() {
+        let node = SmoothStepNode::default();
+
+        let mut module = Module::default();
+
+        let edge0 = module.lit(0.);
+        let edge1 = module.lit(1.);
+        let x = module.lit(0.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![edge0, edge1]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![edge0, edge1, x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "smoothstep(0., 1., 0.5)".to_string());
+    }
+
+    #[test]
+    fn select() {
+        let node = SelectNode::default();
+
+        let mut module = Module::default();
+
+        let cond = module.lit(true);
+        let if_true = module.lit(1.);
+        let if_false = module.lit(0.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![cond, if_true]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node
+            .eval(&mut module, vec![cond, if_true, if_false])
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "select(0., 1., true)".to_string());
+    }
+
+    #[test]
+    fn swizzle() {
+        let node = SwizzleNode::new("xyz").unwrap();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::new(1., 2., 3.));
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(vec3<f32>(1.,2.,3.)).xyz".to_string());
+    }
+
+    #[test]
+    fn swizzle_reverse() {
+        let node = SwizzleNode::new("zyx").unwrap();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::new(1., 2., 3.));
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(vec3<f32>(1.,2.,3.)).zyx".to_string());
+    }
+
+    #[test]
+    fn swizzle_invalid() {
+        assert!(matches!(
+            SwizzleNode::new("xq"),
+            Err(ExprError::SyntaxError(_))
+        ));
+        assert!(SwizzleNode::new("").is_err());
+        assert!(SwizzleNode::new("xyzwx").is_err());
+        assert_eq!(SwizzleNode::new("xxxx").unwrap().mask(), "xxxx");
+    }
+
+    #[test]
+    fn remap() {
+        let node = RemapNode::default();
+
+        let mut module = Module::default();
+
+        let value = module.lit(0.5);
+        let in_min = module.lit(0.);
+        let in_max = module.lit(1.);
+        let out_min = module.lit(2.);
+        let out_max = module.lit(4.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![value, in_min, in_max, out_min]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node
+            .eval(&mut module, vec![value, in_min, in_max, out_min, out_max])
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "(2.) + ((((0.5) - (0.)) * ((4.) - (2.))) / ((1.) - (0.)))".to_string()
+        );
+    }
+
+    #[test]
+    fn fract() {
+        let node = FractNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(1.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "fract(1.5)".to_string());
+    }
+
+    #[test]
+    fn floor() {
+        let node = FloorNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(1.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "floor(1.5)".to_string());
+    }
+
+    #[test]
+    fn ceil() {
+        let node = CeilNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(1.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "ceil(1.5)".to_string());
+    }
+
+    #[test]
+    fn round() {
+        let node = RoundNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(1.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "round(1.5)".to_string());
+    }
+
+    #[test]
+    fn quantize() {
+        let node = QuantizeNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(0.7);
+        let steps = module.lit(4.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, steps]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(floor((0.7) * (4.))) / (4.)".to_string());
+    }
+
+    #[test]
+    fn saturate() {
+        let node = SaturateNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(1.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "saturate(1.5)".to_string());
+    }
+
+    #[test]
+    fn rem() {
+        let node = ModNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(5.);
+        let y = module.lit(3.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(5.) % (3.)".to_string());
+    }
+
+    #[test]
+    fn constant() {
+        let node = ConstantNode::vec3(Vec3::new(1., 2., 3.));
+        assert_eq!(node.value(), Value::from(Vec3::new(1., 2., 3.)));
+        assert_eq!(
+            node.slots()[0].value_type(),
+            Some(ValueType::Vector(VectorType::VEC3F))
+        );
+        assert_eq!(
+            ConstantNode::scalar(1.).slots()[0].value_type(),
+            Some(ValueType::Scalar(ScalarType::Float))
+        );
+
+        let mut module = Module::default();
+        let x = module.lit(1.);
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        let lit = LiteralExpr::new(Vec3::new(1., 2., 3.));
+        assert_eq!(str, lit.to_wgsl_string());
+        assert_eq!(str, "vec3<f32>(1.,2.,3.)".to_string());
+    }
+
+    #[test]
+    fn property() {
+        let mut module = Module::default();
+        let handle = module.add_property("my_prop", 3.0.into());
+        let node = PropertyNode::from_module(&module, handle).unwrap();
+        assert_eq!(node.property(), handle);
+        assert_eq!(node.value_type(), ValueType::Scalar(ScalarType::Float));
+        assert_eq!(
+            node.slots()[0].value_type(),
+            Some(ValueType::Scalar(ScalarType::Float))
+        );
+
+        let x = module.lit(1.);
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+
+        let outputs = node.eval(&mut module, vec![]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout =
+            PropertyLayout::new(&[Property::new("my_prop", ScalarValue::Float(3.))]);
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "properties.my_prop".to_string());
+    }
+
+    #[test]
+    fn less_than() {
+        let node = LessThanNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::new(1., 2., 3.));
+        let y = module.lit(Vec3::ONE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "(vec3<f32>(1.,2.,3.)) < (vec3<f32>(1.,1.,1.))".to_string()
+        );
+    }
+
+    #[test]
+    fn greater_than() {
+        let node = GreaterThanNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::new(1., 2., 3.));
+        let y = module.lit(Vec3::ONE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "(vec3<f32>(1.,2.,3.)) > (vec3<f32>(1.,1.,1.))".to_string()
+        );
+    }
+
+    #[test]
+    fn equal() {
+        let node = EqualNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::new(1., 2., 3.));
+        let y = module.lit(Vec3::ONE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "(vec3<f32>(1.,2.,3.)) == (vec3<f32>(1.,1.,1.))".to_string()
+        );
+    }
+
+    #[test]
+    fn and() {
+        let node = AndNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(true);
+        let y = module.lit(false);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(true) && (false)".to_string());
+    }
+
+    #[test]
+    fn or() {
+        let node = OrNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(true);
+        let y = module.lit(false);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![x]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x, y]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(true) || (false)".to_string());
+    }
+
+    #[test]
+    fn not() {
+        let node = NotNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(true);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "!(true)".to_string());
+    }
+
+    #[test]
+    fn color_ramp() {
+        let node = ColorRampNode::default();
+
+        let mut module = Module::default();
+
+        let color_a = module.lit(Vec4::new(1., 0., 0., 1.));
+        let color_b = module.lit(Vec4::new(0., 0., 1., 0.5));
+        let t = module.lit(1.5);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![color_a, color_b]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![color_a, color_b, t]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "mix(vec4<f32>(1.,0.,0.,1.), vec4<f32>(0.,0.,1.,0.5), saturate(1.5))"
+        );
+    }
+
+    #[test]
+    fn hsv_to_rgb() {
+        let node = HsvToRgbNode::default();
+
+        let mut module = Module::default();
+
+        let h = module.lit(0.);
+        let s = module.lit(1.);
+        let v = module.lit(1.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![h, s]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![h, s, v]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "(1.) * (mix(vec3<f32>(1.,1.,1.), saturate((abs(((((0.) * (6.)) + (vec3<f32>(0.,4.,2.))) % (6.)) - (3.))) - (1.)), 1.))".to_string());
+    }
+
+    #[test]
+    fn noise() {
+        let node = NoiseNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec3::ONE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "value_noise_vec3(vec3<f32>(1.,1.,1.))".to_string());
+    }
+
+    #[test]
+    fn noise_simplex() {
+        let node = NoiseNode::new(NoiseType::Simplex);
+
+        let mut module = Module::default();
+
+        let x = module.lit(Vec2::ONE);
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "simplex_noise_vec2(vec2<f32>(1.,1.))".to_string());
+    }
+
+    #[test]
+    fn triplanar_noise() {
+        let node = TriplanarNoiseNode::default();
+        assert_eq!(node.noise_type(), NoiseType::Value);
+
+        let mut module = Module::default();
+
+        let pos = module.attr(Attribute::POSITION);
+        let normal = module.lit(Vec3::Y);
+        let ret = node.eval(&mut module, vec![pos]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![pos, normal]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str.matches("value_noise_vec2(").count(), 3);
+        let a = "abs(vec3<f32>(0.,1.,0.))";
+        let w = format!("({a}) / ((({a}.x) + ({a}.y)) + ({a}.z))");
+        let p = "particle.position";
+        assert_eq!(
+            str,
+            format!(
+                "(((value_noise_vec2(vec2<f32>(({p}).yz))) * (({w}).x)) + \
+                 ((value_noise_vec2(vec2<f32>(({p}).zx))) * (({w}).y))) + \
+                 ((value_noise_vec2(vec2<f32>(({p}).xy))) * (({w}).z))"
+            )
+        );
+    }
+
+    #[test]
+    fn exp() {
+        let node = ExpNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "exp(2.)".to_string());
+    }
+
+    #[test]
+    fn exp2() {
+        let node = Exp2Node::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "exp2(2.)".to_string());
+    }
+
+    #[test]
+    fn log() {
+        let node = LogNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "log(2.)".to_string());
+    }
+
+    #[test]
+    fn log2() {
+        let node = Log2Node::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "log2(2.)".to_string());
+    }
+
+    #[test]
+    fn sqrt() {
+        let node = SqrtNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "sqrt(2.)".to_string());
+    }
+
+    #[test]
+    fn inverse_sqrt() {
+        let node = InverseSqrtNode::default();
+
+        let mut module = Module::default();
+
+        let x = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![x]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(str, "inverseSqrt(2.)".to_string());
+    }
+
+    #[test]
+    fn ping_pong() {
+        let node = PingPongNode::default();
+
+        let mut module = Module::default();
+
+        let t = module.lit(3.);
+        let length = module.lit(2.);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let ret = node.eval(&mut module, vec![t]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![t, length]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "(2.) - (abs(((3.) % ((2.) * (2.))) - (2.)))".to_string()
+        );
+    }
+
+    #[test]
+    fn curve() {
+        let curve = Gradient::from_keys([(0., 1.), (0.5, 3.), (1., 2.)]);
+        let node = CurveNode::new(curve.clone());
+        assert_eq!(node.curve(), &Curve::Float(curve.clone()));
+        assert_eq!(
+            node.slots()[1].value_type(),
+            Some(ValueType::Scalar(ScalarType::Float))
+        );
+
+        let mut module = Module::default();
+
+        let t = module.attr(Attribute::AGE);
+        let ret = node.eval(&mut module, vec![]);
+        assert!(matches!(
+            ret,
+            Err(ExprError::GraphError(GraphError::WrongInputCount { .. }))
+        ));
+        let outputs = node.eval(&mut module, vec![t]).unwrap();
+        assert_eq!(outputs.len(), 1);
+
+        // The curve was uploaded to the module, and is sampled at the input
+        assert_eq!(module.curves(), &[Curve::Float(curve)]);
+        let Some(Expr::CurveSample(expr)) = module.get(outputs[0]) else {
+            panic!("Expected a curve sample expression.");
+        };
+        assert_eq!(module.get_curve(expr.curve), Some(&module.curves()[0]));
+        assert_eq!(expr.t, t);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, outputs[0]).unwrap();
+        assert_eq!(
+            str,
+            "(1. + 2. * clamp((var0 - 0.) * 2., 0., 1.) + -1. * clamp((var0 - 0.5) * 2., 0., 1.))"
+        );
+        assert_eq!(context.main_code, "let var0 = particle.age;\n");
+    }
+
+    #[test]
+    fn graph() {
+        let mut g = Graph::new();
+
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_add = g.add_node(AddNode::default());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_add_lhs = g.input_slots(nid_add)[0];
+        let sid_add_rhs = g.input_slots(nid_add)[1];
+        g.link(sid_pos, sid_add_lhs).unwrap();
+
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_mul = g.add_node(MulNode::default());
+        let nid_dt = g.add_node(TimeNode::default());
+        let sid_vel = g.output_slots(nid_vel)[0];
+        let sid_dt = g
+            .output_slot(nid_dt, BuiltInOperator::DeltaTime.name())
+            .unwrap();
+        let sid_mul_lhs = g.input_slots(nid_mul)[0];
+        let sid_mul_rhs = g.input_slots(nid_mul)[1];
+        g.link(sid_vel, sid_mul_lhs).unwrap();
+        g.link(sid_dt, sid_mul_rhs).unwrap();
+
+        let sid_mul_out = g.output_slots(nid_mul)[0];
+        g.link(sid_mul_out, sid_add_rhs).unwrap();
+
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(
+            str,
+            "(particle.position) + ((particle.velocity) * (sim_params.delta_time))"
+        );
+    }
+
+    #[test]
+    fn graph_evaluate_input_order() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_sub = g.add_node(SubNode::default());
+
+        // Link "rhs" before "lhs"; evaluation follows the slot order.
+        g.link(
+            g.output_slots(nid_vel)[0],
+            g.input_slot(nid_sub, "rhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slots(nid_pos)[0],
+            g.input_slot(nid_sub, "lhs").unwrap(),
+        )
+        .unwrap();
+
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "(particle.position) - (particle.velocity)");
+    }
+
+    #[test]
+    fn graph_compile() {
+        let mut g = Graph::new();
+        let nid_a = g.add_node(ConstantNode::scalar(1.));
+        let nid_b = g.add_node(ConstantNode::scalar(2.));
+        let nid_add = g.add_node(AddNode::default());
+        let sid_add = g.output_slot(nid_add, "result").unwrap();
+        g.link(
+            g.output_slots(nid_a)[0],
+            g.input_slot(nid_add, "lhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slots(nid_b)[0],
+            g.input_slot(nid_add, "rhs").unwrap(),
+        )
+        .unwrap();
+
+        // Any output slot can be requested, not only the ones of sink nodes.
+        let mut module = Module::default();
+        let exprs = g
+            .compile(&mut module, &[sid_add, g.output_slots(nid_b)[0]])
+            .unwrap();
+        assert_eq!(exprs.len(), 2);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "(1.) + (2.)");
+        let str = context.eval(&module, exprs[1]).unwrap();
+        assert_eq!(str, "2.");
+
+        // Errors are the same as evaluate()
+        g.unlink_all(g.input_slot(nid_add, "rhs").unwrap());
+        let mut module = Module::default();
+        assert_eq!(
+            g.compile(&mut module, &[sid_add]),
+            Err(ExprError::GraphError(GraphError::UnlinkedInput {
+                input: g.input_slot(nid_add, "rhs").unwrap()
+            }))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn graph_compile_input_slot() {
+        let mut g = Graph::new();
+        let nid = g.add_node(NormalizeNode::default());
+        let mut module = Module::default();
+        let _ = g.compile(&mut module, &[g.input_slot(nid, "in").unwrap()]);
+    }
+
+    #[test]
+    fn graph_slot_order() {
+        // Node presenting its inputs in reverse order
+        #[derive(Clone, Serialize, Deserialize)]
+        struct ReversedSubNode {
+            slots: [SlotDef; 3],
+        }
+
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl Node for ReversedSubNode {
+            fn slots(&self) -> &[SlotDef] {
+                &self.slots
+            }
+
+            fn slot_order(&self) -> Vec<usize> {
+                vec![1, 0, 2]
+            }
+
+            fn boxed_clone(&self) -> BoxedNode {
+                Box::new(self.clone())
+            }
+
+            fn eval(
+                &self,
+                module: &mut Module,
+                inputs: Vec<ExprHandle>,
+            ) -> Result<Vec<ExprHandle>, ExprError> {
+                Ok(vec![module.sub(inputs[0], inputs[1])])
+            }
+        }
+
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_sub = g.add_node(ReversedSubNode {
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::input("rhs", None),
+                SlotDef::output("result", None),
+            ],
+        });
+        let sid_lhs = g.input_slot(nid_sub, "lhs").unwrap();
+        let sid_rhs = g.input_slot(nid_sub, "rhs").unwrap();
+        assert_eq!(g.input_slots(nid_sub), vec![sid_rhs, sid_lhs]);
+        assert_eq!(
+            g.output_slots(nid_sub),
+            vec![g.output_slot(nid_sub, "result").unwrap()]
+        );
+
+        // Default order is the definition order
+        let nid_add = g.add_node(AddNode::default());
+        assert_eq!(
+            g.input_slots(nid_add),
+            vec![
+                g.input_slot(nid_add, "lhs").unwrap(),
+                g.input_slot(nid_add, "rhs").unwrap()
+            ]
+        );
+        g.remove_node(nid_add);
+
+        // Evaluation still follows the definition order
+        g.link(g.output_slots(nid_pos)[0], sid_lhs).unwrap();
+        g.link(g.output_slots(nid_vel)[0], sid_rhs).unwrap();
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "(particle.position) - (particle.velocity)");
+    }
+
+    #[test]
+    fn graph_counts() {
+        let mut g = Graph::new();
+        assert_eq!(g.node_count(), 0);
+        assert_eq!(g.slot_count(), 0);
+        assert_eq!(g.nodes().count(), 0);
+
+        let nid_time = g.add_node(TimeNode::default());
+        let nid_add = g.add_node(AddNode::default());
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(g.slot_count(), 2 + 3 + 1);
+        assert_eq!(
+            g.nodes().collect::<Vec<_>>(),
+            vec![nid_time, nid_add, nid_pos]
+        );
+
+        g.remove_node(nid_add);
+        assert_eq!(g.node_count(), 2);
+        assert_eq!(g.slot_count(), 2 + 1);
+        assert_eq!(g.nodes().collect::<Vec<_>>(), vec![nid_time, nid_pos]);
+    }
+
+    #[test]
+    fn graph_find_nodes() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_time = g.add_node(TimeNode::default());
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_removed = g.add_node(AttributeNode::new(Attribute::AGE));
+        g.add_node(AddNode::default());
+        g.remove_node(nid_removed);
+
+        assert_eq!(g.find_nodes::<AttributeNode>(), vec![nid_pos, nid_vel]);
+        assert_eq!(g.find_nodes::<TimeNode>(), vec![nid_time]);
+        assert!(g.find_nodes::<SubNode>().is_empty());
+
+        // Swap all attribute nodes reading POSITION
+        for (_, node) in g.find_nodes_mut::<AttributeNode>() {
+            if node.attr() == Attribute::POSITION {
+                node.set_attr(Attribute::PREV);
+            }
+        }
+        let attrs = g
+            .find_nodes_mut::<AttributeNode>()
+            .into_iter()
+            .map(|(node_id, node)| (node_id, node.attr()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attrs,
+            vec![(nid_pos, Attribute::PREV), (nid_vel, Attribute::VELOCITY)]
+        );
+    }
+
+    #[test]
+    fn graph_evaluate_fan_out() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_noise = g.add_node(NoiseNode::new(NoiseType::Simplex));
+        let nid_one = g.add_node(ConstantNode::scalar(1.));
+        let nid_add1 = g.add_node(AddNode::default());
+        let nid_add2 = g.add_node(AddNode::default());
+        let sid_noise = g.output_slot(nid_noise, "value").unwrap();
+        g.link(
+            g.output_slots(nid_pos)[0],
+            g.input_slot(nid_noise, "pos").unwrap(),
+        )
+        .unwrap();
+        for nid_add in [nid_add1, nid_add2] {
+            g.link(sid_noise, g.input_slot(nid_add, "lhs").unwrap())
+                .unwrap();
+            g.link(
+                g.output_slots(nid_one)[0],
+                g.input_slot(nid_add, "rhs").unwrap(),
+            )
+            .unwrap();
+        }
+
+        // Both additions use the same noise expression
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 2);
+        let lhs = exprs
+            .iter()
+            .map(|&expr| match module.get(expr).unwrap() {
+                Expr::Binary { left, .. } => *left,
+                _ => panic!("Unexpected expression."),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lhs[0], lhs[1]);
+        assert!(matches!(
+            module.get(lhs[0]).unwrap(),
+            Expr::Unary {
+                op: UnaryOperator::SimplexNoise,
+                ..
+            }
+        ));
+
+        // The noise is only evaluated once in the shader
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        for expr in exprs {
+            let str = context.eval(&module, expr).unwrap();
+            assert_eq!(str, "(simplex_noise_vec3(particle.position)) + (1.)");
+        }
+    }
+
+    #[test]
+    fn graph_append() {
+        let mut g = Graph::new();
+        let nid_time = g.add_node(TimeNode::default());
+
+        let mut other = Graph::new();
+        let nid_removed = other.add_node(ConstantNode::scalar(1.));
+        let nid_pos = other.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_norm = other.add_node(NormalizeNode::default());
+        other
+            .link(
+                other.output_slots(nid_pos)[0],
+                other.input_slot(nid_norm, "in").unwrap(),
+            )
+            .unwrap();
+        other.remove_node(nid_removed);
+
+        let node_map = g.append(other);
+        assert_eq!(node_map.len(), 2);
+        assert!(!node_map.contains_key(&nid_removed));
+        let new_pos = node_map[&nid_pos];
+        let new_norm = node_map[&nid_norm];
+        assert_ne!(new_pos, nid_time);
+        assert_ne!(new_norm, nid_time);
+        assert_ne!(new_pos, new_norm);
+        assert!(g
+            .get_node(new_pos)
+            .unwrap()
+            .downcast_ref::<AttributeNode>()
+            .is_some());
+        assert!(g
+            .get_node(new_norm)
+            .unwrap()
+            .downcast_ref::<NormalizeNode>()
+            .is_some());
+
+        // Slots belong to their new node, and the internal link survived
+        let sid_out = g.output_slots(new_pos)[0];
+        let sid_in = g.input_slot(new_norm, "in").unwrap();
+        assert_eq!(g.get_slot(sid_out).node_id(), new_pos);
+        assert_eq!(g.get_slot(sid_in).node_id(), new_norm);
+        assert_eq!(g.get_slot(sid_out).linked_slots, vec![sid_in]);
+        assert_eq!(g.get_slot(sid_in).linked_slots, vec![sid_out]);
+        assert_eq!(g.slots(nid_time).len(), 2);
+
+        // The existing and appended nodes can be linked together
+        let nid_add = g.add_node(AddNode::default());
+        g.link(
+            g.output_slot(nid_time, "time").unwrap(),
+            g.input_slot(nid_add, "lhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slots(new_norm)[0],
+            g.input_slot(nid_add, "rhs").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(g.validate(), Ok(()));
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
+    }
+
+    #[test]
+    fn graph_add_binary() {
+        let mut g = Graph::new();
+        let nid_a = g.add_node(ConstantNode::scalar(2.));
+        let nid_b = g.add_node(ConstantNode::scalar(3.));
+        let sid_mul = g
+            .add_binary(
+                BinaryOperator::Mul,
+                g.output_slots(nid_a)[0],
+                g.output_slots(nid_b)[0],
+            )
+            .unwrap();
+        let nid_mul = g.get_slot(sid_mul).node_id();
+        assert!(g
+            .get_node(nid_mul)
+            .unwrap()
+            .downcast_ref::<MulNode>()
+            .is_some());
+        assert_eq!(g.validate(), Ok(()));
+
+        let mut module = Module::default();
+        let exprs = g.compile(&mut module, &[sid_mul]).unwrap();
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "(2.) * (3.)");
+
+        // Chaining
+        let sid_sub = g
+            .add_binary(BinaryOperator::Sub, sid_mul, g.output_slots(nid_a)[0])
+            .unwrap();
+        let sid_div = g.add_binary(BinaryOperator::Div, sid_sub, sid_mul).unwrap();
+        let sid_add = g.add_binary(BinaryOperator::Add, sid_div, sid_div).unwrap();
+        let mut module = Module::default();
+        let exprs = g.compile(&mut module, &[sid_add]).unwrap();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(
+            str,
+            "((((2.) * (3.)) - (2.)) / ((2.) * (3.))) + ((((2.) * (3.)) - (2.)) / ((2.) * (3.)))"
+        );
+    }
+
+    #[test]
+    fn graph_add_binary_ops() {
+        let mut g = Graph::new();
+        let nid_a = g.add_node(ConstantNode::scalar(2.));
+        let nid_b = g.add_node(ConstantNode::scalar(3.));
+        let sid_a = g.output_slots(nid_a)[0];
+        let sid_b = g.output_slots(nid_b)[0];
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        for (op, expected) in [
+            (BinaryOperator::Pow, "pow(2., 3.)"),
+            (BinaryOperator::Min, "min(2., 3.)"),
+            (BinaryOperator::Max, "max(2., 3.)"),
+            (BinaryOperator::Remainder, "(2.) % (3.)"),
+            (BinaryOperator::Atan2, "atan2(2., 3.)"),
+            (BinaryOperator::Step, "step(2., 3.)"),
+        ] {
+            let sid = g.add_binary(op, sid_a, sid_b).unwrap();
+            let mut module = Module::default();
+            let exprs = g.compile(&mut module, &[sid]).unwrap();
+            let mut context =
+                ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+            let str = context.eval(&module, exprs[0]).unwrap();
+            assert_eq!(str, expected);
+        }
+    }
+
+    #[test]
+    fn graph_add_binary_unsupported() {
+        let mut g = Graph::new();
+        let nid_a = g.add_node(ConstantNode::scalar(2.));
+        let sid_a = g.output_slots(nid_a)[0];
+        assert!(g.add_binary(BinaryOperator::And, sid_a, sid_a).is_none());
+        assert!(g
+            .add_binary(BinaryOperator::UniformRand, sid_a, sid_a)
+            .is_none());
+
+        // The graph is left unchanged
+        assert_eq!(g.node_count(), 1);
+        assert!(g.links().is_empty());
+    }
+
+    #[test]
+    fn graph_evaluate_errors() {
+        // Unlinked input
+        let mut g = Graph::new();
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let mut module = Module::default();
+        assert_eq!(
+            g.evaluate(&mut module),
+            Err(ExprError::GraphError(GraphError::UnlinkedInput {
+                input: g.input_slot(nid_norm, "in").unwrap()
+            }))
+        );
+
+        // Cycle
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_add = g.add_node(AddNode::default());
+        let nid_norm = g.add_node(NormalizeNode::default());
+        g.link_unchecked(
+            g.output_slots(nid_pos)[0],
+            g.input_slot(nid_add, "lhs").unwrap(),
+        );
+        g.link_unchecked(
+            g.output_slots(nid_add)[0],
+            g.input_slot(nid_norm, "in").unwrap(),
+        );
+        g.link_unchecked(
+            g.output_slots(nid_norm)[0],
+            g.input_slot(nid_add, "rhs").unwrap(),
+        );
+        let mut module = Module::default();
+        assert!(matches!(
+            g.evaluate(&mut module),
+            Err(ExprError::GraphError(GraphError::Cycle { .. }))
+        ));
+
+        // Node whose slots changed since it was added to the graph
+        let mut g = Graph::new();
+        let nid_time = g.add_node(TimeNode::default());
+        let nid_add = g.add_node(AddNode::default());
+        g.link(
+            g.output_slots(nid_time)[0],
+            g.input_slot(nid_add, "lhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slots(nid_time)[1],
+            g.input_slot(nid_add, "rhs").unwrap(),
+        )
+        .unwrap();
+        *g.get_node_mut(nid_add)
+            .unwrap()
+            .downcast_mut::<AddNode>()
+            .unwrap() = AddNode {
+            slots: [
+                SlotDef::input("lhs", None),
+                SlotDef::output("out", None),
+                SlotDef::output("out2", None),
+            ],
+            ..default()
+        };
+        let mut module = Module::default();
+        assert_eq!(
+            g.evaluate(&mut module),
+            Err(ExprError::GraphError(GraphError::WrongInputCount {
+                node: Some(nid_add),
+                expected: 1,
+                got: 2
+            }))
+        );
+
+        // Node producing the wrong number of outputs
+        #[derive(Clone, Serialize, Deserialize)]
+        struct NoOutputNode {
+            slots: [SlotDef; 1],
+        }
+
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl Node for NoOutputNode {
+            fn slots(&self) -> &[SlotDef] {
+                &self.slots
+            }
+
+            fn boxed_clone(&self) -> BoxedNode {
+                Box::new(self.clone())
+            }
+
+            fn eval(
+                &self,
+                _module: &mut Module,
+                _inputs: Vec<ExprHandle>,
+            ) -> Result<Vec<ExprHandle>, ExprError> {
+                Ok(vec![])
+            }
+        }
+
+        let mut g = Graph::new();
+        let nid_bad = g.add_node(NoOutputNode {
+            slots: [SlotDef::output("out", None)],
+        });
+        let mut module = Module::default();
+        assert_eq!(
+            g.evaluate(&mut module),
+            Err(ExprError::GraphError(GraphError::WrongOutputCount {
+                node: nid_bad,
+                expected: 1,
+                got: 0
+            }))
+        );
+    }
+
+    #[test]
+    fn graph_link_cycle() {
+        let mut g = Graph::new();
+        let nid_a = g.add_node(NormalizeNode::default());
+        let nid_b = g.add_node(NormalizeNode::default());
+        let nid_c = g.add_node(NormalizeNode::default());
+        let sid_a_out = g.output_slot(nid_a, "out").unwrap();
+        let sid_b_in = g.input_slot(nid_b, "in").unwrap();
+        let sid_b_out = g.output_slot(nid_b, "out").unwrap();
+        let sid_c_in = g.input_slot(nid_c, "in").unwrap();
+        let sid_c_out = g.output_slot(nid_c, "out").unwrap();
+        let sid_a_in = g.input_slot(nid_a, "in").unwrap();
+        assert!(g.link(sid_a_out, sid_b_in).is_ok());
+        assert!(g.link(sid_b_out, sid_c_in).is_ok());
+
+        // Closing the loop C -> A is rejected, and leaves the graph unchanged.
+        assert_eq!(
+            g.link(sid_c_out, sid_a_in),
+            Err(GraphError::Cycle {
+                output: sid_c_out,
+                input: sid_a_in
+            })
+        );
+        assert!(g.get_slot(sid_c_out).linked_slots.is_empty());
+        assert!(g.get_slot(sid_a_in).linked_slots.is_empty());
+
+        // Self-loops are rejected too.
+        assert!(g
+            .link(sid_b_out, g.input_slot(nid_b, "in").unwrap())
+            .is_err());
+
+        // A link across the chain which doesn't form a loop is fine.
+        let nid_d = g.add_node(AddNode::default());
+        assert!(g
+            .link(sid_a_out, g.input_slot(nid_d, "lhs").unwrap())
+            .is_ok());
+        assert!(g
+            .link(sid_c_out, g.input_slot(nid_d, "rhs").unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn graph_get_node() {
+        let mut graph = Graph::new();
+        let time_id = graph.add_node(TimeNode::default());
+        let attr_id = graph.add_node(AttributeNode::new(Attribute::POSITION));
+
+        let node = graph.get_node(time_id).unwrap();
+        assert_eq!(node.name(), "TimeNode");
+        assert!(node.downcast_ref::<TimeNode>().is_some());
+        assert!(node.downcast_ref::<AttributeNode>().is_none());
+
+        let node = graph.get_node_mut(attr_id).unwrap();
+        let attr_node = node.downcast_mut::<AttributeNode>().unwrap();
+        assert_eq!(attr_node.attr(), Attribute::POSITION);
+        attr_node.set_attr(Attribute::VELOCITY);
+
+        let node = graph.get_node(attr_id).unwrap();
+        let attr_node = node.downcast_ref::<AttributeNode>().unwrap();
+        assert_eq!(attr_node.attr(), Attribute::VELOCITY);
+
+        graph.remove_node(time_id);
+        assert!(graph.get_node(time_id).is_none());
+        assert!(graph.get_node_mut(time_id).is_none());
+    }
+
+    #[test]
+    fn graph_topological_order() {
+        // Diamond: A -> B, A -> C, B -> D, C -> D. Add the nodes out of order to
+        // check the ordering doesn't rely on the node identifiers.
+        let mut g = Graph::new();
+        let nid_d = g.add_node(AddNode::default());
+        let nid_c = g.add_node(NormalizeNode::default());
+        let nid_b = g.add_node(NormalizeNode::default());
+        let nid_a = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let sid_a_out = g.output_slots(nid_a)[0];
+        g.link(sid_a_out, g.input_slot(nid_b, "in").unwrap())
+            .unwrap();
+        g.link(sid_a_out, g.input_slot(nid_c, "in").unwrap())
+            .unwrap();
+        g.link(
+            g.output_slot(nid_b, "out").unwrap(),
+            g.input_slot(nid_d, "lhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slot(nid_c, "out").unwrap(),
+            g.input_slot(nid_d, "rhs").unwrap(),
+        )
+        .unwrap();
+
+        let order = g.topological_order().unwrap();
+        assert_eq!(order.len(), 4);
+        let pos = |nid| order.iter().position(|&id| id == nid).unwrap();
+        assert_eq!(pos(nid_a), 0);
+        assert!(pos(nid_b) < pos(nid_d));
+        assert!(pos(nid_c) < pos(nid_d));
+        assert_eq!(pos(nid_d), 3);
+
+        // Removed nodes are skipped.
+        g.remove_node(nid_c);
+        assert_eq!(g.topological_order(), Ok(vec![nid_a, nid_b, nid_d]));
+
+        // Cycle B -> E -> F -> E, downstream of A. The reported link is on the
+        // cycle itself.
+        let nid_e = g.add_node(AddNode::default());
+        let nid_f = g.add_node(NormalizeNode::default());
+        let sid_e_out = g.output_slots(nid_e)[0];
+        let sid_f_in = g.input_slot(nid_f, "in").unwrap();
+        let sid_f_out = g.output_slot(nid_f, "out").unwrap();
+        let sid_e_rhs = g.input_slot(nid_e, "rhs").unwrap();
+        g.link_unchecked(
+            g.output_slot(nid_b, "out").unwrap(),
+            g.input_slot(nid_e, "lhs").unwrap(),
+        );
+        g.link_unchecked(sid_e_out, sid_f_in);
+        g.link_unchecked(sid_f_out, sid_e_rhs);
+        let err = g.topological_order().unwrap_err();
+        assert!(
+            err == GraphError::Cycle {
+                output: sid_e_out,
+                input: sid_f_in
+            } || err
+                == GraphError::Cycle {
+                    output: sid_f_out,
+                    input: sid_e_rhs
+                }
+        );
+    }
+
+    #[test]
+    fn graph_clone() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let mut node = NormalizeNode::default();
+        node.set_label(Some("norm".to_string()));
+        let nid_norm = g.add_node(node);
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        g.link(sid_pos, sid_norm_in).unwrap();
+
+        let mut g2 = g.clone();
+        assert_eq!(g2.get_node(nid_norm).unwrap().label(), Some("norm"));
+        assert_eq!(g2.get_slot(sid_norm_in).linked_slots, vec![sid_pos]);
+
+        // Mutating the clone leaves the original unchanged.
+        g2.unlink(sid_pos, sid_norm_in);
+        g2.get_node_mut(nid_norm).unwrap().set_label(None);
+        let nid_age = g2.add_node(AttributeNode::new(Attribute::AGE));
+        g2.link(g2.output_slots(nid_age)[0], sid_norm_in).unwrap();
+        assert_eq!(g2.get_slot(sid_norm_in).linked_slots.len(), 1);
+        assert_ne!(g2.get_slot(sid_norm_in).linked_slots[0], sid_pos);
+
+        assert_eq!(g.get_node(nid_norm).unwrap().label(), Some("norm"));
+        assert_eq!(g.get_slot(sid_norm_in).linked_slots, vec![sid_pos]);
+        assert_eq!(g.get_slot(sid_pos).linked_slots, vec![sid_norm_in]);
+        assert!(g.get_node(nid_age).is_none());
+    }
+
+    #[test]
+    fn graph_slot_def() {
+        let mut g = Graph::new();
+        let nid_split = g.add_node(SplitVec3Node::default());
+        let sid_in = g.input_slots(nid_split)[0];
+        let sid_x = g.output_slot(nid_split, "x").unwrap();
+
+        let slot_def = g.slot_def(sid_in).unwrap();
+        assert_eq!(slot_def.dir(), SlotDir::Input);
+        assert_eq!(
+            slot_def.value_type(),
+            Some(ValueType::Vector(VectorType::VEC3F))
+        );
+
+        let slot_def = g.slot_def(sid_x).unwrap();
+        assert_eq!(slot_def.name(), "x");
+        assert_eq!(slot_def.dir(), SlotDir::Output);
+        assert_eq!(
+            slot_def.value_type(),
+            Some(ValueType::Scalar(ScalarType::Float))
+        );
+
+        g.rename_slot(sid_x, "first").unwrap();
+        assert_eq!(g.slot_def(sid_x).unwrap().name(), "first");
+
+        g.remove_node(nid_split);
+        assert!(g.slot_def(sid_in).is_none());
+        assert!(g
+            .slot_def(SlotId::new(NonZeroU32::new(1000).unwrap()))
+            .is_none());
+    }
+
+    #[test]
+    fn graph_rename_slot() {
+        let mut g = Graph::new();
+        let nid_add = g.add_node(AddNode::default());
+        let nid_time = g.add_node(TimeNode::default());
+        let sid_lhs = g.input_slot(nid_add, "lhs").unwrap();
+        let sid_rhs = g.input_slot(nid_add, "rhs").unwrap();
+        let sid_out = g.output_slots(nid_add)[0];
+
+        g.rename_slot(sid_lhs, "base").unwrap();
+        assert_eq!(g.input_slot(nid_add, "base"), Some(sid_lhs));
+        assert_eq!(g.get_slot_id("base"), Some(sid_lhs));
+        assert!(g.input_slot(nid_add, "lhs").is_none());
+        assert!(g.get_slot_id("lhs").is_none());
+
+        // Renaming to the current name is allowed
+        assert!(g.rename_slot(sid_lhs, "base").is_ok());
+
+        // Names must be unique per node, whatever the slot direction
+        let out_name = g.get_slot(sid_out).def().name().to_string();
+        assert_eq!(
+            g.rename_slot(sid_rhs, "base"),
+            Err(GraphError::DuplicateSlotName {
+                slot: sid_rhs,
+                existing: sid_lhs,
+            })
+        );
+        assert_eq!(
+            g.rename_slot(sid_rhs, out_name),
+            Err(GraphError::DuplicateSlotName {
+                slot: sid_rhs,
+                existing: sid_out,
+            })
+        );
+        assert_eq!(g.input_slot(nid_add, "rhs"), Some(sid_rhs));
+
+        // Other nodes can use the same name
+        let sid_time = g.output_slots(nid_time)[0];
+        assert!(g.rename_slot(sid_time, "base").is_ok());
+        assert_eq!(g.output_slot(nid_time, "base"), Some(sid_time));
+    }
+
+    #[test]
+    fn graph_node_enabled() {
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let eval = |module: &Module, expr| {
+            let mut context =
+                ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+            context.eval(module, expr).unwrap()
+        };
+
+        // velocity -> normalize -> * 2
+        let mut g = Graph::new();
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let nid_two = g.add_node(ConstantNode::scalar(2.));
+        g.link(g.output_slots(nid_vel)[0], g.input_slots(nid_norm)[0])
+            .unwrap();
+        let sid_mul = g
+            .add_binary(
+                BinaryOperator::Mul,
+                g.output_slots(nid_norm)[0],
+                g.output_slots(nid_two)[0],
+            )
+            .unwrap();
+        assert!(g.is_node_enabled(nid_norm));
+
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(
+            eval(&module, exprs[0]),
+            "(normalize(particle.velocity)) * (2.)"
+        );
+
+        // Disabled, the input flows straight to the multiplication
+        g.set_node_enabled(nid_norm, false);
+        assert!(!g.is_node_enabled(nid_norm));
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(eval(&module, exprs[0]), "(particle.velocity) * (2.)");
+
+        // The disabled state is kept when appending to another graph
+        let mut g2 = Graph::new();
+        let node_map = g2.append(g.clone());
+        assert!(!g2.is_node_enabled(node_map[&nid_norm]));
+        assert!(g2.is_node_enabled(node_map[&nid_vel]));
+
+        g.set_node_enabled(nid_norm, true);
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(
+            eval(&module, exprs[0]),
+            "(normalize(particle.velocity)) * (2.)"
+        );
+
+        // Nodes with several inputs can't be bypassed
+        let nid_mul = g.get_slot(sid_mul).node_id();
+        g.set_node_enabled(nid_mul, false);
+        let mut module = Module::default();
+        assert!(matches!(
+            g.evaluate(&mut module),
+            Err(ExprError::GraphError(GraphError::NoPassthrough { node })) if node == nid_mul
+        ));
+
+        // Removing a node forgets its state
+        g.remove_node(nid_mul);
+        assert!(g.is_node_enabled(nid_mul));
+    }
+
+    #[test]
+    fn graph_disconnect_node() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let nid_len = g.add_node(LengthNode::default());
+        let nid_dot = g.add_node(DotNode::default());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        let sid_norm_out = g.output_slot(nid_norm, "out").unwrap();
+        let sid_len_in = g.input_slot(nid_len, "in").unwrap();
+        let sid_dot_lhs = g.input_slots(nid_dot)[0];
+        let sid_dot_rhs = g.input_slots(nid_dot)[1];
+        g.link(sid_pos, sid_norm_in).unwrap();
+        g.link(sid_norm_out, sid_len_in).unwrap();
+        g.link(sid_norm_out, sid_dot_lhs).unwrap();
+        g.link(sid_pos, sid_dot_rhs).unwrap();
+
+        g.disconnect_node(nid_norm);
+
+        // The node and its slots remain, but are not linked anymore.
+        assert!(g.get_node(nid_norm).is_some());
+        assert_eq!(g.slots(nid_norm), vec![sid_norm_in, sid_norm_out]);
+        assert!(g.get_slot(sid_norm_in).linked_slots.is_empty());
+        assert!(g.get_slot(sid_norm_out).linked_slots.is_empty());
+
+        // Neighbors don't keep any link to the node, but keep their other
+        // links.
+        assert_eq!(g.get_slot(sid_pos).linked_slots, vec![sid_dot_rhs]);
+        assert!(g.get_slot(sid_len_in).linked_slots.is_empty());
+        assert!(g.get_slot(sid_dot_lhs).linked_slots.is_empty());
+        assert_eq!(g.get_slot(sid_dot_rhs).linked_slots, vec![sid_pos]);
+
+        // The node can be linked again.
+        g.link(sid_pos, sid_norm_in).unwrap();
+        g.link(sid_norm_out, sid_len_in).unwrap();
+        assert_eq!(
+            g.get_slot(sid_pos).linked_slots,
+            vec![sid_dot_rhs, sid_norm_in]
+        );
+        assert_eq!(g.get_slot(sid_len_in).linked_slots, vec![sid_norm_out]);
+    }
+
+    #[test]
+    fn graph_remove_node() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let nid_len = g.add_node(LengthNode::default());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        let sid_norm_out = g.output_slot(nid_norm, "out").unwrap();
+        let sid_len_in = g.input_slot(nid_len, "in").unwrap();
+        let sid_len_out = g.output_slot(nid_len, "out").unwrap();
+        g.link(sid_pos, sid_norm_in).unwrap();
+        g.link(sid_norm_out, sid_len_in).unwrap();
+
+        g.remove_node(nid_norm);
+
+        // Remaining nodes and slots keep their identifiers, and lost their
+        // links to the removed node.
+        assert!(g.slots(nid_norm).is_empty());
+        assert_eq!(g.output_slots(nid_pos), vec![sid_pos]);
+        assert_eq!(g.input_slot(nid_len, "in"), Some(sid_len_in));
+        assert_eq!(g.output_slot(nid_len, "out"), Some(sid_len_out));
+        assert_eq!(g.get_slot_id("out"), Some(sid_len_out));
+        assert!(g.get_slot(sid_pos).linked_slots.is_empty());
+        assert!(g.get_slot(sid_len_in).linked_slots.is_empty());
+
+        // New nodes don't reuse the identifiers of the removed one.
+        let nid_norm2 = g.add_node(NormalizeNode::default());
+        assert_ne!(nid_norm2, nid_norm);
+        let sid_norm2_in = g.input_slot(nid_norm2, "in").unwrap();
+        assert_ne!(sid_norm2_in, sid_norm_in);
+        g.link(sid_pos, sid_len_in).unwrap();
+        g.link(sid_len_out, sid_norm2_in).unwrap();
+
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "normalize(length(particle.position))");
     }
 
-    fn eval(
-        &self,
-        module: &mut Module,
-        inputs: Vec<ExprHandle>,
-    ) -> Result<Vec<ExprHandle>, ExprError> {
-        if inputs.len() != 2 {
-            return Err(ExprError::GraphEvalError(format!(
-                "Unexpected input count to MulNode::eval(): expected 2, got
-{}",
-                inputs.len()
-            )));
+    #[test]
+    fn graph_multi_input() {
+        /// Test node summing any number of values.
+        #[derive(Clone, Serialize, Deserialize)]
+        struct SumNode {
+            slots: [SlotDef; 2],
         }
-        let mut inputs = inputs.into_iter();
-        let left = inputs.next().unwrap();
-        let right = inputs.next().unwrap();
-        let mul = module.mul(left, right);
-        Ok(vec![mul])
-    }
-}
 
-/// Graph node to divide two values.
-#[derive(Debug, Clone)]
-pub struct DivNode {
-    slots: [SlotDef; 3],
-}
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl Node for SumNode {
+            fn slots(&self) -> &[SlotDef] {
+                &self.slots
+            }
 
-impl Default for DivNode {
-    fn default() -> Self {
-        Self {
+            fn boxed_clone(&self) -> BoxedNode {
+                Box::new(self.clone())
+            }
+
+            fn eval(
+                &self,
+                module: &mut Module,
+                inputs: Vec<ExprHandle>,
+            ) -> Result<Vec<ExprHandle>, ExprError> {
+                let sum = inputs
+                    .into_iter()
+                    .reduce(|acc, input| module.add(acc, input))
+                    .unwrap();
+                Ok(vec![sum])
+            }
+        }
+
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_sum = g.add_node(SumNode {
             slots: [
-                SlotDef::input("lhs", None),
-                SlotDef::input("rhs", None),
-                SlotDef::output("result", None),
+                SlotDef::input("in", None).with_multi_input(),
+                SlotDef::output("out", None),
             ],
-        }
-    }
-}
+        });
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_vel = g.output_slots(nid_vel)[0];
+        let sid_sum_in = g.input_slot(nid_sum, "in").unwrap();
+        g.link(sid_pos, sid_sum_in).unwrap();
+        g.link(sid_vel, sid_sum_in).unwrap();
+        assert_eq!(g.get_slot(sid_sum_in).linked_slots, vec![sid_pos, sid_vel]);
 
-impl Node for DivNode {
-    fn slots(&self) -> &[SlotDef] {
-        &self.slots
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "(particle.position) + (particle.velocity)");
+
+        // Unlinking removes only the given output.
+        g.unlink(sid_pos, sid_sum_in);
+        assert_eq!(g.get_slot(sid_sum_in).linked_slots, vec![sid_vel]);
+        assert!(g.get_slot(sid_pos).linked_slots.is_empty());
+        assert_eq!(g.get_slot(sid_vel).linked_slots, vec![sid_sum_in]);
+
+        // Single-input slots still replace their link, and the replaced output
+        // slot is unlinked too.
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        g.link(sid_pos, sid_norm_in).unwrap();
+        g.link(sid_vel, sid_norm_in).unwrap();
+        assert_eq!(g.get_slot(sid_norm_in).linked_slots, vec![sid_vel]);
+        assert!(g.get_slot(sid_pos).linked_slots.is_empty());
     }
 
-    fn eval(
-        &self,
-        module: &mut Module,
-        inputs: Vec<ExprHandle>,
-    ) -> Result<Vec<ExprHandle>, ExprError> {
-        if inputs.len() != 2 {
-            return Err(ExprError::GraphEvalError(format!(
-                "Unexpected input count to DivNode::eval(): expected 2, got
-{}",
-                inputs.len()
-            )));
+    #[test]
+    fn graph_get_slot_id_on() {
+        let mut g = Graph::new();
+        let nid_add1 = g.add_node(AddNode::default());
+        let nid_add2 = g.add_node(AddNode::default());
+
+        // Global lookup returns the first match.
+        let sid_lhs1 = g.get_slot_id("lhs").unwrap();
+        assert_eq!(g.get_slot(sid_lhs1).node_id(), nid_add1);
+
+        for nid in [nid_add1, nid_add2] {
+            for name in ["lhs", "rhs", "result"] {
+                let sid = g.get_slot_id_on(nid, name).unwrap();
+                assert_eq!(g.get_slot(sid).node_id(), nid);
+                assert_eq!(g.get_slot(sid).def().name(), name);
+            }
         }
-        let mut inputs = inputs.into_iter();
-        let left = inputs.next().unwrap();
-        let right = inputs.next().unwrap();
-        let div = module.div(left, right);
-        Ok(vec![div])
+        assert_eq!(g.get_slot_id_on(nid_add1, "lhs"), Some(sid_lhs1));
+        assert_ne!(
+            g.get_slot_id_on(nid_add1, "lhs"),
+            g.get_slot_id_on(nid_add2, "lhs")
+        );
+        assert_eq!(
+            g.get_slot_id_on(nid_add2, "result"),
+            g.output_slot(nid_add2, "result")
+        );
+        assert!(g.get_slot_id_on(nid_add1, "unknown").is_none());
+
+        // Removed nodes have no slot.
+        g.remove_node(nid_add1);
+        assert!(g.get_slot_id_on(nid_add1, "lhs").is_none());
+        assert!(g.get_slot_id_on(nid_add2, "lhs").is_some());
     }
-}
 
-/// Graph node to get any single particle attribute.
-#[derive(Debug, Clone)]
-pub struct AttributeNode {
-    /// The attribute to get.
-    attr: Attribute,
-    /// The output slot corresponding to the get value.
-    slots: [SlotDef; 1],
-}
+    #[test]
+    fn graph_replace_node() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_op = g.add_node(AddNode::default());
+        let nid_norm = g.add_node(NormalizeNode::default());
+        g.link(
+            g.output_slots(nid_pos)[0],
+            g.input_slot(nid_op, "lhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slots(nid_vel)[0],
+            g.input_slot(nid_op, "rhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slot(nid_op, "result").unwrap(),
+            g.input_slot(nid_norm, "in").unwrap(),
+        )
+        .unwrap();
+        g.set_node_enabled(nid_op, false);
 
-impl Default for AttributeNode {
-    fn default() -> Self {
-        Self::new(Attribute::POSITION)
-    }
-}
+        let eval = |g: &Graph| {
+            let mut module = Module::default();
+            let exprs = g.evaluate(&mut module).unwrap();
+            let property_layout = PropertyLayout::default();
+            let particle_layout = ParticleLayout::default();
+            let mut context =
+                ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+            context.eval(&module, exprs[0]).unwrap()
+        };
 
-impl AttributeNode {
-    /// Create a new attribute node for the given [`Attribute`].
-    pub fn new(attr: Attribute) -> Self {
-        Self {
-            attr,
-            slots: [SlotDef::output(attr.name(), Some(attr.value_type()))],
+        // Same slots, all links are kept.
+        let slot_count = g.slot_count();
+        let old_slots = g.slots(nid_op);
+        g.replace_node(nid_op, Box::new(MulNode::default()))
+            .unwrap();
+        assert!(g.get_node(nid_op).unwrap().as_any().is::<MulNode>());
+        assert!(!g.is_node_enabled(nid_op));
+        g.set_node_enabled(nid_op, true);
+        assert_eq!(g.slot_count(), slot_count);
+        let new_slots = g.slots(nid_op);
+        assert_eq!(new_slots.len(), 3);
+        assert!(old_slots.iter().all(|id| g.slot_def(*id).is_none()));
+        let sid_lhs = g.input_slot(nid_op, "lhs").unwrap();
+        let sid_rhs = g.input_slot(nid_op, "rhs").unwrap();
+        let sid_result = g.output_slot(nid_op, "result").unwrap();
+        assert_eq!(new_slots, vec![sid_lhs, sid_rhs, sid_result]);
+        assert_eq!(
+            g.get_slot(sid_lhs).linked_slots,
+            vec![g.output_slots(nid_pos)[0]]
+        );
+        assert_eq!(
+            g.get_slot(sid_rhs).linked_slots,
+            vec![g.output_slots(nid_vel)[0]]
+        );
+        assert_eq!(
+            g.get_slot(g.output_slots(nid_pos)[0]).linked_slots,
+            vec![sid_lhs]
+        );
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        assert_eq!(g.get_slot(sid_result).linked_slots, vec![sid_norm_in]);
+        assert_eq!(g.get_slot(sid_norm_in).linked_slots, vec![sid_result]);
+        assert!(g.validate().is_ok());
+        assert_eq!(
+            eval(&g),
+            "normalize((particle.position) * (particle.velocity))"
+        );
+
+        // A kept link between slots of different types is rejected, and the
+        // graph is left unchanged.
+        let ret = g.replace_node(nid_op, Box::new(AndNode::default()));
+        assert_eq!(
+            ret,
+            Err(GraphError::TypeMismatch {
+                output: g.output_slots(nid_pos)[0],
+                input: sid_lhs,
+                output_type: ValueType::Vector(VectorType::VEC3F),
+                input_type: ValueType::Scalar(ScalarType::Bool),
+            })
+        );
+        assert!(g.get_node(nid_op).unwrap().as_any().is::<MulNode>());
+        assert_eq!(g.slots(nid_op), new_slots);
+        assert_eq!(g.slot_count(), slot_count);
+
+        // Different slots, all links are dropped.
+        g.replace_node(nid_op, Box::new(AttributeNode::new(Attribute::AGE)))
+            .unwrap();
+        assert_eq!(g.slots(nid_op).len(), 1);
+        assert_eq!(g.slot_count(), slot_count - 2);
+        assert!(new_slots.iter().all(|id| g.slot_def(*id).is_none()));
+        for nid in [nid_op, nid_pos, nid_vel, nid_norm] {
+            for sid in g.slots(nid) {
+                assert!(g.get_slot(sid).linked_slots.is_empty());
+            }
         }
+        assert_eq!(
+            g.validate(),
+            Err(vec![GraphError::UnlinkedInput { input: sid_norm_in }])
+        );
     }
-}
 
-impl AttributeNode {
-    /// Get the attribute this node reads.
-    pub fn attr(&self) -> Attribute {
-        self.attr
-    }
+    #[test]
+    fn graph_describe() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        g.link(sid_pos, sid_norm_in).unwrap();
 
-    /// Set the attribute this node reads.
-    pub fn set_attr(&mut self, attr: Attribute) {
-        self.attr = attr;
+        assert_eq!(
+            g.describe(),
+            r#"#1 AttributeNode
+    out position: vec3<f32> -> #2 NormalizeNode.in
+#2 NormalizeNode
+    in in <- #1 AttributeNode.position
+    out out
+"#
+        );
+
+        // Labels are used to refer to nodes, and disabled nodes are marked.
+        g.get_node_mut(nid_pos)
+            .unwrap()
+            .set_label(Some("first".to_string()));
+        g.set_node_enabled(nid_norm, false);
+        assert_eq!(
+            g.describe(),
+            r#"#1 AttributeNode "first"
+    out position: vec3<f32> -> #2 NormalizeNode.in
+#2 NormalizeNode (disabled)
+    in in <- #1 first.position
+    out out
+"#
+        );
     }
-}
 
-impl Node for AttributeNode {
-    fn slots(&self) -> &[SlotDef] {
-        &self.slots
+    #[test]
+    fn graph_links() {
+        let mut g = Graph::new();
+        assert!(g.links().is_empty());
+
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_add = g.add_node(AddNode::default());
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let nid_len = g.add_node(LengthNode::default());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_vel = g.output_slots(nid_vel)[0];
+        let sid_add_lhs = g.input_slot(nid_add, "lhs").unwrap();
+        let sid_add_rhs = g.input_slot(nid_add, "rhs").unwrap();
+        let sid_add_out = g.output_slot(nid_add, "result").unwrap();
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        let sid_len_in = g.input_slots(nid_len)[0];
+        g.link(sid_add_out, sid_len_in).unwrap();
+        g.link(sid_pos, sid_add_lhs).unwrap();
+        g.link(sid_vel, sid_add_rhs).unwrap();
+        g.link(sid_add_out, sid_norm_in).unwrap();
+
+        assert_eq!(
+            g.links(),
+            vec![
+                (sid_pos, sid_add_lhs),
+                (sid_vel, sid_add_rhs),
+                (sid_add_out, sid_len_in),
+                (sid_add_out, sid_norm_in),
+            ]
+        );
+
+        // Unlinked and removed links are not returned.
+        g.unlink(sid_add_out, sid_len_in);
+        g.remove_node(nid_pos);
+        assert_eq!(
+            g.links(),
+            vec![(sid_vel, sid_add_rhs), (sid_add_out, sid_norm_in)]
+        );
     }
 
-    fn eval(
-        &self,
-        module: &mut Module,
-        inputs: Vec<ExprHandle>,
-    ) -> Result<Vec<ExprHandle>, ExprError> {
-        if !inputs.is_empty() {
-            return Err(ExprError::GraphEvalError(
-                "Unexpected non-empty input to
-AttributeNode::eval()."
-                    .to_string(),
-            ));
+    #[test]
+    fn graph_connections() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let nid_len = g.add_node(LengthNode::default());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        let sid_norm_out = g.output_slot(nid_norm, "out").unwrap();
+        let sid_len_in = g.input_slots(nid_len)[0];
+        for sid in [sid_pos, sid_norm_in, sid_norm_out, sid_len_in] {
+            assert!(!g.is_connected(sid));
+            assert!(g.connections(sid).is_empty());
         }
-        let attr = module.attr(self.attr);
-        Ok(vec![attr])
-    }
-}
 
-/// Graph node to get various time values related to the effect system.
-#[derive(Debug, Clone)]
-pub struct TimeNode {
-    /// Output slots corresponding to the various time-related quantities.
-    slots: [SlotDef; 2],
-}
+        g.link(sid_pos, sid_norm_in).unwrap();
+        g.link(sid_pos, sid_len_in).unwrap();
+        assert!(g.is_connected(sid_pos));
+        assert!(g.is_connected(sid_norm_in));
+        assert!(g.is_connected(sid_len_in));
+        assert!(!g.is_connected(sid_norm_out));
+        assert_eq!(g.connections(sid_pos), &[sid_norm_in, sid_len_in]);
+        assert_eq!(g.connections(sid_norm_in), &[sid_pos]);
+        assert_eq!(g.connections(sid_len_in), &[sid_pos]);
 
-impl Default for TimeNode {
-    fn default() -> Self {
-        Self {
-            slots: [BuiltInOperator::Time, BuiltInOperator::DeltaTime]
-                .map(|op| SlotDef::output(op.name(), Some(op.value_type()))),
-        }
+        g.unlink(sid_pos, sid_norm_in);
+        assert!(!g.is_connected(sid_norm_in));
+        assert!(g.is_connected(sid_pos));
+        assert_eq!(g.connections(sid_pos), &[sid_len_in]);
     }
-}
 
-impl Node for TimeNode {
-    fn slots(&self) -> &[SlotDef] {
-        &self.slots
-    }
+    #[test]
+    fn graph_unconnected_inputs() {
+        let mut g = Graph::new();
+        assert!(g.unconnected_inputs().is_empty());
 
-    fn eval(
-        &self,
-        module: &mut Module,
-        inputs: Vec<ExprHandle>,
-    ) -> Result<Vec<ExprHandle>, ExprError> {
-        if !inputs.is_empty() {
-            return Err(ExprError::GraphEvalError(
-                "Unexpected non-empty input to
-TimeNode::eval()."
-                    .to_string(),
-            ));
-        }
-        Ok([BuiltInOperator::Time, BuiltInOperator::DeltaTime]
-            .map(|op| module.builtin(op))
-            .to_vec())
+        let nid_time = g.add_node(TimeNode::default());
+        let nid_add = g.add_node(AddNode::default());
+        let sid_lhs = g.input_slot(nid_add, "lhs").unwrap();
+        let sid_rhs = g.input_slot(nid_add, "rhs").unwrap();
+        assert_eq!(g.unconnected_inputs(), vec![sid_lhs, sid_rhs]);
+
+        g.link(g.output_slot(nid_time, "time").unwrap(), sid_lhs)
+            .unwrap();
+        assert_eq!(g.unconnected_inputs(), vec![sid_rhs]);
+
+        // Inputs with a default value, or accepting multiple inputs, don't
+        // need a link.
+        g.add_node(OscillatorNode::default());
+        g.add_node(AggregateNode::new(AggregateOp::Sum));
+        assert_eq!(g.unconnected_inputs(), vec![sid_rhs]);
+        assert_eq!(
+            g.validate(),
+            Err(vec![GraphError::UnlinkedInput { input: sid_rhs }])
+        );
     }
-}
 
-/// Graph node to normalize a vector value.
-#[derive(Debug, Clone)]
-pub struct NormalizeNode {
-    /// Input and output vectors.
-    slots: [SlotDef; 2],
-}
+    #[test]
+    fn graph_debug_tap_modifiers() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_tap = g.add_node(DebugTapNode::new(Attribute::F32X3_0));
+        let nid_norm = g.add_node(NormalizeNode::default());
+        g.link(
+            g.output_slots(nid_pos)[0],
+            g.input_slot(nid_tap, "in").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slot(nid_tap, "out").unwrap(),
+            g.input_slot(nid_norm, "in").unwrap(),
+        )
+        .unwrap();
+        assert!(g.validate().is_ok());
 
-impl Default for NormalizeNode {
-    fn default() -> Self {
-        Self {
-            slots: [SlotDef::output("in", None), SlotDef::output("out", None)],
+        // The tap doesn't change the graph result.
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "normalize(particle.position)");
+
+        let modifiers = g.debug_tap_modifiers(&mut module).unwrap();
+        if cfg!(feature = "debug") {
+            assert_eq!(modifiers.len(), 1);
+            assert_eq!(modifiers[0].attribute, Attribute::F32X3_0);
+            let str = context.eval(&module, modifiers[0].value).unwrap();
+            assert_eq!(str, "particle.position");
+        } else {
+            assert!(modifiers.is_empty());
         }
-    }
-}
 
-impl Node for NormalizeNode {
-    fn slots(&self) -> &[SlotDef] {
-        &self.slots
+        // Disabled taps are skipped.
+        g.set_node_enabled(nid_tap, false);
+        assert!(g.debug_tap_modifiers(&mut module).unwrap().is_empty());
+
+        // Tapping a value of another type than the attribute is invalid.
+        let nid_bad = g.add_node(DebugTapNode::new(Attribute::F32_0));
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_bad_in = g.input_slot(nid_bad, "in").unwrap();
+        g.link(sid_pos, sid_bad_in).unwrap();
+        let errors = g.validate().unwrap_err();
+        assert!(errors.contains(&GraphError::TypeMismatch {
+            output: sid_pos,
+            input: sid_bad_in,
+            output_type: ValueType::Vector(VectorType::VEC3F),
+            input_type: ValueType::Scalar(ScalarType::Float),
+        }));
     }
 
-    fn eval(
-        &self,
-        module: &mut Module,
-        inputs: Vec<ExprHandle>,
-    ) -> Result<Vec<ExprHandle>, ExprError> {
-        if inputs.len() != 1 {
-            return Err(ExprError::GraphEvalError(
-                "Unexpected input slot count to NormalizeNode::eval() not
-equal to one."
-                    .to_string(),
-            ));
-        }
-        let input = inputs.into_iter().next().unwrap();
-        let norm = module.normalize(input);
-        Ok(vec![norm])
+    #[test]
+    fn graph_referenced_attributes() {
+        let mut g = Graph::new();
+        assert!(g.referenced_attributes().is_empty());
+
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let sid_add = g
+            .add_binary(
+                BinaryOperator::Add,
+                g.output_slots(nid_pos)[0],
+                g.output_slots(nid_vel)[0],
+            )
+            .unwrap();
+        let nid_norm = g.add_node(NormalizeNode::default());
+        g.link(sid_add, g.input_slot(nid_norm, "in").unwrap())
+            .unwrap();
+        assert_eq!(
+            g.referenced_attributes(),
+            HashSet::from([Attribute::POSITION, Attribute::VELOCITY])
+        );
+
+        let nid_deriv = g.add_node(DerivativeNode::new(Attribute::AGE, Attribute::F32_0));
+        assert_eq!(
+            g.referenced_attributes(),
+            HashSet::from([
+                Attribute::POSITION,
+                Attribute::VELOCITY,
+                Attribute::AGE,
+                Attribute::F32_0
+            ])
+        );
+
+        // Disabled nodes don't read their attributes.
+        g.set_node_enabled(nid_deriv, false);
+        assert_eq!(g.referenced_attributes().len(), 2);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use bevy::prelude::*;
+    #[test]
+    fn graph_default_input() {
+        /// Test node scaling its input by a factor defaulting to 2.
+        #[derive(Clone, Serialize, Deserialize)]
+        struct ScaleNode {
+            slots: [SlotDef; 3],
+        }
 
-    use super::*;
-    use crate::{EvalContext, ModifierContext, ParticleLayout, PropertyLayout, ShaderWriter};
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl Node for ScaleNode {
+            fn slots(&self) -> &[SlotDef] {
+                &self.slots
+            }
 
-    #[test]
-    fn add() {
-        let node = AddNode::default();
+            fn boxed_clone(&self) -> BoxedNode {
+                Box::new(self.clone())
+            }
 
-        let mut module = Module::default();
+            fn eval(
+                &self,
+                module: &mut Module,
+                inputs: Vec<ExprHandle>,
+            ) -> Result<Vec<ExprHandle>, ExprError> {
+                Ok(vec![module.mul(inputs[0], inputs[1])])
+            }
+        }
 
-        let ret = node.eval(&mut module, vec![]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
-        let three = module.lit(3.);
-        let ret = node.eval(&mut module, vec![three]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
+        let scale_type = ValueType::Scalar(ScalarType::Float);
+        let slot_def = SlotDef::input_with_default("scale", Some(scale_type), 2_f32.into());
+        assert!(slot_def.is_input());
+        assert_eq!(slot_def.value_type(), Some(scale_type));
+        assert_eq!(slot_def.default_value(), Some(2_f32.into()));
 
-        let two = module.lit(2.);
-        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
-        assert_eq!(outputs.len(), 1);
-        let out = outputs[0];
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_scale = g.add_node(ScaleNode {
+            slots: [
+                SlotDef::input("in", None),
+                slot_def,
+                SlotDef::output("out", None),
+            ],
+        });
+        g.link(
+            g.output_slots(nid_pos)[0],
+            g.input_slot(nid_scale, "in").unwrap(),
+        )
+        .unwrap();
 
+        // The "scale" input is not linked, but has a default value.
+        assert_eq!(g.validate(), Ok(()));
+
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
         let property_layout = PropertyLayout::default();
         let particle_layout = ParticleLayout::default();
         let mut context =
             ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-        let str = context.eval(&module, out).unwrap();
-        assert_eq!(str, "(3.) + (2.)".to_string());
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "(particle.position) * (2.)");
+
+        // A linked input ignores its default value.
+        let nid_age = g.add_node(AttributeNode::new(Attribute::AGE));
+        g.link(
+            g.output_slots(nid_age)[0],
+            g.input_slot(nid_scale, "scale").unwrap(),
+        )
+        .unwrap();
+        let exprs = g.evaluate(&mut module).unwrap();
+        let str = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, "(particle.position) * (particle.age)");
     }
 
     #[test]
-    fn sub() {
-        let node = SubNode::default();
+    fn graph_inspect_literal_inputs() {
+        let mut g = Graph::new();
+        let nid_a = g.add_node(ConstantNode::scalar(2.));
+        let nid_b = g.add_node(ConstantNode::scalar(3.));
+        g.add_binary(
+            BinaryOperator::Add,
+            g.output_slots(nid_a)[0],
+            g.output_slots(nid_b)[0],
+        )
+        .unwrap();
 
         let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
 
-        let ret = node.eval(&mut module, vec![]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
-        let three = module.lit(3.);
-        let ret = node.eval(&mut module, vec![three]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
+        // Both operands of the addition are literals, so could be folded.
+        let Some(&Expr::Binary {
+            op: BinaryOperator::Add,
+            left,
+            right,
+        }) = module.get(exprs[0])
+        else {
+            panic!("Expected an addition.");
+        };
+        let operands: Vec<_> = [left, right]
+            .iter()
+            .map(|&expr| {
+                module
+                    .get(expr)
+                    .unwrap()
+                    .as_literal()
+                    .map(|lit| lit.value())
+            })
+            .collect();
+        assert_eq!(
+            operands,
+            vec![
+                Some(Value::Scalar(2_f32.into())),
+                Some(Value::Scalar(3_f32.into()))
+            ]
+        );
+        assert!(module.get(exprs[0]).unwrap().as_literal().is_none());
+    }
 
-        let two = module.lit(2.);
-        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
-        assert_eq!(outputs.len(), 1);
-        let out = outputs[0];
+    #[test]
+    fn graph_evaluate_folded() {
         let property_layout = PropertyLayout::default();
         let particle_layout = ParticleLayout::default();
-        let mut context =
-            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-        let str = context.eval(&module, out).unwrap();
-        assert_eq!(str, "(3.) - (2.)".to_string());
+        let eval = |module: &Module, expr| {
+            let mut context =
+                ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+            context.eval(module, expr).unwrap()
+        };
+
+        // 3 + 2 folds to 5
+        let mut g = Graph::new();
+        let nid_3 = g.add_node(ConstantNode::scalar(3.));
+        let nid_2 = g.add_node(ConstantNode::scalar(2.));
+        let sid_3 = g.output_slots(nid_3)[0];
+        let sid_2 = g.output_slots(nid_2)[0];
+        let sid_add = g.add_binary(BinaryOperator::Add, sid_3, sid_2).unwrap();
+        let mut module = Module::default();
+        let exprs = g.evaluate_folded(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
+        let literal = module.get(exprs[0]).unwrap().as_literal().unwrap();
+        assert_eq!(literal.value(), Value::Scalar(5_f32.into()));
+        assert_eq!(eval(&module, exprs[0]), "5.");
+
+        // Without folding, the addition is kept
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
+        assert_eq!(eval(&module, exprs[0]), "(3.) + (2.)");
+
+        // Folding propagates along chains of nodes, and broadcasts scalars
+        let nid_v = g.add_node(ConstantNode::new(Vec3::new(1., 2., 4.)));
+        let sid_v = g.output_slots(nid_v)[0];
+        g.add_binary(BinaryOperator::Mul, sid_add, sid_v).unwrap();
+        let mut module = Module::default();
+        let exprs = g.evaluate_folded(&mut module).unwrap();
+        assert_eq!(exprs.len(), 1);
+        assert_eq!(eval(&module, exprs[0]), "vec3<f32>(5.,10.,20.)");
+
+        // 3 + x is not folded
+        let mut g = Graph::new();
+        let nid_3 = g.add_node(ConstantNode::scalar(3.));
+        let nid_x = g.add_node(AttributeNode::new(Attribute::AGE));
+        g.add_binary(
+            BinaryOperator::Add,
+            g.output_slots(nid_3)[0],
+            g.output_slots(nid_x)[0],
+        )
+        .unwrap();
+        let mut module = Module::default();
+        let exprs = g.evaluate_folded(&mut module).unwrap();
+        assert_eq!(eval(&module, exprs[0]), "(3.) + (particle.age)");
+
+        // Divisions by zero are not folded
+        let mut g = Graph::new();
+        let nid_3 = g.add_node(ConstantNode::scalar(3.));
+        let nid_0 = g.add_node(ConstantNode::new(Vec2::new(1., 0.)));
+        g.add_binary(
+            BinaryOperator::Div,
+            g.output_slots(nid_3)[0],
+            g.output_slots(nid_0)[0],
+        )
+        .unwrap();
+        let mut module = Module::default();
+        let exprs = g.evaluate_folded(&mut module).unwrap();
+        assert_eq!(eval(&module, exprs[0]), "(3.) / (vec2<f32>(1.,0.))");
     }
 
     #[test]
-    fn mul() {
-        let node = MulNode::default();
+    fn graph_validate() {
+        /// Test node with a single `Vec3` input.
+        #[derive(Clone, Serialize, Deserialize)]
+        struct Vec3SinkNode {
+            slots: [SlotDef; 1],
+        }
 
-        let mut module = Module::default();
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl Node for Vec3SinkNode {
+            fn slots(&self) -> &[SlotDef] {
+                &self.slots
+            }
 
-        let ret = node.eval(&mut module, vec![]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
-        let three = module.lit(3.);
-        let ret = node.eval(&mut module, vec![three]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
+            fn boxed_clone(&self) -> BoxedNode {
+                Box::new(self.clone())
+            }
 
-        let two = module.lit(2.);
-        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
-        assert_eq!(outputs.len(), 1);
-        let out = outputs[0];
-        let property_layout = PropertyLayout::default();
-        let particle_layout = ParticleLayout::default();
-        let mut context =
-            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-        let str = context.eval(&module, out).unwrap();
-        assert_eq!(str, "(3.) * (2.)".to_string());
+            fn eval(
+                &self,
+                _module: &mut Module,
+                _inputs: Vec<ExprHandle>,
+            ) -> Result<Vec<ExprHandle>, ExprError> {
+                Ok(vec![])
+            }
+        }
+
+        let vec3_sink = || Vec3SinkNode {
+            slots: [SlotDef::input(
+                "in",
+                Some(ValueType::Vector(VectorType::VEC3F)),
+            )],
+        };
+
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_sink = g.add_node(vec3_sink());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_sink = g.input_slots(nid_sink)[0];
+
+        // Unlinked input
+        assert_eq!(
+            g.validate(),
+            Err(vec![GraphError::UnlinkedInput { input: sid_sink }])
+        );
+
+        // Matching types
+        g.link(sid_pos, sid_sink).unwrap();
+        assert_eq!(g.validate(), Ok(()));
+
+        // Float output into a Vec3 input
+        let nid_age = g.add_node(AttributeNode::new(Attribute::AGE));
+        let nid_sink2 = g.add_node(vec3_sink());
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let sid_age = g.output_slots(nid_age)[0];
+        let sid_sink2 = g.input_slots(nid_sink2)[0];
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        g.link(sid_age, sid_sink2).unwrap();
+
+        // Variant input types are not checked, but unlinked ones are reported.
+        g.link(sid_age, sid_norm_in).unwrap();
+        assert_eq!(
+            g.validate(),
+            Err(vec![GraphError::TypeMismatch {
+                output: sid_age,
+                input: sid_sink2,
+                output_type: ValueType::Scalar(ScalarType::Float),
+                input_type: ValueType::Vector(VectorType::VEC3F),
+            }])
+        );
+
+        g.unlink(sid_age, sid_norm_in);
+        let errors = g.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.contains(&GraphError::UnlinkedInput { input: sid_norm_in }));
     }
 
     #[test]
-    fn div() {
-        let node = DivNode::default();
+    fn node_infer_output_types() {
+        let f32 = Some(ValueType::Scalar(ScalarType::Float));
+        let i32 = Some(ValueType::Scalar(ScalarType::Int));
+        let vec3 = Some(ValueType::Vector(VectorType::VEC3F));
+        let vec2 = Some(ValueType::Vector(VectorType::VEC2F));
+        let bool = Some(ValueType::Scalar(ScalarType::Bool));
 
-        let mut module = Module::default();
+        let add = AddNode::default();
+        assert_eq!(add.infer_output_types(&[vec3, vec3]), Ok(vec![vec3]));
+        assert_eq!(add.infer_output_types(&[f32, vec3]), Ok(vec![vec3]));
+        assert_eq!(add.infer_output_types(&[vec3, f32]), Ok(vec![vec3]));
+        assert_eq!(add.infer_output_types(&[None, vec3]), Ok(vec![None]));
+        assert_eq!(
+            add.infer_output_types(&[vec2, vec3]),
+            Err(GraphError::IncompatibleTypes {
+                node: None,
+                lhs: ValueType::Vector(VectorType::VEC2F),
+                rhs: ValueType::Vector(VectorType::VEC3F),
+            })
+        );
+        assert!(add.infer_output_types(&[i32, vec3]).is_err());
 
-        let ret = node.eval(&mut module, vec![]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
-        let three = module.lit(3.);
-        let ret = node.eval(&mut module, vec![three]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
+        let lt = LessThanNode::default();
+        assert_eq!(
+            lt.infer_output_types(&[vec3, vec3]),
+            Ok(vec![Some(ValueType::Vector(VectorType::VEC3B))])
+        );
+        assert_eq!(lt.infer_output_types(&[f32, f32]), Ok(vec![bool]));
+        assert_eq!(
+            lt.infer_output_types(&[f32, vec3]),
+            Err(GraphError::IncompatibleTypes {
+                node: None,
+                lhs: ValueType::Scalar(ScalarType::Float),
+                rhs: ValueType::Vector(VectorType::VEC3F),
+            })
+        );
+        assert!(EqualNode::default()
+            .infer_output_types(&[vec3, f32])
+            .is_err());
 
-        let two = module.lit(2.);
-        let outputs = node.eval(&mut module, vec![three, two]).unwrap();
-        assert_eq!(outputs.len(), 1);
-        let out = outputs[0];
-        let property_layout = PropertyLayout::default();
-        let particle_layout = ParticleLayout::default();
-        let mut context =
-            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-        let str = context.eval(&module, out).unwrap();
-        assert_eq!(str, "(3.) / (2.)".to_string());
+        let select = SelectNode::default();
+        assert_eq!(
+            select.infer_output_types(&[bool, vec3, vec3]),
+            Ok(vec![vec3])
+        );
+        assert_eq!(
+            select.infer_output_types(&[bool, None, vec3]),
+            Ok(vec![None])
+        );
+        assert_eq!(
+            select.infer_output_types(&[bool, f32, vec3]),
+            Err(GraphError::IncompatibleTypes {
+                node: None,
+                lhs: ValueType::Scalar(ScalarType::Float),
+                rhs: ValueType::Vector(VectorType::VEC3F),
+            })
+        );
+
+        let swizzle = SwizzleNode::new("xy").unwrap();
+        assert_eq!(swizzle.infer_output_types(&[vec3]), Ok(vec![vec2]));
+
+        // Default implementation
+        assert_eq!(
+            NormalizeNode::default().infer_output_types(&[vec3]),
+            Ok(vec![vec3])
+        );
+        assert_eq!(
+            TimeNode::default().infer_output_types(&[]),
+            Ok(vec![f32, f32])
+        );
     }
 
     #[test]
-    fn attr() {
-        let node = AttributeNode::new(Attribute::POSITION);
+    fn graph_validate_inferred_types() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
+        let nid_age = g.add_node(AttributeNode::new(Attribute::AGE));
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_vel = g.output_slots(nid_vel)[0];
+        let sid_age = g.output_slots(nid_age)[0];
 
-        let mut module = Module::default();
+        // vec3 + vec3 -> vec3, into a vec3 input
+        let sid_add = g.add_binary(BinaryOperator::Add, sid_pos, sid_vel).unwrap();
+        let nid_split = g.add_node(SplitVec3Node::default());
+        let sid_split = g.input_slots(nid_split)[0];
+        g.link(sid_add, sid_split).unwrap();
+        assert_eq!(g.validate(), Ok(()));
 
-        let three = module.lit(3.);
-        let ret = node.eval(&mut module, vec![three]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
+        // f32 * vec3 -> vec3, into a vec3 input
+        let sid_mul = g.add_binary(BinaryOperator::Mul, sid_age, sid_add).unwrap();
+        g.unlink(sid_add, sid_split);
+        g.link(sid_mul, sid_split).unwrap();
+        assert_eq!(g.validate(), Ok(()));
 
-        let outputs = node.eval(&mut module, vec![]).unwrap();
-        assert_eq!(outputs.len(), 1);
-        let out = outputs[0];
-        let property_layout = PropertyLayout::default();
-        let particle_layout = ParticleLayout::default();
-        let mut context =
-            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-        let str = context.eval(&module, out).unwrap();
-        assert_eq!(str, format!("particle.{}", Attribute::POSITION.name()));
+        // f32 * f32 -> f32, into a vec3 input
+        let sid_mul2 = g.add_binary(BinaryOperator::Mul, sid_age, sid_age).unwrap();
+        g.unlink(sid_mul, sid_split);
+        g.link(sid_mul2, sid_split).unwrap();
+        assert_eq!(
+            g.validate(),
+            Err(vec![GraphError::TypeMismatch {
+                output: sid_mul2,
+                input: sid_split,
+                output_type: ValueType::Scalar(ScalarType::Float),
+                input_type: ValueType::Vector(VectorType::VEC3F),
+            }])
+        );
+        g.unlink(sid_mul2, sid_split);
+        g.link(sid_mul, sid_split).unwrap();
+
+        // vec3 - vec2
+        let nid_swizzle = g.add_node(SwizzleNode::new("xy").unwrap());
+        g.link(sid_pos, g.input_slots(nid_swizzle)[0]).unwrap();
+        let sid_sub = g
+            .add_binary(BinaryOperator::Sub, sid_pos, g.output_slots(nid_swizzle)[0])
+            .unwrap();
+        let nid_sub = g.get_slot(sid_sub).node_id();
+        let errors = g.validate().unwrap_err();
+        assert!(errors.contains(&GraphError::IncompatibleTypes {
+            node: Some(nid_sub),
+            lhs: ValueType::Vector(VectorType::VEC3F),
+            rhs: ValueType::Vector(VectorType::VEC2F),
+        }));
     }
 
     #[test]
-    fn time() {
-        let node = TimeNode::default();
+    fn graph_validate_array_types() {
+        /// Test node with a single array slot.
+        #[derive(Clone, Serialize, Deserialize)]
+        struct ArrayNode {
+            slots: [SlotDef; 1],
+        }
 
-        let mut module = Module::default();
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl Node for ArrayNode {
+            fn slots(&self) -> &[SlotDef] {
+                &self.slots
+            }
 
-        let three = module.lit(3.);
-        let ret = node.eval(&mut module, vec![three]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
+            fn boxed_clone(&self) -> BoxedNode {
+                Box::new(self.clone())
+            }
 
-        let outputs = node.eval(&mut module, vec![]).unwrap();
-        assert_eq!(outputs.len(), 2);
-        let property_layout = PropertyLayout::default();
-        let particle_layout = ParticleLayout::default();
-        let mut context =
-            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-        let str0 = context.eval(&module, outputs[0]).unwrap();
-        let str1 = context.eval(&module, outputs[1]).unwrap();
-        assert_eq!(str0, format!("sim_params.{}", BuiltInOperator::Time.name()));
+            fn eval(
+                &self,
+                _module: &mut Module,
+                _inputs: Vec<ExprHandle>,
+            ) -> Result<Vec<ExprHandle>, ExprError> {
+                unimplemented!()
+            }
+        }
+
+        let array_source = |elem_type: ScalarType, count: u32| ArrayNode {
+            slots: [SlotDef::output(
+                "out",
+                Some(ValueType::Array(ArrayType::new(elem_type, count))),
+            )],
+        };
+        let array_node = |elem_type: ScalarType, count: u32| ArrayNode {
+            slots: [SlotDef::input(
+                "in",
+                Some(ValueType::Array(ArrayType::new(elem_type, count))),
+            )],
+        };
+
+        let mut g = Graph::new();
+        let nid_src = g.add_node(array_source(ScalarType::Float, 4));
+        let nid_dst = g.add_node(array_node(ScalarType::Float, 4));
+        let sid_src = g.output_slots(nid_src)[0];
+        let sid_dst = g.input_slots(nid_dst)[0];
+
+        // Matching element type and count
+        g.link(sid_src, sid_dst).unwrap();
+        assert_eq!(g.validate(), Ok(()));
+
+        // Mismatching count
+        let nid_dst2 = g.add_node(array_node(ScalarType::Float, 3));
+        let sid_dst2 = g.input_slots(nid_dst2)[0];
+        g.link(sid_src, sid_dst2).unwrap();
         assert_eq!(
-            str1,
-            format!("sim_params.{}", BuiltInOperator::DeltaTime.name())
+            g.validate(),
+            Err(vec![GraphError::TypeMismatch {
+                output: sid_src,
+                input: sid_dst2,
+                output_type: ValueType::Array(ArrayType::new(ScalarType::Float, 4)),
+                input_type: ValueType::Array(ArrayType::new(ScalarType::Float, 3)),
+            }])
+        );
+        g.remove_node(nid_dst2);
+
+        // Mismatching element type
+        let nid_dst3 = g.add_node(array_node(ScalarType::Int, 4));
+        let sid_dst3 = g.input_slots(nid_dst3)[0];
+        g.link(sid_src, sid_dst3).unwrap();
+        assert_eq!(
+            g.validate(),
+            Err(vec![GraphError::TypeMismatch {
+                output: sid_src,
+                input: sid_dst3,
+                output_type: ValueType::Array(ArrayType::new(ScalarType::Float, 4)),
+                input_type: ValueType::Array(ArrayType::new(ScalarType::Int, 4)),
+            }])
         );
     }
 
     #[test]
-    fn normalize() {
-        let node = NormalizeNode::default();
+    fn node_name() {
+        assert_eq!(AddNode::default().name(), "AddNode");
+        assert_eq!(SwizzleNode::new("x").unwrap().name(), "SwizzleNode");
+        let node: Box<dyn Node> = Box::new(TimeNode::default());
+        assert_eq!(node.name(), "TimeNode");
+    }
 
-        let mut module = Module::default();
+    #[test]
+    fn graph_to_dot() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_norm = g.add_node(NormalizeNode::default());
+        let sid_pos = g.output_slots(nid_pos)[0];
+        let sid_norm_in = g.input_slot(nid_norm, "in").unwrap();
+        g.link(sid_pos, sid_norm_in).unwrap();
 
-        let ret = node.eval(&mut module, vec![]);
-        assert!(matches!(ret, Err(ExprError::GraphEvalError(_))));
+        let dot = g.to_dot();
+        assert_eq!(
+            dot,
+            r#"digraph {
+    node [shape=record];
+    n1 [label="{AttributeNode|{<s1> position}}"];
+    n2 [label="{{<s2> in}|NormalizeNode|{<s3> out}}"];
+    n1:s1 -> n2:s2;
+}
+"#
+        );
+    }
 
-        let ones = module.lit(Vec3::ONE);
-        let outputs = node.eval(&mut module, vec![ones]).unwrap();
-        assert_eq!(outputs.len(), 1);
+    #[test]
+    fn id_serde() {
+        let node_id = NodeId::new(NonZeroU32::new(5).unwrap());
+        let s = ron::to_string(&node_id).unwrap();
+        assert_eq!(s, "5");
+        assert_eq!(ron::from_str::<NodeId>(&s).unwrap(), node_id);
+        assert!(ron::from_str::<NodeId>("0").is_err());
+
+        let slot_id = SlotId::new(NonZeroU32::new(42).unwrap());
+        let s = ron::to_string(&slot_id).unwrap();
+        assert_eq!(s, "42");
+        assert_eq!(ron::from_str::<SlotId>(&s).unwrap(), slot_id);
+        assert!(ron::from_str::<SlotId>("0").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn graph_serde_ron() {
+        let mut g = Graph::new();
+        let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
+        let nid_swizzle = g.add_node(SwizzleNode::new("zyx").unwrap());
+        let nid_time = g.add_node(TimeNode::default());
+        let nid_mul = g.add_node(MulNode::default());
+        let nid_removed = g.add_node(NormalizeNode::default());
+        g.link(
+            g.output_slots(nid_pos)[0],
+            g.input_slot(nid_swizzle, "in").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slot(nid_swizzle, "out").unwrap(),
+            g.input_slot(nid_mul, "lhs").unwrap(),
+        )
+        .unwrap();
+        g.link(
+            g.output_slot(nid_time, "time").unwrap(),
+            g.input_slot(nid_mul, "rhs").unwrap(),
+        )
+        .unwrap();
+        g.remove_node(nid_removed);
+
+        let s = ron::to_string(&g).unwrap();
+        let g_serde: Graph = ron::from_str(&s).unwrap();
+
+        assert_eq!(g.slots, g_serde.slots);
+        assert_eq!(g.nodes.len(), g_serde.nodes.len());
+        for (node, node_serde) in g.nodes.iter().zip(g_serde.nodes.iter()) {
+            assert_eq!(
+                node.as_ref().map(|n| n.name()),
+                node_serde.as_ref().map(|n| n.name())
+            );
+        }
+        assert_eq!(s, ron::to_string(&g_serde).unwrap());
+
+        // The deserialized graph evaluates to the same expressions.
         let property_layout = PropertyLayout::default();
         let particle_layout = ParticleLayout::default();
+        let mut module = Module::default();
+        let exprs = g.evaluate(&mut module).unwrap();
         let mut context =
             ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-        let str = context.eval(&module, outputs[0]).unwrap();
-        assert_eq!(str, "normalize(vec3<f32>(1.,1.,1.))".to_string());
+        let str = context.eval(&module, exprs[0]).unwrap();
+        let mut module = Module::default();
+        let exprs = g_serde.evaluate(&mut module).unwrap();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        let str_serde = context.eval(&module, exprs[0]).unwrap();
+        assert_eq!(str, str_serde);
+        assert_eq!(str, "((particle.position).zyx) * (sim_params.time)");
     }
 
     #[test]
-    fn graph() {
-        let mut g = Graph::new();
+    fn node_label() {
+        let mut node = AddNode::default();
+        assert_eq!(node.label(), None);
+        node.set_label(Some("first".to_string()));
+        assert_eq!(node.label(), Some("first"));
+        assert_eq!(node.name(), "AddNode");
 
+        let mut g = Graph::new();
+        let nid_first = g.add_node(node);
+        let mut node = AddNode::default();
+        node.set_label(Some("second".to_string()));
+        let nid_second = g.add_node(node);
         let nid_pos = g.add_node(AttributeNode::new(Attribute::POSITION));
-        let nid_add = g.add_node(AddNode::default());
         let sid_pos = g.output_slots(nid_pos)[0];
-        let sid_add_lhs = g.input_slots(nid_add)[0];
-        let sid_add_rhs = g.input_slots(nid_add)[1];
-        g.link(sid_pos, sid_add_lhs);
-
-        let nid_vel = g.add_node(AttributeNode::new(Attribute::VELOCITY));
-        let nid_mul = g.add_node(MulNode::default());
-        let nid_dt = g.add_node(TimeNode::default());
-        let sid_vel = g.output_slots(nid_vel)[0];
-        let sid_dt = g
-            .output_slot(nid_dt, BuiltInOperator::DeltaTime.name())
+        g.link(sid_pos, g.input_slot(nid_first, "lhs").unwrap())
             .unwrap();
-        let sid_mul_lhs = g.input_slots(nid_mul)[0];
-        let sid_mul_rhs = g.input_slots(nid_mul)[1];
-        g.link(sid_vel, sid_mul_lhs);
-        g.link(sid_dt, sid_mul_rhs);
+        g.link(sid_pos, g.input_slot(nid_first, "rhs").unwrap())
+            .unwrap();
+        g.link(
+            g.output_slots(nid_first)[0],
+            g.input_slot(nid_second, "lhs").unwrap(),
+        )
+        .unwrap();
 
-        let sid_mul_out = g.output_slots(nid_mul)[0];
-        g.link(sid_mul_out, sid_add_rhs);
+        // The "rhs" input of the second node is not linked.
+        let mut module = Module::default();
+        assert_eq!(
+            g.evaluate(&mut module),
+            Err(ExprError::GraphError(GraphError::UnlinkedInput {
+                input: g.input_slot(nid_second, "rhs").unwrap()
+            }))
+        );
+
+        assert!(g.to_dot().contains("|first|"));
+
+        // Errors raised by a node are prefixed with its label.
+        #[derive(Clone, Serialize, Deserialize)]
+        struct FailingNode {
+            slots: [SlotDef; 0],
+        }
+
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl Node for FailingNode {
+            fn slots(&self) -> &[SlotDef] {
+                &self.slots
+            }
+
+            fn boxed_clone(&self) -> BoxedNode {
+                Box::new(self.clone())
+            }
+
+            fn label(&self) -> Option<&str> {
+                Some("third")
+            }
+
+            fn eval(
+                &self,
+                _module: &mut Module,
+                _inputs: Vec<ExprHandle>,
+            ) -> Result<Vec<ExprHandle>, ExprError> {
+                Err(ExprError::GraphEvalError("Failed.".to_string()))
+            }
+        }
+
+        let mut g = Graph::new();
+        g.add_node(AttributeNode::new(Attribute::POSITION));
+        g.add_node(FailingNode { slots: [] });
+        let Err(ExprError::GraphEvalError(msg)) = g.evaluate(&mut module) else {
+            panic!("Expected evaluation error.");
+        };
+        assert_eq!(msg, "Node 'third' (FailingNode #2): Failed.");
     }
 }
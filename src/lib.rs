@@ -949,50 +949,68 @@ impl EffectShaderSource {
                 init_shader_source
             );
 
-            // Generate the shader code for the update shader
-            let (mut update_code, update_extra) = {
-                let mut update_context =
-                    ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
-                for m in asset.update_modifiers_for_group(dest_group_index) {
-                    if let Err(err) = m.apply(&mut module, &mut update_context) {
-                        error!(
-                            "Failed to compile effect '{}', error in update context: {}",
-                            asset.name, err
-                        );
-                        return Err(ShaderGenerateError::Expr(err));
-                    }
-                }
-                (update_context.main_code, update_context.extra_code)
-            };
-
-            // Insert Euler motion integration if needed.
+            // Generate the Euler motion integration code, if needed.
             let has_position = present_attributes.contains(&Attribute::POSITION);
             let has_velocity = present_attributes.contains(&Attribute::VELOCITY);
-            if asset.motion_integration != MotionIntegration::None {
-                if has_position && has_velocity {
-                    // Note the prepended "\n" to prevent appending to a comment line.
-                    let code = format!(
-                        "\nparticle.{0} += particle.{1} * sim_params.delta_time;\n",
-                        Attribute::POSITION.name(),
-                        Attribute::VELOCITY.name()
-                    );
-                    if asset.motion_integration == MotionIntegration::PreUpdate {
-                        update_code.insert_str(0, &code);
+            let integration_code = if asset.motion_integration == MotionIntegration::None {
+                None
+            } else if has_position && has_velocity {
+                // Note the prepended "\n" to prevent appending to a comment line.
+                Some(format!(
+                    "\nparticle.{0} += particle.{1} * sim_params.delta_time;\n",
+                    Attribute::POSITION.name(),
+                    Attribute::VELOCITY.name()
+                ))
+            } else {
+                warn!(
+                    "Asset {} specifies motion integration but is missing {}.",
+                    asset.name,
+                    if has_position {
+                        "Attribute::VELOCITY"
                     } else {
-                        update_code += &code;
+                        "Attribute::POSITION"
                     }
-                } else {
-                    warn!(
-                        "Asset {} specifies motion integration but is missing {}.",
-                        asset.name,
-                        if has_position {
-                            "Attribute::VELOCITY"
-                        } else {
-                            "Attribute::POSITION"
+                );
+                None
+            };
+
+            // Generate the shader code for the update shader, one update phase
+            // after the other, inserting the motion integration at the start
+            // or the end of the main phase.
+            let (update_code, update_extra) = {
+                let mut update_context =
+                    ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+                for phase in [
+                    UpdatePhase::PreIntegration,
+                    UpdatePhase::Main,
+                    UpdatePhase::PostIntegration,
+                ] {
+                    let is_main = phase == UpdatePhase::Main;
+                    if is_main && asset.motion_integration == MotionIntegration::PreUpdate {
+                        if let Some(code) = &integration_code {
+                            update_context.main_code += code;
                         }
-                    )
+                    }
+                    for m in asset
+                        .update_modifiers_for_group(dest_group_index)
+                        .filter(|m| m.update_phase() == phase)
+                    {
+                        if let Err(err) = m.apply(&mut module, &mut update_context) {
+                            error!(
+                                "Failed to compile effect '{}', error in update context: {}",
+                                asset.name, err
+                            );
+                            return Err(ShaderGenerateError::Expr(err));
+                        }
+                    }
+                    if is_main && asset.motion_integration == MotionIntegration::PostUpdate {
+                        if let Some(code) = &integration_code {
+                            update_context.main_code += code;
+                        }
+                    }
                 }
-            }
+                (update_context.main_code, update_context.extra_code)
+            };
 
             // Generate the shader code for the render shader
             let (
@@ -1935,6 +1953,12 @@ else { return c1; }
         let res = EffectShaderSource::generate(&asset);
         assert!(res.is_ok());
         let shader_source = res.unwrap();
+        validate_shader_source(&shader_source);
+    }
+
+    /// Compose all the shaders of an effect with the Hanabi common shader, and
+    /// validate them with naga.
+    fn validate_shader_source(shader_source: &EffectShaderSource) {
         for (name, code) in shader_source
             .shaders
             .iter()
@@ -2032,6 +2056,70 @@ else { return c1; }
         }
     }
 
+    #[test]
+    fn test_effect_shader_source_hash() {
+        // The hash function is defined in the common shader, and must be
+        // imported by the init, update, and render shaders.
+        let mut module = Module::default();
+        let zero = module.lit(Vec3::ZERO);
+        let seed = module.lit(42u32);
+        let hash = module.hash(seed);
+        let asset = EffectAsset::new(256, Spawner::rate(32.0.into()), module)
+            .with_simulation_space(SimulationSpace::Local)
+            .init(SetAttributeModifier::new(Attribute::POSITION, zero))
+            .init(SetAttributeModifier::new(Attribute::F32_0, hash))
+            .update(SetAttributeModifier::new(Attribute::F32_1, hash))
+            .render(OrientModifier::new(OrientMode::FaceCameraPosition).with_rotation(hash));
+        let res = EffectShaderSource::generate(&asset);
+        assert!(res.is_ok());
+        let shader_source = res.unwrap();
+        for shader in &shader_source.shaders {
+            assert!(shader.init.contains("hash_float01(42u)"));
+            assert!(shader.update.contains("hash_float01(42u)"));
+            assert!(shader.render.contains("hash_float01(42u)"));
+        }
+        validate_shader_source(&shader_source);
+    }
+
+    #[test]
+    fn test_effect_shader_update_phase() {
+        let mut module = Module::default();
+        let zero = module.lit(Vec3::ZERO);
+        let offset = module.add_property("offset", Vec3::ZERO.into());
+        let accel = AccelModifier::constant(&mut module, Vec3::new(0., -9.81, 0.));
+        let floating_origin = FloatingOriginModifier::via_property(&mut module, offset);
+        assert_eq!(accel.update_phase(), UpdatePhase::Main);
+        assert_eq!(floating_origin.update_phase(), UpdatePhase::PreIntegration);
+
+        // Add the floating origin last, to check it's moved before the others.
+        let mut asset = EffectAsset::new(256, Spawner::rate(32.0.into()), module)
+            .init(SetAttributeModifier::new(Attribute::POSITION, zero))
+            .init(SetAttributeModifier::new(Attribute::VELOCITY, zero))
+            .update(accel)
+            .update(floating_origin);
+
+        let floating_origin = "if (any(abs(properties.offset - particle.f32x3_0)";
+        let accel = "particle.velocity += (vec3<f32>(0.,-9.81,0.))";
+        let integration = "particle.position += particle.velocity * sim_params.delta_time;";
+        for (motion_integration, expected_order) in [
+            (
+                MotionIntegration::PostUpdate,
+                [floating_origin, accel, integration],
+            ),
+            (
+                MotionIntegration::PreUpdate,
+                [floating_origin, integration, accel],
+            ),
+        ] {
+            asset.motion_integration = motion_integration;
+            let shader_source = EffectShaderSource::generate(&asset).unwrap();
+            let update = &shader_source.shaders[0].update;
+            let positions = expected_order.map(|code| update.find(code).unwrap());
+            assert!(positions[0] < positions[1]);
+            assert!(positions[1] < positions[2]);
+        }
+    }
+
     // Regression test for #343
     #[test]
     fn test_compile_effect_invalid_handle() {
@@ -0,0 +1,115 @@
+//! Modifiers to change the rate at which particles age.
+//!
+//! The [`AgeRateModifier`] scales the aging of particles, to make them age
+//! faster or slower than the simulation time. This allows time dilation
+//! effects, where particles in some regions of space burn out faster than
+//! others.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expr::PropertyHandle,
+    graph::{BuiltInExpr, BuiltInOperator, ExprError},
+    Attribute, BoxedModifier, EvalContext, ExprHandle, Modifier, ModifierContext, Module,
+    ShaderWriter,
+};
+
+/// A modifier to scale the rate at which particles age.
+///
+/// The update pass already ages each particle by the simulation timestep
+/// before any modifier is applied. This modifier then adds a correction so
+/// that, overall, the particle aged by `rate` times the timestep this frame:
+///
+/// ```txt
+/// particle.age += (rate - 1.) * simulation.delta_time;
+/// ```
+///
+/// A rate of `1.` leaves the aging unchanged, a rate of `2.` makes particles
+/// age twice as fast, and a rate of `0.` freezes their age. The rate is
+/// evaluated per particle, so can depend for example on the particle position.
+/// Negative rates make particles younger; the age is not clamped to zero.
+///
+/// Because the built-in aging runs first, other update modifiers see the age
+/// already aged at the normal rate if applied before this modifier, and at the
+/// scaled rate if applied after it. Particles whose scaled age exceeds their
+/// lifetime are reaped at the end of the update pass, as usual.
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::AGE`]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct AgeRateModifier {
+    /// The aging rate, as a multiplier of the simulation timestep.
+    ///
+    /// Expression type: `f32`
+    pub rate: ExprHandle,
+}
+
+impl AgeRateModifier {
+    /// Create a new modifier from an aging rate expression.
+    pub fn new(rate: ExprHandle) -> Self {
+        Self { rate }
+    }
+
+    /// Create a new modifier with an aging rate derived from a property.
+    ///
+    /// To create a new property, use [`Module::add_property()`].
+    pub fn via_property(module: &mut Module, property: PropertyHandle) -> Self {
+        Self::new(module.prop(property))
+    }
+
+    /// Create a new modifier with a constant aging rate.
+    pub fn constant(module: &mut Module, rate: f32) -> Self {
+        Self::new(module.lit(rate))
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for AgeRateModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[Attribute::AGE]
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(*self)
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let age = module.attr(Attribute::AGE);
+        let age = context.eval(module, age)?;
+        let rate = context.eval(module, self.rate)?;
+        let dt = BuiltInExpr::new(BuiltInOperator::DeltaTime).eval(context)?;
+        context.main_code += &format!("{} += (({}) - 1.) * {};\n", age, rate, dt);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_age_rate() {
+        let mut module = Module::default();
+        let modifier = AgeRateModifier::constant(&mut module, 2.5);
+        assert_eq!(modifier.context(), ModifierContext::Update);
+        assert_eq!(modifier.attributes(), &[Attribute::AGE]);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            "particle.age += ((2.5) - 1.) * sim_params.delta_time;\n"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Modifiers to limit the speed of particles.
+//!
+//! The [`ClampVelocityModifier`] caps the magnitude of the particle velocity
+//! without changing its direction, which keeps simulations stable when strong
+//! accelerations or forces are applied.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expr::PropertyHandle, graph::ExprError, Attribute, BoxedModifier, EvalContext, ExprHandle,
+    Modifier, ModifierContext, Module, ShaderWriter,
+};
+
+/// A modifier to clamp the speed of all particles.
+///
+/// Each frame, the particle velocity is rescaled so its length never exceeds
+/// a maximum speed, while keeping its direction:
+///
+/// ```txt
+/// let speed = length(particle.velocity);
+/// if (speed > max_speed) {
+///     particle.velocity *= max_speed / speed;
+/// }
+/// ```
+///
+/// Particles slower than the maximum speed are not affected. The maximum
+/// speed must be positive or zero; a zero maximum speed stops all particles.
+/// Since the velocity is modified directly, this modifier should be applied
+/// after any other modifier accelerating the particles.
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::VELOCITY`]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct ClampVelocityModifier {
+    /// The maximum speed of the particles.
+    ///
+    /// Expression type: `f32`
+    pub max_speed: ExprHandle,
+}
+
+impl ClampVelocityModifier {
+    /// Create a new modifier from a maximum speed expression.
+    pub fn new(max_speed: ExprHandle) -> Self {
+        Self { max_speed }
+    }
+
+    /// Create a new modifier with a maximum speed derived from a property.
+    ///
+    /// To create a new property, use [`Module::add_property()`].
+    pub fn via_property(module: &mut Module, property: PropertyHandle) -> Self {
+        Self::new(module.prop(property))
+    }
+
+    /// Create a new modifier with a constant maximum speed.
+    pub fn constant(module: &mut Module, max_speed: f32) -> Self {
+        Self::new(module.lit(max_speed))
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for ClampVelocityModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[Attribute::VELOCITY]
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(*self)
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let vel = module.attr(Attribute::VELOCITY);
+        let vel = context.eval(module, vel)?;
+        let max_speed = context.eval(module, self.max_speed)?;
+
+        // Also check the speed is non-zero, to never divide by zero even with
+        // an invalid negative maximum speed.
+        let speed_var = context.make_local_var();
+        let max_speed_var = context.make_local_var();
+        context.main_code += &format!(
+            r#"let {1} = length({0});
+let {2} = {3};
+if ({1} > {2} && {1} > 0.) {{
+    {0} *= {2} / {1};
+}}
+"#,
+            vel, speed_var, max_speed_var, max_speed
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_clamp_velocity() {
+        let mut module = Module::default();
+        let modifier = ClampVelocityModifier::constant(&mut module, 4.5);
+        assert_eq!(modifier.context(), ModifierContext::Update);
+        assert_eq!(modifier.attributes(), &[Attribute::VELOCITY]);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"let var0 = length(particle.velocity);
+let var1 = 4.5;
+if (var0 > var1 && var0 > 0.) {
+    particle.velocity *= var1 / var0;
+}
+"#
+        );
+    }
+}
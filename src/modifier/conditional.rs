@@ -0,0 +1,320 @@
+//! Modifiers to apply another modifier only under some condition.
+//!
+//! The [`ConditionalModifier`] wraps any other modifier, and only applies it to
+//! the particles for which a boolean expression evaluates to `true`. This
+//! allows reusing existing modifiers for a subset of the particles, for
+//! example to apply some drag only to the particles moving fast enough.
+//!
+//! The [`SpawnWindowModifier`] is a specialized conditional modifier which
+//! applies its inner modifier only during a window of the simulation time.
+
+use bevy::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expr::PropertyHandle, graph::ExprError, Attribute, BoxedModifier, BuiltInOperator, EvalContext,
+    ExprHandle, Modifier, ModifierContext, Module, ShaderWriter, UpdatePhase,
+};
+
+/// A modifier applying another modifier only when a condition is true.
+///
+/// The shader code generated by the inner modifier is wrapped into a
+/// conditional block, so that it only executes for the particles for which the
+/// condition expression evaluates to `true`:
+///
+/// ```txt
+/// if (condition) {
+///     // inner modifier code
+/// }
+/// ```
+///
+/// Any expression evaluated by the inner modifier is local to that block.
+/// Expressions with side effects, like random values, shared with other
+/// modifiers are evaluated again by those other modifiers.
+///
+/// # Attributes
+///
+/// This modifier requires the attributes of the inner modifier, as well as any
+/// attribute read by the condition expression.
+#[derive(Clone, Reflect)]
+#[reflect(from_reflect = false)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConditionalModifier {
+    /// The condition under which the inner modifier applies.
+    ///
+    /// Expression type: `bool`
+    condition: ExprHandle,
+    /// The modifier applied when the condition is true.
+    #[reflect(ignore)]
+    inner: BoxedModifier,
+    /// Attributes required by the modifier. This is the attributes of the
+    /// inner modifier, followed by the extra ones read by the condition.
+    attributes: Vec<Attribute>,
+}
+
+impl ConditionalModifier {
+    /// Create a new modifier applying the `inner` modifier only when the
+    /// `condition` expression is true.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `condition` doesn't refer to an expression of `module`.
+    pub fn new(module: &Module, condition: ExprHandle, inner: BoxedModifier) -> Self {
+        let mut attributes = inner.attributes().to_vec();
+        for attr in module.used_attributes(condition) {
+            if !attributes.contains(&attr) {
+                attributes.push(attr);
+            }
+        }
+        Self {
+            condition,
+            inner,
+            attributes,
+        }
+    }
+
+    /// Get the condition under which the inner modifier applies.
+    pub fn condition(&self) -> ExprHandle {
+        self.condition
+    }
+
+    /// Get the modifier applied when the condition is true.
+    pub fn inner(&self) -> &dyn Modifier {
+        self.inner.as_ref()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for ConditionalModifier {
+    fn context(&self) -> ModifierContext {
+        self.inner.context()
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    fn update_phase(&self) -> UpdatePhase {
+        self.inner.update_phase()
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let condition = context.eval(module, self.condition)?;
+
+        // Generate the code of the inner modifier, then move it into the
+        // conditional block. Expressions evaluated inside that block can't be
+        // reused outside of it, so restore the cache once done.
+        let expr_cache = context.expr_cache.clone();
+        let start = context.main_code.len();
+        self.inner.apply(module, context)?;
+        let inner_code = context.main_code.split_off(start);
+        context.expr_cache = expr_cache;
+
+        context.main_code += &format!("if ({}) {{\n", condition);
+        for line in inner_code.lines() {
+            if !line.is_empty() {
+                context.main_code += "    ";
+                context.main_code += line;
+            }
+            context.main_code += "\n";
+        }
+        context.main_code += "}\n";
+
+        Ok(())
+    }
+}
+
+/// A modifier applying another modifier only during a time window.
+///
+/// This is a [`ConditionalModifier`] whose condition compares the simulation
+/// time with a start and an end bound, so that the inner modifier only applies
+/// while the time is in the `[start, end)` range:
+///
+/// ```txt
+/// if ((time >= start) && (time < end)) {
+///     // inner modifier code
+/// }
+/// ```
+///
+/// This allows for example to initialize the particles spawned during a time
+/// window differently, or to apply a force only for some time.
+///
+/// # Attributes
+///
+/// This modifier requires the attributes of the inner modifier, as well as any
+/// attribute read by the bound expressions.
+#[derive(Clone, Reflect)]
+#[reflect(from_reflect = false)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpawnWindowModifier {
+    /// The simulation time at which the window starts, inclusive.
+    ///
+    /// Expression type: `f32`
+    start: ExprHandle,
+    /// The simulation time at which the window ends, exclusive.
+    ///
+    /// Expression type: `f32`
+    end: ExprHandle,
+    /// The conditional modifier gating the inner modifier on the time window.
+    conditional: ConditionalModifier,
+}
+
+impl SpawnWindowModifier {
+    /// Create a new modifier applying the `inner` modifier only while the
+    /// simulation time is in the `[start, end)` range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` or `end` doesn't refer to an expression of `module`.
+    pub fn new(
+        module: &mut Module,
+        start: ExprHandle,
+        end: ExprHandle,
+        inner: BoxedModifier,
+    ) -> Self {
+        let time = module.builtin(BuiltInOperator::Time);
+        let after_start = module.ge(time, start);
+        let before_end = module.lt(time, end);
+        let condition = module.and(after_start, before_end);
+        Self {
+            start,
+            end,
+            conditional: ConditionalModifier::new(module, condition, inner),
+        }
+    }
+
+    /// Create a new modifier with constant time bounds.
+    pub fn constant(module: &mut Module, start: f32, end: f32, inner: BoxedModifier) -> Self {
+        let start = module.lit(start);
+        let end = module.lit(end);
+        Self::new(module, start, end, inner)
+    }
+
+    /// Create a new modifier with time bounds derived from properties.
+    ///
+    /// To create a new property, use [`Module::add_property()`].
+    pub fn via_property(
+        module: &mut Module,
+        start: PropertyHandle,
+        end: PropertyHandle,
+        inner: BoxedModifier,
+    ) -> Self {
+        let start = module.prop(start);
+        let end = module.prop(end);
+        Self::new(module, start, end, inner)
+    }
+
+    /// Get the simulation time at which the window starts, inclusive.
+    pub fn start(&self) -> ExprHandle {
+        self.start
+    }
+
+    /// Get the simulation time at which the window ends, exclusive.
+    pub fn end(&self) -> ExprHandle {
+        self.end
+    }
+
+    /// Get the modifier applied during the time window.
+    pub fn inner(&self) -> &dyn Modifier {
+        self.conditional.inner()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for SpawnWindowModifier {
+    fn context(&self) -> ModifierContext {
+        self.conditional.context()
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        self.conditional.attributes()
+    }
+
+    fn update_phase(&self) -> UpdatePhase {
+        self.conditional.update_phase()
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        self.conditional.apply(module, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FloatingOriginModifier, ParticleLayout, Property, PropertyLayout};
+
+    #[test]
+    fn mod_conditional() {
+        let mut module = Module::default();
+        let offset = module.add_property("offset", Vec3::X.into());
+        let age = module.attr(Attribute::AGE);
+        let one = module.lit(1.);
+        let condition = module.gt(age, one);
+        let inner = FloatingOriginModifier::via_property(&mut module, offset);
+        let modifier = ConditionalModifier::new(&module, condition, Box::new(inner.clone()));
+        assert_eq!(modifier.condition(), condition);
+        assert_eq!(modifier.context(), inner.context());
+        assert_eq!(
+            modifier.attributes(),
+            [inner.attributes(), &[Attribute::AGE]].concat()
+        );
+
+        let property_layout = PropertyLayout::new(&[Property::new("offset", Vec3::X)]);
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"if ((particle.age) > (1.)) {
+    if (any(abs(properties.offset - particle.f32x3_0) > vec3<f32>(0.0001))) {
+        particle.position += properties.offset - particle.f32x3_0;
+        particle.f32x3_0 = properties.offset;
+    }
+}
+"#
+        );
+
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            "if ((particle.age) > (1.)) {\n    particle.f32x3_0 = properties.offset;\n}\n"
+        );
+    }
+
+    #[test]
+    fn mod_spawn_window() {
+        let mut module = Module::default();
+        let offset = module.add_property("offset", Vec3::X.into());
+        let inner = FloatingOriginModifier::via_property(&mut module, offset);
+        let modifier = SpawnWindowModifier::constant(&mut module, 1., 2.5, Box::new(inner.clone()));
+        assert_eq!(modifier.context(), inner.context());
+        assert_eq!(modifier.attributes(), inner.attributes());
+        let start = module.get(modifier.start()).unwrap().as_literal().unwrap();
+        assert_eq!(start.value(), 1_f32.into());
+        let end = module.get(modifier.end()).unwrap().as_literal().unwrap();
+        assert_eq!(end.value(), 2.5_f32.into());
+
+        let property_layout = PropertyLayout::new(&[Property::new("offset", Vec3::X)]);
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            "if (((sim_params.time) >= (1.)) && ((sim_params.time) < (2.5))) {\n    particle.f32x3_0 = properties.offset;\n}\n"
+        );
+    }
+}
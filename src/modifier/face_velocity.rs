@@ -0,0 +1,155 @@
+//! Modifiers to orient particles along their velocity.
+//!
+//! The [`FaceVelocityModifier`] updates the local axes of each particle during
+//! the simulation so that the particle's X axis points along its velocity. The
+//! axes are stored in particle attributes, so they're available both to
+//! rendering and to other modifiers.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::ExprError, Attribute, BoxedModifier, EvalContext, ExprHandle, Modifier, ModifierContext,
+    Module, ShaderWriter,
+};
+
+/// A modifier to orient particles along their velocity.
+///
+/// Each frame, the local axes of the particle are rebuilt from its velocity and
+/// an `up` vector, as an orthonormal basis:
+///
+/// ```txt
+/// particle.axis_x = normalize(particle.velocity);
+/// particle.axis_z = normalize(cross(particle.velocity, up));
+/// particle.axis_y = cross(particle.axis_z, particle.axis_x);
+/// ```
+///
+/// The X axis points along the velocity, and the Y axis is the direction
+/// closest to `up` perpendicular to it. This orients for example aligned
+/// sprites like sparks or rain streaks. The `up` vector doesn't need to be
+/// normalized.
+///
+/// When the velocity is close to zero, or almost parallel to `up`, the axes
+/// are not well defined; the particle then keeps its previous axes, which
+/// avoids producing NaN values.
+///
+/// An [`OrientModifier`] recalculates the axes during rendering, so overrides
+/// this modifier if both are used.
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::VELOCITY`]
+/// - [`Attribute::AXIS_X`]
+/// - [`Attribute::AXIS_Y`]
+/// - [`Attribute::AXIS_Z`]
+///
+/// [`OrientModifier`]: crate::modifier::output::OrientModifier
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct FaceVelocityModifier {
+    /// The up vector the particle's Y axis is oriented toward.
+    ///
+    /// Expression type: `Vec3`
+    pub up: ExprHandle,
+}
+
+impl FaceVelocityModifier {
+    /// Create a new modifier from the expression of the up vector.
+    pub fn new(up: ExprHandle) -> Self {
+        Self { up }
+    }
+
+    /// Create a new modifier with a constant up vector.
+    pub fn constant(module: &mut Module, up: Vec3) -> Self {
+        Self::new(module.lit(up))
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for FaceVelocityModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[
+            Attribute::VELOCITY,
+            Attribute::AXIS_X,
+            Attribute::AXIS_Y,
+            Attribute::AXIS_Z,
+        ]
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(*self)
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let attr = module.attr(Attribute::VELOCITY);
+        let vel = context.eval(module, attr)?;
+        let attr = module.attr(Attribute::AXIS_X);
+        let axis_x = context.eval(module, attr)?;
+        let attr = module.attr(Attribute::AXIS_Y);
+        let axis_y = context.eval(module, attr)?;
+        let attr = module.attr(Attribute::AXIS_Z);
+        let axis_z = context.eval(module, attr)?;
+        let up = context.eval(module, self.up)?;
+
+        // The cross product is near zero if either the velocity is, or if it's
+        // parallel to the up vector; normalizing would then produce NaNs.
+        let vel_var = context.make_local_var();
+        let side_var = context.make_local_var();
+        context.main_code += &format!(
+            r#"let {0} = {1};
+let {2} = cross({0}, {3});
+if (dot({0}, {0}) > 0.000001 && dot({2}, {2}) > 0.000001) {{
+    {4} = normalize({0});
+    {6} = normalize({2});
+    {5} = cross({6}, {4});
+}}
+"#,
+            vel_var, vel, side_var, up, axis_x, axis_y, axis_z
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_face_velocity() {
+        let mut module = Module::default();
+        let modifier = FaceVelocityModifier::constant(&mut module, Vec3::Y);
+        assert_eq!(modifier.context(), ModifierContext::Update);
+        assert_eq!(
+            modifier.attributes(),
+            &[
+                Attribute::VELOCITY,
+                Attribute::AXIS_X,
+                Attribute::AXIS_Y,
+                Attribute::AXIS_Z
+            ]
+        );
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"let var0 = particle.velocity;
+let var1 = cross(var0, vec3<f32>(0.,1.,0.));
+if (dot(var0, var0) > 0.000001 && dot(var1, var1) > 0.000001) {
+    particle.axis_x = normalize(var0);
+    particle.axis_z = normalize(var1);
+    particle.axis_y = cross(particle.axis_z, particle.axis_x);
+}
+"#
+        );
+    }
+}
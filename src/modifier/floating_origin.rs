@@ -0,0 +1,641 @@
+//! Modifiers to support a floating origin.
+//!
+//! Large worlds often use a _floating origin_ to keep the camera close to the
+//! world origin and retain floating-point precision. When the origin moves, the
+//! entire world is translated, and so must be all existing particles. The
+//! [`FloatingOriginModifier`] applies that translation to all particles on the
+//! GPU, by tracking per particle the last translation offset it received.
+//! Similarly, the [`FloatingRotationModifier`] applies a rotation of the world
+//! around its origin, for worlds which also reorient.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expr::PropertyHandle, graph::ExprError, Attribute, BoxedModifier, EvalContext, ExprHandle,
+    Modifier, ModifierContext, Module, ShaderWriter, ToWgslString, UpdatePhase, ValueType,
+    VectorType,
+};
+
+/// A modifier to translate all particles when a floating origin moves.
+///
+/// Each particle stores the last translation offset it received into the
+/// [`Attribute::F32X3_0`] attribute. Each frame, the current translation offset
+/// is compared with that stored value, and if any component differs by more
+/// than an epsilon then the particle's position is translated by the
+/// difference, and the new offset is stored.
+///
+/// ```txt
+/// if (any(abs(translation_offset - particle.f32x3_0) > vec3<f32>(epsilon))) {
+///     particle.position += translation_offset - particle.f32x3_0;
+///     particle.f32x3_0 = translation_offset;
+/// }
+/// ```
+///
+/// When spawned, particles simply record the current translation offset, since
+/// they are already spawned at a position consistent with that offset.
+///
+/// If the translation offset is a literal expression, for example when created
+/// with [`constant()`], it never changes, so no particle ever needs to be
+/// translated. The modifier then doesn't emit any code, and doesn't require
+/// the [`Attribute::F32X3_0`] attribute to store the offset.
+///
+/// The epsilon avoids translating the particles every frame when the offset
+/// only changes by tiny amounts, for example when it's interpolated, which
+/// would accumulate floating-point errors. It defaults to [`DEFAULT_EPSILON`],
+/// and can be customized with [`with_epsilon()`]. Because the stored offset is
+/// only updated when the particles are translated, small changes are not lost;
+/// they're applied at once as soon as they add up beyond the epsilon.
+///
+/// To limit the work to the particles close to the origin, for example in
+/// large worlds where far away particles are not visible, the translation can
+/// be restricted to the particles within a given distance of the origin with
+/// [`with_radius()`]. The change detection then also checks that distance:
+///
+/// ```txt
+/// if (length(particle.position) < radius
+///     && any(abs(translation_offset - particle.f32x3_0) > vec3<f32>(epsilon))) {
+///     // [...]
+/// }
+/// ```
+///
+/// Particles outside the radius keep their stored offset, so they catch up
+/// with all the changes of the offset at once if they later move inside the
+/// radius.
+///
+/// Some effects store other world-space positions per particle, for example a
+/// previous position for trails. Those attributes can be translated along with
+/// the particle position with [`with_extra_attribute()`].
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::POSITION`]
+/// - [`Attribute::F32X3_0`], unless the translation offset is a literal
+/// - Any extra attribute added with [`with_extra_attribute()`].
+///
+/// [`constant()`]: FloatingOriginModifier::constant
+/// [`with_extra_attribute()`]: FloatingOriginModifier::with_extra_attribute
+/// [`DEFAULT_EPSILON`]: FloatingOriginModifier::DEFAULT_EPSILON
+/// [`with_epsilon()`]: FloatingOriginModifier::with_epsilon
+/// [`with_radius()`]: FloatingOriginModifier::with_radius
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct FloatingOriginModifier {
+    /// The translation offset of the floating origin.
+    ///
+    /// Expression type: `Vec3`
+    translation_offset: ExprHandle,
+    /// The threshold under which a change of the translation offset is
+    /// ignored, or `None` to use [`DEFAULT_EPSILON`].
+    ///
+    /// Expression type: `f32` or `Vec3`
+    ///
+    /// [`DEFAULT_EPSILON`]: FloatingOriginModifier::DEFAULT_EPSILON
+    pub epsilon: Option<ExprHandle>,
+    /// The distance from the origin beyond which particles are not translated,
+    /// or `None` to translate all particles.
+    ///
+    /// Expression type: `f32`
+    pub radius: Option<ExprHandle>,
+    /// Attributes required by the modifier. This is [`Attribute::POSITION`]
+    /// and [`Attribute::F32X3_0`], followed by any extra attribute offset along
+    /// with the particle position. For a literal offset, this is only
+    /// [`Attribute::POSITION`].
+    attributes: Vec<Attribute>,
+}
+
+impl FloatingOriginModifier {
+    /// Default threshold under which a change of the translation offset is
+    /// ignored.
+    pub const DEFAULT_EPSILON: f32 = 1e-4;
+
+    /// Create a new modifier from a translation offset expression.
+    ///
+    /// If `translation_offset` is a literal expression, the modifier doesn't
+    /// store the offset per particle, so doesn't require the
+    /// [`Attribute::F32X3_0`] attribute.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `translation_offset` doesn't refer to an expression of
+    /// `module`.
+    pub fn new(module: &Module, translation_offset: ExprHandle) -> Self {
+        let is_literal = module
+            .get(translation_offset)
+            .expect("Invalid translation offset expression.")
+            .as_literal()
+            .is_some();
+        let attributes = if is_literal {
+            vec![Attribute::POSITION]
+        } else {
+            vec![Attribute::POSITION, Attribute::F32X3_0]
+        };
+        Self {
+            translation_offset,
+            epsilon: None,
+            radius: None,
+            attributes,
+        }
+    }
+
+    /// Create a new modifier with a translation offset derived from a property.
+    ///
+    /// To create a new property, use [`Module::add_property()`].
+    pub fn via_property(module: &mut Module, property: PropertyHandle) -> Self {
+        let translation_offset = module.prop(property);
+        Self::new(module, translation_offset)
+    }
+
+    /// Create a new modifier with a constant translation offset.
+    ///
+    /// Since the offset never changes, the particles never need to be
+    /// translated, and the modifier doesn't store the offset per particle. It
+    /// doesn't require the [`Attribute::F32X3_0`] attribute, and doesn't emit
+    /// any code.
+    pub fn constant(module: &mut Module, translation_offset: Vec3) -> Self {
+        let translation_offset = module.lit(translation_offset);
+        Self::new(module, translation_offset)
+    }
+
+    /// Get the translation offset of the floating origin.
+    pub fn translation_offset(&self) -> ExprHandle {
+        self.translation_offset
+    }
+
+    /// Set the threshold under which a change of the translation offset is
+    /// ignored.
+    ///
+    /// The epsilon is compared to the absolute difference of each component of
+    /// the offset. It can be either a `f32` or a `Vec3` expression.
+    pub fn with_epsilon(mut self, epsilon: ExprHandle) -> Self {
+        self.epsilon = Some(epsilon);
+        self
+    }
+
+    /// Set the distance from the origin beyond which particles are not
+    /// translated.
+    ///
+    /// Only the particles whose position is strictly closer to the origin than
+    /// the radius are translated.
+    pub fn with_radius(mut self, radius: ExprHandle) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    /// Add an extra attribute to translate along with the particle position.
+    ///
+    /// The attribute is offset by the same amount as [`Attribute::POSITION`]
+    /// each time the translation offset changes. Adding an attribute which is
+    /// already translated, or adding an attribute to a modifier with a literal
+    /// translation offset, has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attribute is not a `Vec3` attribute.
+    pub fn with_extra_attribute(mut self, attribute: Attribute) -> Self {
+        assert_eq!(
+            attribute.value_type(),
+            ValueType::Vector(VectorType::VEC3F),
+            "FloatingOriginModifier can only offset Vec3 attributes."
+        );
+        if self.stores_offset() && !self.attributes.contains(&attribute) {
+            self.attributes.push(attribute);
+        }
+        self
+    }
+
+    /// Get the extra attributes translated along with the particle position.
+    pub fn extra_attributes(&self) -> &[Attribute] {
+        self.attributes.get(2..).unwrap_or_default()
+    }
+
+    /// Check if the modifier stores per particle the last translation offset it
+    /// received, which is the case unless the offset is a literal.
+    fn stores_offset(&self) -> bool {
+        self.attributes.contains(&Attribute::F32X3_0)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for FloatingOriginModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Init | ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    fn update_phase(&self) -> UpdatePhase {
+        UpdatePhase::PreIntegration
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        // A literal offset never changes, so particles are always spawned at a
+        // position consistent with it, and never need to be translated.
+        if !self.stores_offset() {
+            return Ok(());
+        }
+
+        let stored = module.attr(Attribute::F32X3_0);
+        let stored = context.eval(module, stored)?;
+        let offset = context.eval(module, self.translation_offset)?;
+
+        if context.modifier_context() == ModifierContext::Init {
+            context.main_code += &format!("{} = {};\n", stored, offset);
+            return Ok(());
+        }
+
+        let epsilon = if let Some(epsilon) = self.epsilon {
+            context.eval(module, epsilon)?
+        } else {
+            Self::DEFAULT_EPSILON.to_wgsl_string()
+        };
+
+        let radius_guard = if let Some(radius) = self.radius {
+            let position = module.attr(Attribute::POSITION);
+            let position = context.eval(module, position)?;
+            let radius = context.eval(module, radius)?;
+            format!("length({}) < {} && ", position, radius)
+        } else {
+            String::new()
+        };
+
+        let mut translate_code = String::new();
+        for &attr in self
+            .attributes
+            .iter()
+            .filter(|&&attr| attr != Attribute::F32X3_0)
+        {
+            let attr = module.attr(attr);
+            let attr = context.eval(module, attr)?;
+            translate_code += &format!("    {0} += {2} - {1};\n", attr, stored, offset);
+        }
+
+        context.main_code += &format!(
+            r#"if ({4}any(abs({1} - {0}) > vec3<f32>({3}))) {{
+{2}    {0} = {1};
+}}
+"#,
+            stored, offset, translate_code, epsilon, radius_guard
+        );
+
+        Ok(())
+    }
+}
+
+/// A modifier to rotate all particles when a floating origin reorients.
+///
+/// This is the rotation counterpart of [`FloatingOriginModifier`]. The world
+/// rotation is a unit quaternion stored in a `Vec4` expression as `(x, y, z,
+/// w)`. Each particle stores the last rotation it received into the
+/// [`Attribute::F32X4_0`] attribute. Each frame, the current rotation is
+/// compared with that stored value, and if they differ then the delta rotation
+/// between the two is applied to the particle's position and frame axes, and
+/// the new rotation is stored.
+///
+/// ```txt
+/// if (any(rotation != particle.f32x4_0)) {
+///     delta = rotation * conjugate(particle.f32x4_0);
+///     particle.position = rotate(delta, particle.position);
+///     particle.axis_x = rotate(delta, particle.axis_x);
+///     particle.axis_y = rotate(delta, particle.axis_y);
+///     particle.axis_z = rotate(delta, particle.axis_z);
+///     particle.f32x4_0 = rotation;
+/// }
+/// ```
+///
+/// When spawned, particles simply record the current rotation, since they are
+/// already spawned with a position and orientation consistent with it.
+///
+/// Other `Vec3` attributes, like the velocity, can be rotated too with
+/// [`with_extra_attribute()`].
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::POSITION`]
+/// - [`Attribute::AXIS_X`]
+/// - [`Attribute::AXIS_Y`]
+/// - [`Attribute::AXIS_Z`]
+/// - [`Attribute::F32X4_0`]
+/// - Any extra attribute added with [`with_extra_attribute()`].
+///
+/// [`with_extra_attribute()`]: FloatingRotationModifier::with_extra_attribute
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct FloatingRotationModifier {
+    /// The rotation of the floating origin, as a unit quaternion.
+    ///
+    /// Expression type: `Vec4`
+    pub rotation: ExprHandle,
+    /// Attributes required by the modifier. This is [`Attribute::F32X4_0`],
+    /// followed by the rotated attributes.
+    attributes: Vec<Attribute>,
+}
+
+impl FloatingRotationModifier {
+    /// Create a new modifier from a rotation expression.
+    ///
+    /// The rotation is a unit quaternion stored in a `Vec4` expression.
+    pub fn new(rotation: ExprHandle) -> Self {
+        Self {
+            rotation,
+            attributes: vec![
+                Attribute::F32X4_0,
+                Attribute::POSITION,
+                Attribute::AXIS_X,
+                Attribute::AXIS_Y,
+                Attribute::AXIS_Z,
+            ],
+        }
+    }
+
+    /// Create a new modifier with a rotation derived from a property.
+    ///
+    /// The property must be a `Vec4` storing a unit quaternion. To create a new
+    /// property, use [`Module::add_property()`].
+    pub fn via_property(module: &mut Module, property: PropertyHandle) -> Self {
+        Self::new(module.prop(property))
+    }
+
+    /// Create a new modifier with a constant rotation.
+    pub fn constant(module: &mut Module, rotation: Quat) -> Self {
+        Self::new(module.lit(Vec4::from(rotation)))
+    }
+
+    /// Create a new modifier with a constant rotation of a given angle around
+    /// a given axis.
+    ///
+    /// The angle is in radians, and the axis must be normalized.
+    pub fn constant_axis_angle(module: &mut Module, axis: Vec3, angle: f32) -> Self {
+        Self::constant(module, Quat::from_axis_angle(axis, angle))
+    }
+
+    /// Add an extra attribute to rotate along with the particle position.
+    ///
+    /// Adding an attribute which is already rotated has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attribute is not a `Vec3` attribute.
+    pub fn with_extra_attribute(mut self, attribute: Attribute) -> Self {
+        assert_eq!(
+            attribute.value_type(),
+            ValueType::Vector(VectorType::VEC3F),
+            "FloatingRotationModifier can only rotate Vec3 attributes."
+        );
+        if !self.attributes.contains(&attribute) {
+            self.attributes.push(attribute);
+        }
+        self
+    }
+
+    /// Get the attributes rotated by this modifier.
+    pub fn rotated_attributes(&self) -> &[Attribute] {
+        &self.attributes[1..]
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for FloatingRotationModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Init | ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    fn update_phase(&self) -> UpdatePhase {
+        UpdatePhase::PreIntegration
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let stored = module.attr(Attribute::F32X4_0);
+        let stored = context.eval(module, stored)?;
+        let rotation = context.eval(module, self.rotation)?;
+
+        if context.modifier_context() == ModifierContext::Init {
+            context.main_code += &format!("{} = {};\n", stored, rotation);
+            return Ok(());
+        }
+
+        // Rotate each vector v by the delta quaternion q with:
+        //   v' = v + 2 * cross(q.xyz, cross(q.xyz, v) + q.w * v)
+        let mut rotate_code = String::new();
+        for &attr in self.rotated_attributes() {
+            let attr = module.attr(attr);
+            let attr = context.eval(module, attr)?;
+            rotate_code += &format!(
+                "    {0} += 2. * cross(rot_delta.xyz, cross(rot_delta.xyz, {0}) + rot_delta.w * {0});\n",
+                attr
+            );
+        }
+
+        // The delta rotation is rot_new * conjugate(rot_old).
+        context.main_code += &format!(
+            r#"if (any({0} != {1})) {{
+    let rot_new = {1};
+    let rot_old = {0};
+    let rot_delta = vec4<f32>(
+        rot_old.w * rot_new.xyz - rot_new.w * rot_old.xyz - cross(rot_new.xyz, rot_old.xyz),
+        rot_new.w * rot_old.w + dot(rot_new.xyz, rot_old.xyz));
+{2}    {0} = rot_new;
+}}
+"#,
+            stored, rotation, rotate_code
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, Property, PropertyLayout};
+
+    #[test]
+    fn mod_floating_origin() {
+        let mut module = Module::default();
+        let offset = module.add_property("offset", Vec3::X.into());
+        let modifier = FloatingOriginModifier::via_property(&mut module, offset)
+            .with_extra_attribute(Attribute::F32X3_1)
+            .with_extra_attribute(Attribute::F32X3_1);
+        assert_eq!(modifier.extra_attributes(), &[Attribute::F32X3_1]);
+        assert_eq!(
+            modifier.attributes(),
+            &[Attribute::POSITION, Attribute::F32X3_0, Attribute::F32X3_1]
+        );
+
+        let property_layout = PropertyLayout::new(&[Property::new("offset", Vec3::X)]);
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"if (any(abs(properties.offset - particle.f32x3_0) > vec3<f32>(0.0001))) {
+    particle.position += properties.offset - particle.f32x3_0;
+    particle.f32x3_1 += properties.offset - particle.f32x3_0;
+    particle.f32x3_0 = properties.offset;
+}
+"#
+        );
+
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(context.main_code, "particle.f32x3_0 = properties.offset;\n");
+    }
+
+    #[test]
+    fn mod_floating_origin_epsilon() {
+        let mut module = Module::default();
+        let offset = module.add_property("offset", Vec3::X.into());
+        let epsilon = module.lit(0.01);
+        let modifier =
+            FloatingOriginModifier::via_property(&mut module, offset).with_epsilon(epsilon);
+        assert_eq!(modifier.epsilon, Some(epsilon));
+
+        let property_layout = PropertyLayout::new(&[Property::new("offset", Vec3::X)]);
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"if (any(abs(properties.offset - particle.f32x3_0) > vec3<f32>(0.01))) {
+    particle.position += properties.offset - particle.f32x3_0;
+    particle.f32x3_0 = properties.offset;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn mod_floating_origin_radius() {
+        let mut module = Module::default();
+        let offset = module.add_property("offset", Vec3::X.into());
+        let radius = module.lit(100.);
+        let modifier =
+            FloatingOriginModifier::via_property(&mut module, offset).with_radius(radius);
+        assert_eq!(modifier.radius, Some(radius));
+        assert_eq!(
+            modifier.attributes(),
+            &[Attribute::POSITION, Attribute::F32X3_0]
+        );
+
+        let property_layout = PropertyLayout::new(&[Property::new("offset", Vec3::X)]);
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"if (length(particle.position) < 100. && any(abs(properties.offset - particle.f32x3_0) > vec3<f32>(0.0001))) {
+    particle.position += properties.offset - particle.f32x3_0;
+    particle.f32x3_0 = properties.offset;
+}
+"#
+        );
+
+        // The radius doesn't apply on spawn
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(context.main_code, "particle.f32x3_0 = properties.offset;\n");
+    }
+
+    #[test]
+    fn mod_floating_origin_constant() {
+        let mut module = Module::default();
+        let modifier = FloatingOriginModifier::constant(&mut module, Vec3::X)
+            .with_extra_attribute(Attribute::F32X3_1);
+        assert!(modifier.extra_attributes().is_empty());
+        assert_eq!(modifier.attributes(), &[Attribute::POSITION]);
+
+        // No per-frame comparison, nor initialization of a stored offset
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        for modifier_context in [ModifierContext::Init, ModifierContext::Update] {
+            let mut context =
+                ShaderWriter::new(modifier_context, &property_layout, &particle_layout);
+            assert!(modifier.apply(&mut module, &mut context).is_ok());
+            assert!(context.main_code.is_empty());
+        }
+
+        // Same for any literal offset
+        let offset = module.lit(Vec3::Y);
+        let modifier =
+            FloatingOriginModifier::new(&module, offset).with_extra_attribute(Attribute::F32X3_1);
+        assert_eq!(modifier.translation_offset(), offset);
+        assert_eq!(modifier.attributes(), &[Attribute::POSITION]);
+        for modifier_context in [ModifierContext::Init, ModifierContext::Update] {
+            let mut context =
+                ShaderWriter::new(modifier_context, &property_layout, &particle_layout);
+            assert!(modifier.apply(&mut module, &mut context).is_ok());
+            assert!(context.main_code.is_empty());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn mod_floating_origin_invalid_attribute() {
+        let mut module = Module::default();
+        let offset = module.add_property("offset", Vec3::X.into());
+        let _ = FloatingOriginModifier::via_property(&mut module, offset)
+            .with_extra_attribute(Attribute::AGE);
+    }
+
+    #[test]
+    fn mod_floating_rotation() {
+        let mut module = Module::default();
+        let modifier = FloatingRotationModifier::constant_axis_angle(&mut module, Vec3::Z, 0.)
+            .with_extra_attribute(Attribute::VELOCITY)
+            .with_extra_attribute(Attribute::POSITION);
+        assert_eq!(
+            modifier.rotated_attributes(),
+            &[
+                Attribute::POSITION,
+                Attribute::AXIS_X,
+                Attribute::AXIS_Y,
+                Attribute::AXIS_Z,
+                Attribute::VELOCITY
+            ]
+        );
+        assert_eq!(modifier.attributes()[0], Attribute::F32X4_0);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        let code = context.main_code;
+        assert!(code.starts_with("if (any(particle.f32x4_0 != vec4<f32>(0.,0.,0.,1.))) {\n"));
+        assert!(code.contains("    let rot_new = vec4<f32>(0.,0.,0.,1.);\n"));
+        for name in ["position", "axis_x", "axis_y", "axis_z", "velocity"] {
+            assert!(code.contains(&format!(
+                "    particle.{0} += 2. * cross(rot_delta.xyz, cross(rot_delta.xyz, particle.{0}) + rot_delta.w * particle.{0});\n",
+                name
+            )));
+        }
+        assert!(code.ends_with("    particle.f32x4_0 = rot_new;\n}\n"));
+
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            "particle.f32x4_0 = vec4<f32>(0.,0.,0.,1.);\n"
+        );
+    }
+}
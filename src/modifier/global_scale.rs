@@ -0,0 +1,253 @@
+//! Modifiers to support a global rescaling of the world.
+//!
+//! Some worlds switch between coordinate systems of different scales, for
+//! example when changing the level of detail of a planet-sized scene. When the
+//! scale changes, all existing particles must be rescaled so they stay
+//! consistent with the rest of the world. The [`GlobalScaleModifier`] applies
+//! that rescaling to all particles on the GPU, by tracking per particle the
+//! last scale factor it received.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expr::PropertyHandle, graph::ExprError, Attribute, BoxedModifier, EvalContext, ExprHandle,
+    Modifier, ModifierContext, Module, ScalarType, ShaderWriter, ValueType,
+};
+
+/// A modifier to rescale all particles when a global scale factor changes.
+///
+/// Each particle stores the last scale factor it received into the
+/// [`Attribute::F32_0`] attribute. Each frame, the current scale factor is
+/// compared with that stored value, and if they differ then the particle's
+/// position and size are multiplied by the ratio of the new scale factor over
+/// the stored one, and the new scale factor is stored.
+///
+/// ```txt
+/// if (scale != particle.f32_0) {
+///     let scale_ratio = scale / particle.f32_0;
+///     particle.position *= scale_ratio;
+///     particle.size *= scale_ratio;
+///     particle.f32_0 = scale;
+/// }
+/// ```
+///
+/// The position is scaled around the origin of the simulation space of the
+/// effect. When spawned, particles simply record the current scale factor,
+/// since they are already spawned with a position and size consistent with
+/// it. The scale factor must never be zero.
+///
+/// By default the [`Attribute::SIZE`] attribute is scaled; effects using a
+/// non-uniform size can scale [`Attribute::SIZE2`] or [`Attribute::SIZE3`]
+/// instead with [`with_size_attribute()`]. Other attributes, like the
+/// velocity, can be scaled too with [`with_extra_attribute()`].
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::F32_0`]
+/// - [`Attribute::POSITION`]
+/// - [`Attribute::SIZE`], or the size attribute selected with
+///   [`with_size_attribute()`].
+/// - Any extra attribute added with [`with_extra_attribute()`].
+///
+/// [`with_size_attribute()`]: GlobalScaleModifier::with_size_attribute
+/// [`with_extra_attribute()`]: GlobalScaleModifier::with_extra_attribute
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct GlobalScaleModifier {
+    /// The global scale factor.
+    ///
+    /// Expression type: `f32`
+    pub scale: ExprHandle,
+    /// Attributes required by the modifier. This is [`Attribute::F32_0`],
+    /// followed by the scaled attributes, starting with [`Attribute::POSITION`]
+    /// and the size attribute.
+    attributes: Vec<Attribute>,
+}
+
+impl GlobalScaleModifier {
+    /// Create a new modifier from a scale factor expression.
+    pub fn new(scale: ExprHandle) -> Self {
+        Self {
+            scale,
+            attributes: vec![Attribute::F32_0, Attribute::POSITION, Attribute::SIZE],
+        }
+    }
+
+    /// Create a new modifier with a scale factor derived from a property.
+    ///
+    /// To create a new property, use [`Module::add_property()`].
+    pub fn via_property(module: &mut Module, property: PropertyHandle) -> Self {
+        Self::new(module.prop(property))
+    }
+
+    /// Create a new modifier with a constant scale factor.
+    pub fn constant(module: &mut Module, scale: f32) -> Self {
+        Self::new(module.lit(scale))
+    }
+
+    /// Set the size attribute scaled by the modifier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attribute is not one of [`Attribute::SIZE`],
+    /// [`Attribute::SIZE2`], or [`Attribute::SIZE3`].
+    pub fn with_size_attribute(mut self, attribute: Attribute) -> Self {
+        assert!(
+            [Attribute::SIZE, Attribute::SIZE2, Attribute::SIZE3].contains(&attribute),
+            "GlobalScaleModifier can only use SIZE, SIZE2, or SIZE3 as size attribute."
+        );
+        if self.attributes[2] != attribute {
+            // Avoid scaling twice an attribute previously added as extra.
+            self.attributes.retain(|&attr| attr != attribute);
+            self.attributes[2] = attribute;
+        }
+        self
+    }
+
+    /// Add an extra attribute to scale along with the particle position and
+    /// size.
+    ///
+    /// Adding an attribute which is already scaled has no effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attribute is not a floating-point scalar or vector
+    /// attribute, or is [`Attribute::F32_0`] which stores the scale factor.
+    pub fn with_extra_attribute(mut self, attribute: Attribute) -> Self {
+        let is_float = match attribute.value_type() {
+            ValueType::Scalar(scalar_type) => scalar_type == ScalarType::Float,
+            ValueType::Vector(vector_type) => vector_type.elem_type() == ScalarType::Float,
+            ValueType::Matrix(_) | ValueType::Array(_) => false,
+        };
+        assert!(
+            is_float && attribute != Attribute::F32_0,
+            "GlobalScaleModifier can only scale floating-point attributes."
+        );
+        if !self.attributes.contains(&attribute) {
+            self.attributes.push(attribute);
+        }
+        self
+    }
+
+    /// Get the attributes scaled by this modifier.
+    pub fn scaled_attributes(&self) -> &[Attribute] {
+        &self.attributes[1..]
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for GlobalScaleModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Init | ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let stored = module.attr(Attribute::F32_0);
+        let stored = context.eval(module, stored)?;
+        let scale = context.eval(module, self.scale)?;
+
+        if context.modifier_context() == ModifierContext::Init {
+            context.main_code += &format!("{} = {};\n", stored, scale);
+            return Ok(());
+        }
+
+        let mut scale_code = String::new();
+        for &attr in self.scaled_attributes() {
+            let attr = module.attr(attr);
+            let attr = context.eval(module, attr)?;
+            scale_code += &format!("    {} *= scale_ratio;\n", attr);
+        }
+
+        context.main_code += &format!(
+            r#"if ({1} != {0}) {{
+    let scale_ratio = {1} / {0};
+{2}    {0} = {1};
+}}
+"#,
+            stored, scale, scale_code
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_global_scale() {
+        let mut module = Module::default();
+        let modifier = GlobalScaleModifier::constant(&mut module, 2.)
+            .with_extra_attribute(Attribute::VELOCITY)
+            .with_extra_attribute(Attribute::VELOCITY);
+        assert_eq!(
+            modifier.scaled_attributes(),
+            &[Attribute::POSITION, Attribute::SIZE, Attribute::VELOCITY]
+        );
+        assert_eq!(modifier.attributes()[0], Attribute::F32_0);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"if (2. != particle.f32_0) {
+    let scale_ratio = 2. / particle.f32_0;
+    particle.position *= scale_ratio;
+    particle.size *= scale_ratio;
+    particle.velocity *= scale_ratio;
+    particle.f32_0 = 2.;
+}
+"#
+        );
+
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(context.main_code, "particle.f32_0 = 2.;\n");
+    }
+
+    #[test]
+    fn mod_global_scale_size2() {
+        let mut module = Module::default();
+        let modifier = GlobalScaleModifier::constant(&mut module, 2.)
+            .with_extra_attribute(Attribute::SIZE2)
+            .with_size_attribute(Attribute::SIZE2)
+            .with_size_attribute(Attribute::SIZE2);
+        assert_eq!(
+            modifier.scaled_attributes(),
+            &[Attribute::POSITION, Attribute::SIZE2]
+        );
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert!(context
+            .main_code
+            .contains("    particle.size2 *= scale_ratio;\n"));
+        assert!(!context.main_code.contains("particle.size "));
+    }
+
+    #[test]
+    #[should_panic]
+    fn mod_global_scale_invalid_attribute() {
+        let mut module = Module::default();
+        let _ = GlobalScaleModifier::constant(&mut module, 2.)
+            .with_extra_attribute(Attribute::SPRITE_INDEX);
+    }
+}
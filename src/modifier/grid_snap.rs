@@ -0,0 +1,124 @@
+//! Modifiers to snap particles to a grid.
+//!
+//! The [`GridSnapModifier`] rounds the position of all particles to the
+//! nearest node of a 3D grid, which gives a voxel-like look to effects, for
+//! example pixel art particles or block-based debris.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expr::PropertyHandle, graph::ExprError, Attribute, BoxedModifier, EvalContext, ExprHandle,
+    Modifier, ModifierContext, Module, ShaderWriter,
+};
+
+/// A modifier to snap the position of all particles to a grid.
+///
+/// Each frame, the particle position is rounded to the nearest multiple of
+/// the grid cell size, independently on each axis:
+///
+/// ```txt
+/// particle.position = round(particle.position / cell_size) * cell_size;
+/// ```
+///
+/// The cell size can be different on each axis. A zero component disables
+/// snapping on that axis, which keeps the position unchanged instead of
+/// dividing by zero. For example a cell size of `(1, 0, 1)` only snaps the
+/// particles on the XZ plane.
+///
+/// The particle position is modified directly, so the snapping accumulates
+/// with the particle motion. In particular, a particle moving by less than
+/// half a cell per frame is snapped back to its previous position, and never
+/// moves. Snapping is best used with particles moving fast enough, or with a
+/// position integrated from another attribute.
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::POSITION`]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct GridSnapModifier {
+    /// The size of a grid cell along each axis.
+    ///
+    /// Expression type: `Vec3`
+    pub cell_size: ExprHandle,
+}
+
+impl GridSnapModifier {
+    /// Create a new modifier from a cell size expression.
+    pub fn new(cell_size: ExprHandle) -> Self {
+        Self { cell_size }
+    }
+
+    /// Create a new modifier with a cell size derived from a property.
+    ///
+    /// To create a new property, use [`Module::add_property()`].
+    pub fn via_property(module: &mut Module, property: PropertyHandle) -> Self {
+        Self::new(module.prop(property))
+    }
+
+    /// Create a new modifier with a constant cell size.
+    pub fn constant(module: &mut Module, cell_size: Vec3) -> Self {
+        Self::new(module.lit(cell_size))
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for GridSnapModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[Attribute::POSITION]
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(*self)
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let pos = module.attr(Attribute::POSITION);
+        let pos = context.eval(module, pos)?;
+        let cell_size = context.eval(module, self.cell_size)?;
+
+        // Store the cell size into a local variable, to evaluate its
+        // expression only once. The select() discards the result of the
+        // division on the axes with a zero cell size.
+        let var = context.make_local_var();
+        context.main_code += &format!(
+            r#"let {1} = {2};
+{0} = select({0}, round({0} / {1}) * {1}, {1} != vec3<f32>(0.));
+"#,
+            pos, var, cell_size
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_grid_snap() {
+        let mut module = Module::default();
+        let modifier = GridSnapModifier::constant(&mut module, Vec3::new(0.5, 0., 2.));
+        assert_eq!(modifier.context(), ModifierContext::Update);
+        assert_eq!(modifier.attributes(), &[Attribute::POSITION]);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"let var0 = vec3<f32>(0.5,0.,2.);
+particle.position = select(particle.position, round(particle.position / var0) * var0, var0 != vec3<f32>(0.));
+"#
+        );
+    }
+}
@@ -183,6 +183,75 @@ impl Modifier for KillAabbModifier {
     }
 }
 
+/// A modifier killing all particles for which a predicate is true.
+///
+/// The predicate is an arbitrary boolean expression, evaluated for each
+/// particle during the update pass. This enables culling particles based on
+/// any condition, like entering a region of space or exceeding some speed.
+///
+/// # Attributes
+///
+/// This modifier requires the attributes read by the predicate expression, if
+/// any.
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct KillWhenModifier {
+    /// The predicate deciding whether a particle is killed.
+    ///
+    /// Expression type: `bool`
+    predicate: ExprHandle,
+    /// Attributes read by the predicate.
+    attributes: Vec<Attribute>,
+}
+
+impl KillWhenModifier {
+    /// Create a new instance of a [`KillWhenModifier`] killing the particles
+    /// for which the `predicate` expression is true.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `predicate` doesn't refer to an expression of `module`.
+    pub fn new(module: &Module, predicate: ExprHandle) -> Self {
+        Self {
+            predicate,
+            attributes: module.used_attributes(predicate),
+        }
+    }
+
+    /// Get the predicate deciding whether a particle is killed.
+    pub fn predicate(&self) -> ExprHandle {
+        self.predicate
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for KillWhenModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let expr = context.eval(module, self.predicate)?;
+
+        context.main_code += &format!(
+            r#"if ({}) {{
+    is_alive = false;
+}}
+"#,
+            expr
+        );
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,4 +273,26 @@ mod tests {
         assert!(context.main_code.contains("is_alive = false")); // TODO - less
                                                                  // weak check
     }
+
+    #[test]
+    fn mod_kill_when() {
+        let mut module = Module::default();
+        let pos = module.attr(Attribute::POSITION);
+        let y = module.y(pos);
+        let ground = module.lit(0.);
+        let predicate = module.lt(y, ground);
+        let modifier = KillWhenModifier::new(&module, predicate);
+        assert_eq!(modifier.predicate(), predicate);
+        assert_eq!(modifier.attributes(), &[Attribute::POSITION]);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            "if ((particle.position.y) < (0.)) {\n    is_alive = false;\n}\n"
+        );
+    }
 }
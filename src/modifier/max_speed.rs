@@ -0,0 +1,146 @@
+//! Modifiers to track the peak speed of particles.
+//!
+//! The [`MaxSpeedTrackerModifier`] records into a custom attribute the highest
+//! speed each particle reached since it was spawned. Other modifiers can then
+//! use that value, for example to stretch particles or drive the length of
+//! their trail by their peak speed, even once they slowed down.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::ExprError, Attribute, BoxedModifier, EvalContext, Modifier, ModifierContext, Module,
+    ScalarType, ShaderWriter, ValueType,
+};
+
+/// A modifier to record the maximum speed reached by particles.
+///
+/// Each frame, the speed of the particle is compared with the maximum speed
+/// stored in a `f32` attribute, and the stored value is updated if the
+/// particle is faster:
+///
+/// ```txt
+/// particle.f32_0 = max(particle.f32_0, length(particle.velocity));
+/// ```
+///
+/// When spawned, the stored maximum speed is initialized with the spawn speed
+/// of the particle, since the attributes of a new particle may contain the
+/// value of a previously dead particle.
+///
+/// The speed recorded is the one at the time the modifier is applied, so
+/// modifiers changing the velocity after this one are only accounted for the
+/// next frame.
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::VELOCITY`]
+/// - The attribute storing the maximum speed, by default
+///   [`Attribute::F32_0`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct MaxSpeedTrackerModifier {
+    /// Attributes required by the modifier. This is [`Attribute::VELOCITY`],
+    /// followed by the attribute storing the maximum speed.
+    attributes: [Attribute; 2],
+}
+
+impl Default for MaxSpeedTrackerModifier {
+    fn default() -> Self {
+        Self::new(Attribute::F32_0)
+    }
+}
+
+impl MaxSpeedTrackerModifier {
+    /// Create a new modifier storing the maximum speed into the given
+    /// attribute.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attribute is not a `f32` attribute.
+    pub fn new(attribute: Attribute) -> Self {
+        assert_eq!(
+            attribute.value_type(),
+            ValueType::Scalar(ScalarType::Float),
+            "MaxSpeedTrackerModifier can only store the maximum speed into a f32 attribute."
+        );
+        Self {
+            attributes: [Attribute::VELOCITY, attribute],
+        }
+    }
+
+    /// Get the attribute storing the maximum speed.
+    pub fn attribute(&self) -> Attribute {
+        self.attributes[1]
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for MaxSpeedTrackerModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Init | ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(*self)
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let vel = module.attr(Attribute::VELOCITY);
+        let vel = context.eval(module, vel)?;
+        let stored = module.attr(self.attribute());
+        let stored = context.eval(module, stored)?;
+
+        if context.modifier_context() == ModifierContext::Init {
+            context.main_code += &format!("{} = length({});\n", stored, vel);
+        } else {
+            context.main_code += &format!("{0} = max({0}, length({1}));\n", stored, vel);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_max_speed_tracker() {
+        let mut module = Module::default();
+        let modifier = MaxSpeedTrackerModifier::new(Attribute::F32_2);
+        assert_eq!(modifier.attribute(), Attribute::F32_2);
+        assert_eq!(
+            modifier.attributes(),
+            &[Attribute::VELOCITY, Attribute::F32_2]
+        );
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            "particle.f32_2 = max(particle.f32_2, length(particle.velocity));\n"
+        );
+
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            "particle.f32_2 = length(particle.velocity);\n"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn mod_max_speed_tracker_invalid_attribute() {
+        let _ = MaxSpeedTrackerModifier::new(Attribute::F32X3_0);
+    }
+}
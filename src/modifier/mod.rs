@@ -57,20 +57,40 @@ use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
 pub mod accel;
+pub mod age_rate;
 pub mod attr;
+pub mod clamp_velocity;
+pub mod conditional;
+pub mod face_velocity;
+pub mod floating_origin;
 pub mod force;
+pub mod global_scale;
+pub mod grid_snap;
 pub mod kill;
+pub mod max_speed;
 pub mod output;
 pub mod position;
+pub mod trail_history;
 pub mod velocity;
+pub mod wrap_box;
 
 pub use accel::*;
+pub use age_rate::*;
 pub use attr::*;
+pub use clamp_velocity::*;
+pub use conditional::*;
+pub use face_velocity::*;
+pub use floating_origin::*;
 pub use force::*;
+pub use global_scale::*;
+pub use grid_snap::*;
 pub use kill::*;
+pub use max_speed::*;
 pub use output::*;
 pub use position::*;
+pub use trail_history::*;
 pub use velocity::*;
+pub use wrap_box::*;
 
 use crate::{
     Attribute, EvalContext, ExprError, ExprHandle, Gradient, Module, ParticleLayout,
@@ -147,6 +167,40 @@ impl std::fmt::Display for ModifierContext {
     }
 }
 
+/// Phase of the particle update a modifier runs in, relative to the motion
+/// integration.
+///
+/// The update modifiers of an effect are grouped by phase, and their code is
+/// generated phase after phase. Inside a phase, the modifiers keep the order
+/// they were added to the effect in. The motion integration, if any, runs
+/// either at the start or at the end of the [`UpdatePhase::Main`] phase, as
+/// specified by the [`MotionIntegration`] of the effect.
+///
+/// [`MotionIntegration`]: crate::MotionIntegration
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum UpdatePhase {
+    /// Run before the motion integration, and before the modifiers of the
+    /// other phases, whatever the [`MotionIntegration`] of the effect.
+    ///
+    /// This is used by modifiers which must see the particle position before
+    /// the particle moves this frame, like the [`FloatingOriginModifier`].
+    ///
+    /// [`MotionIntegration`]: crate::MotionIntegration
+    PreIntegration,
+    /// Run with most other modifiers, either before or after the motion
+    /// integration as specified by the [`MotionIntegration`] of the effect.
+    /// This is the default.
+    ///
+    /// [`MotionIntegration`]: crate::MotionIntegration
+    #[default]
+    Main,
+    /// Run after the motion integration, and after the modifiers of the other
+    /// phases, whatever the [`MotionIntegration`] of the effect.
+    ///
+    /// [`MotionIntegration`]: crate::MotionIntegration
+    PostIntegration,
+}
+
 /// Trait describing a modifier customizing an effect pipeline.
 #[cfg_attr(feature = "serde", typetag::serde)]
 pub trait Modifier: Reflect + Send + Sync + 'static {
@@ -166,6 +220,15 @@ pub trait Modifier: Reflect + Send + Sync + 'static {
     /// Get the list of attributes required for this modifier to be used.
     fn attributes(&self) -> &[Attribute];
 
+    /// Get the phase of the particle update this modifier runs in.
+    ///
+    /// This is only used if the modifier applies to the
+    /// [`ModifierContext::Update`] context. The default implementation
+    /// returns [`UpdatePhase::Main`].
+    fn update_phase(&self) -> UpdatePhase {
+        UpdatePhase::Main
+    }
+
     /// Clone self.
     fn boxed_clone(&self) -> BoxedModifier;
 
@@ -625,6 +688,8 @@ pub(crate) use impl_mod_render;
 
 #[cfg(test)]
 mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
     use bevy::prelude::*;
     use naga::front::wgsl::Frontend;
 
@@ -798,19 +863,30 @@ fn main() {{
 
     #[test]
     fn validate_update() {
-        let writer = ExprWriter::new();
+        let module = Rc::new(RefCell::new(Module::default()));
+        let writer = ExprWriter::from_module(Rc::clone(&module));
         let origin = writer.lit(Vec3::ZERO).expr();
         let center = origin;
         let axis = origin;
         let y_axis = writer.lit(Vec3::Y).expr();
         let one = writer.lit(1.).expr();
         let radius = one;
+        // Use a non-literal offset, otherwise the modifier doesn't emit any code
+        let floating_offset = (writer.time() * writer.lit(Vec3::X)).expr();
+        let floating_origin = FloatingOriginModifier::new(&module.borrow(), floating_offset)
+            .with_extra_attribute(Attribute::F32X3_1);
         let modifiers: &[&dyn Modifier] = &[
             &AccelModifier::new(origin),
             &RadialAccelModifier::new(origin, one),
             &TangentAccelModifier::new(origin, y_axis, one),
             &ConformToSphereModifier::new(origin, one, one, one, one),
             &LinearDragModifier::new(writer.lit(3.5).expr()),
+            &floating_origin,
+            &FloatingRotationModifier::new(writer.lit(Vec4::W).expr())
+                .with_extra_attribute(Attribute::VELOCITY),
+            &GlobalScaleModifier::new(writer.lit(2.).expr())
+                .with_extra_attribute(Attribute::VELOCITY),
+            &GridSnapModifier::new(writer.lit(Vec3::ONE).expr()),
             &KillAabbModifier::new(writer.lit(Vec3::ZERO).expr(), writer.lit(Vec3::ONE).expr()),
             &SetPositionCircleModifier {
                 center,
@@ -843,6 +919,12 @@ fn main() {{
                 axis,
                 speed: radius,
             },
+            &WrapBoxModifier::new(writer.lit(Vec3::ZERO).expr(), writer.lit(Vec3::ONE).expr()),
+            &FaceVelocityModifier::new(writer.lit(Vec3::Y).expr()),
+            &ClampVelocityModifier::new(writer.lit(1.).expr()),
+            &AgeRateModifier::new(writer.lit(2.).expr()),
+            &TrailHistoryModifier::default(),
+            &MaxSpeedTrackerModifier::default(),
         ];
         let mut module = writer.finish();
         for &modifier in modifiers.iter() {
@@ -0,0 +1,203 @@
+//! Modifiers to record the past positions of particles.
+//!
+//! The [`TrailHistoryModifier`] keeps, for each particle, a short history of
+//! its previous positions in a set of custom attributes. This history can then
+//! be used to draw a trail behind each particle, without having to spawn one
+//! particle per trail segment.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::ExprError, Attribute, BoxedModifier, EvalContext, Modifier, ModifierContext, Module,
+    ShaderWriter, ValueType, VectorType,
+};
+
+/// A modifier to record a history of the previous positions of particles.
+///
+/// The history is stored into a list of `vec3<f32>` attributes, the first one
+/// holding the most recent position. Each frame, the stored positions are
+/// shifted down the list, discarding the oldest one, and the current particle
+/// position is stored into the first attribute:
+///
+/// ```txt
+/// particle.f32x3_3 = particle.f32x3_2;
+/// particle.f32x3_2 = particle.f32x3_1;
+/// particle.f32x3_1 = particle.f32x3_0;
+/// particle.f32x3_0 = particle.position;
+/// ```
+///
+/// When spawned, all the history attributes are initialized with the spawn
+/// position, so that a new particle doesn't have a trail extending to the
+/// origin.
+///
+/// The position recorded is the one at the time the modifier is applied, so
+/// depends on its order relative to the other update modifiers and to the
+/// motion integration.
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::POSITION`]
+/// - The history attributes, by default [`Attribute::F32X3_0`] to
+///   [`Attribute::F32X3_3`].
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct TrailHistoryModifier {
+    /// Attributes required by the modifier. This is [`Attribute::POSITION`],
+    /// followed by the history attributes from the most recent position to
+    /// the oldest one.
+    attributes: Vec<Attribute>,
+}
+
+impl Default for TrailHistoryModifier {
+    fn default() -> Self {
+        Self::new(&[
+            Attribute::F32X3_0,
+            Attribute::F32X3_1,
+            Attribute::F32X3_2,
+            Attribute::F32X3_3,
+        ])
+    }
+}
+
+impl TrailHistoryModifier {
+    /// Create a new modifier storing the position history into the given
+    /// attributes, from the most recent position to the oldest one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `history` is empty, contains the same attribute more than
+    /// once, or contains an attribute which is not a `vec3<f32>` or is
+    /// [`Attribute::POSITION`].
+    pub fn new(history: &[Attribute]) -> Self {
+        assert!(
+            !history.is_empty(),
+            "TrailHistoryModifier needs at least one history attribute."
+        );
+        let mut attributes = vec![Attribute::POSITION];
+        for &attribute in history {
+            assert!(
+                attribute.value_type() == ValueType::Vector(VectorType::VEC3F)
+                    && !attributes.contains(&attribute),
+                "TrailHistoryModifier can only store the history into distinct vec3<f32> \
+                 attributes other than the position."
+            );
+            attributes.push(attribute);
+        }
+        Self { attributes }
+    }
+
+    /// Get the attributes storing the position history, from the most recent
+    /// position to the oldest one.
+    pub fn history_attributes(&self) -> &[Attribute] {
+        &self.attributes[1..]
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for TrailHistoryModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Init | ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let pos = module.attr(Attribute::POSITION);
+        let pos = context.eval(module, pos)?;
+        let mut history = vec![];
+        for &attr in self.history_attributes() {
+            let attr = module.attr(attr);
+            history.push(context.eval(module, attr)?);
+        }
+
+        if context.modifier_context() == ModifierContext::Init {
+            for attr in &history {
+                context.main_code += &format!("{} = {};\n", attr, pos);
+            }
+            return Ok(());
+        }
+
+        // Shift from the oldest position, so each value is read before being
+        // overwritten.
+        for pair in history.windows(2).rev() {
+            context.main_code += &format!("{} = {};\n", pair[1], pair[0]);
+        }
+        context.main_code += &format!("{} = {};\n", history[0], pos);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_trail_history() {
+        let mut module = Module::default();
+        let modifier = TrailHistoryModifier::default();
+        assert_eq!(
+            modifier.history_attributes(),
+            &[
+                Attribute::F32X3_0,
+                Attribute::F32X3_1,
+                Attribute::F32X3_2,
+                Attribute::F32X3_3
+            ]
+        );
+        assert_eq!(modifier.attributes()[0], Attribute::POSITION);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"particle.f32x3_3 = particle.f32x3_2;
+particle.f32x3_2 = particle.f32x3_1;
+particle.f32x3_1 = particle.f32x3_0;
+particle.f32x3_0 = particle.position;
+"#
+        );
+
+        let mut context =
+            ShaderWriter::new(ModifierContext::Init, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"particle.f32x3_0 = particle.position;
+particle.f32x3_1 = particle.position;
+particle.f32x3_2 = particle.position;
+particle.f32x3_3 = particle.position;
+"#
+        );
+    }
+
+    #[test]
+    fn mod_trail_history_single() {
+        let mut module = Module::default();
+        let modifier = TrailHistoryModifier::new(&[Attribute::F32X3_2]);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(context.main_code, "particle.f32x3_2 = particle.position;\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn mod_trail_history_invalid_attribute() {
+        let _ = TrailHistoryModifier::new(&[Attribute::F32X3_0, Attribute::F32X3_0]);
+    }
+}
@@ -0,0 +1,127 @@
+//! Modifiers to wrap particles around the faces of a box.
+//!
+//! The [`WrapBoxModifier`] keeps all particles inside an axis-aligned box by
+//! moving the particles exiting the box through one face back in through the
+//! opposite face. This allows simulating an infinite field of particles, for
+//! example snow or dust around the camera, with a finite number of particles.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expr::PropertyHandle, graph::ExprError, Attribute, BoxedModifier, EvalContext, ExprHandle,
+    Modifier, ModifierContext, Module, ShaderWriter,
+};
+
+/// A modifier to wrap all particles around the faces of a box.
+///
+/// Each frame, the particle position is wrapped into the axis-aligned box
+/// defined by its `min` and `max` corners, independently on each axis. A
+/// particle exiting the box through a face re-enters it through the opposite
+/// face:
+///
+/// ```txt
+/// let size = max - min;
+/// particle.position = ((particle.position - min) % size + size) % size + min;
+/// ```
+///
+/// The wrapping works for particles on either side of the box, and at any
+/// distance of it. The box must have a non-zero size on all axes.
+///
+/// # Attributes
+///
+/// This modifier requires the following particle attributes:
+/// - [`Attribute::POSITION`]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct WrapBoxModifier {
+    /// The minimum corner of the box.
+    ///
+    /// Expression type: `Vec3`
+    pub min: ExprHandle,
+    /// The maximum corner of the box.
+    ///
+    /// Expression type: `Vec3`
+    pub max: ExprHandle,
+}
+
+impl WrapBoxModifier {
+    /// Create a new modifier from the expressions of the box corners.
+    pub fn new(min: ExprHandle, max: ExprHandle) -> Self {
+        Self { min, max }
+    }
+
+    /// Create a new modifier with box corners derived from properties.
+    ///
+    /// To create a new property, use [`Module::add_property()`].
+    pub fn via_property(module: &mut Module, min: PropertyHandle, max: PropertyHandle) -> Self {
+        Self::new(module.prop(min), module.prop(max))
+    }
+
+    /// Create a new modifier with constant box corners.
+    pub fn constant(module: &mut Module, min: Vec3, max: Vec3) -> Self {
+        Self::new(module.lit(min), module.lit(max))
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Modifier for WrapBoxModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[Attribute::POSITION]
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(*self)
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let pos = module.attr(Attribute::POSITION);
+        let pos = context.eval(module, pos)?;
+        let min = context.eval(module, self.min)?;
+        let max = context.eval(module, self.max)?;
+
+        // WGSL's % truncates toward zero, so takes the sign of the dividend.
+        // Add the size back before a second modulo to wrap negative offsets.
+        let min_var = context.make_local_var();
+        let size_var = context.make_local_var();
+        context.main_code += &format!(
+            r#"let {1} = {2};
+let {3} = {4} - {1};
+{0} = (({0} - {1}) % {3} + {3}) % {3} + {1};
+"#,
+            pos, min_var, min, size_var, max
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParticleLayout, PropertyLayout};
+
+    #[test]
+    fn mod_wrap_box() {
+        let mut module = Module::default();
+        let modifier = WrapBoxModifier::constant(&mut module, Vec3::new(-1., 0., -2.), Vec3::ONE);
+        assert_eq!(modifier.context(), ModifierContext::Update);
+        assert_eq!(modifier.attributes(), &[Attribute::POSITION]);
+
+        let property_layout = PropertyLayout::default();
+        let particle_layout = ParticleLayout::default();
+        let mut context =
+            ShaderWriter::new(ModifierContext::Update, &property_layout, &particle_layout);
+        assert!(modifier.apply(&mut module, &mut context).is_ok());
+        assert_eq!(
+            context.main_code,
+            r#"let var0 = vec3<f32>(-1.,0.,-2.);
+let var1 = vec3<f32>(1.,1.,1.) - var0;
+particle.position = ((particle.position - var0) % var1 + var1) % var1 + var0;
+"#
+        );
+    }
+}
@@ -177,6 +177,11 @@ fn to_float01(u: u32) -> f32 {
     return bitcast<f32>((u & 0x007fffffu) | 0x3f800000u) - 1.;
 }
 
+// Deterministic pseudo-random floating-point number in [0:1) from a seed
+fn hash_float01(input: u32) -> f32 {
+    return to_float01(pcg_hash(input));
+}
+
 // Random floating-point number in [0:1]
 fn frand() -> f32 {
     seed = pcg_hash(seed);
@@ -269,3 +274,140 @@ fn rand_normal_vec4(mean: vec4f, std_dev: vec4f) -> vec4f {
 fn proj(u: vec3<f32>, v: vec3<f32>) -> vec3<f32> {
     return dot(v, u) / dot(u,u) * u;
 }
+
+// Hash a 2D integer lattice point into a floating-point number in [0:1]
+fn noise_hash2(p: vec2<f32>) -> f32 {
+    let q = vec2<u32>(vec2<i32>(p));
+    return to_float01(pcg_hash(q.x ^ pcg_hash(q.y)));
+}
+
+// Hash a 3D integer lattice point into a floating-point number in [0:1]
+fn noise_hash3(p: vec3<f32>) -> f32 {
+    let q = vec3<u32>(vec3<i32>(p));
+    return to_float01(pcg_hash(q.x ^ pcg_hash(q.y ^ pcg_hash(q.z))));
+}
+
+// Value noise in [-1:1]
+fn value_noise_vec2(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3. - 2. * f);
+    let a = noise_hash2(i);
+    let b = noise_hash2(i + vec2<f32>(1., 0.));
+    let c = noise_hash2(i + vec2<f32>(0., 1.));
+    let d = noise_hash2(i + vec2<f32>(1., 1.));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y) * 2. - 1.;
+}
+
+// Value noise in [-1:1]
+fn value_noise_vec3(p: vec3<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3. - 2. * f);
+    let a = noise_hash3(i);
+    let b = noise_hash3(i + vec3<f32>(1., 0., 0.));
+    let c = noise_hash3(i + vec3<f32>(0., 1., 0.));
+    let d = noise_hash3(i + vec3<f32>(1., 1., 0.));
+    let e = noise_hash3(i + vec3<f32>(0., 0., 1.));
+    let f1 = noise_hash3(i + vec3<f32>(1., 0., 1.));
+    let g = noise_hash3(i + vec3<f32>(0., 1., 1.));
+    let h = noise_hash3(i + vec3<f32>(1., 1., 1.));
+    let z0 = mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+    let z1 = mix(mix(e, f1, u.x), mix(g, h, u.x), u.y);
+    return mix(z0, z1, u.z) * 2. - 1.;
+}
+
+// Simplex noise
+// https://github.com/ashima/webgl-noise (MIT license)
+fn noise_mod289_vec2(x: vec2<f32>) -> vec2<f32> {
+    return x - floor(x * (1. / 289.)) * 289.;
+}
+
+fn noise_mod289_vec3(x: vec3<f32>) -> vec3<f32> {
+    return x - floor(x * (1. / 289.)) * 289.;
+}
+
+fn noise_mod289_vec4(x: vec4<f32>) -> vec4<f32> {
+    return x - floor(x * (1. / 289.)) * 289.;
+}
+
+fn noise_permute_vec3(x: vec3<f32>) -> vec3<f32> {
+    return noise_mod289_vec3(((x * 34.) + 10.) * x);
+}
+
+fn noise_permute_vec4(x: vec4<f32>) -> vec4<f32> {
+    return noise_mod289_vec4(((x * 34.) + 10.) * x);
+}
+
+// Simplex noise in [-1:1]
+fn simplex_noise_vec2(v: vec2<f32>) -> f32 {
+    let C = vec4<f32>(0.211324865405187, 0.366025403784439, -0.577350269189626, 0.024390243902439);
+    // First corner
+    var i = floor(v + dot(v, C.yy));
+    let x0 = v - i + dot(i, C.xx);
+    // Other corners
+    let i1 = select(vec2<f32>(0., 1.), vec2<f32>(1., 0.), x0.x > x0.y);
+    var x12 = x0.xyxy + C.xxzz;
+    x12 = vec4<f32>(x12.xy - i1, x12.zw);
+    // Permutations
+    i = noise_mod289_vec2(i);
+    let p = noise_permute_vec3(noise_permute_vec3(i.y + vec3<f32>(0., i1.y, 1.)) + i.x + vec3<f32>(0., i1.x, 1.));
+    var m = max(0.5 - vec3<f32>(dot(x0, x0), dot(x12.xy, x12.xy), dot(x12.zw, x12.zw)), vec3<f32>(0.));
+    m = m * m;
+    m = m * m;
+    // Gradients
+    let x = 2. * fract(p * C.www) - 1.;
+    let h = abs(x) - 0.5;
+    let ox = floor(x + 0.5);
+    let a0 = x - ox;
+    m *= 1.79284291400159 - 0.85373472095314 * (a0 * a0 + h * h);
+    let g = vec3<f32>(a0.x * x0.x + h.x * x0.y, a0.yz * x12.xz + h.yz * x12.yw);
+    return 130. * dot(m, g);
+}
+
+// Simplex noise in [-1:1]
+fn simplex_noise_vec3(v: vec3<f32>) -> f32 {
+    let C = vec2<f32>(1. / 6., 1. / 3.);
+    let D = vec4<f32>(0., 0.5, 1., 2.);
+    // First corner
+    var i = floor(v + dot(v, C.yyy));
+    let x0 = v - i + dot(i, C.xxx);
+    // Other corners
+    let g = step(x0.yzx, x0.xyz);
+    let l = 1. - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+    let x1 = x0 - i1 + C.xxx;
+    let x2 = x0 - i2 + C.yyy;
+    let x3 = x0 - D.yyy;
+    // Permutations
+    i = noise_mod289_vec3(i);
+    let p = noise_permute_vec4(noise_permute_vec4(noise_permute_vec4(
+        i.z + vec4<f32>(0., i1.z, i2.z, 1.))
+        + i.y + vec4<f32>(0., i1.y, i2.y, 1.))
+        + i.x + vec4<f32>(0., i1.x, i2.x, 1.));
+    // Gradients: 7x7 points over a square, mapped onto an octahedron
+    let ns = (1. / 7.) * D.wyz - D.xzx;
+    let j = p - 49. * floor(p * ns.z * ns.z);
+    let x_ = floor(j * ns.z);
+    let y_ = floor(j - 7. * x_);
+    let x = x_ * ns.x + ns.yyyy;
+    let y = y_ * ns.x + ns.yyyy;
+    let h = 1. - abs(x) - abs(y);
+    let b0 = vec4<f32>(x.xy, y.xy);
+    let b1 = vec4<f32>(x.zw, y.zw);
+    let s0 = floor(b0) * 2. + 1.;
+    let s1 = floor(b1) * 2. + 1.;
+    let sh = -step(h, vec4<f32>(0.));
+    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
+    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
+    // Normalize gradients
+    let p0 = normalize(vec3<f32>(a0.xy, h.x));
+    let p1 = normalize(vec3<f32>(a0.zw, h.y));
+    let p2 = normalize(vec3<f32>(a1.xy, h.z));
+    let p3 = normalize(vec3<f32>(a1.zw, h.w));
+    // Mix final noise value
+    var m = max(0.5 - vec4<f32>(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3)), vec4<f32>(0.));
+    m = m * m;
+    return 105. * dot(m * m, vec4<f32>(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+}
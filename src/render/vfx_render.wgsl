@@ -1,9 +1,10 @@
 #import bevy_render::view::View
 #import bevy_hanabi::vfx_common::{
     DispatchIndirect, IndirectBuffer, SimParams, Spawner,
-    seed, tau, pcg_hash, to_float01, frand, frand2, frand3, frand4,
+    seed, tau, pcg_hash, to_float01, hash_float01, frand, frand2, frand3, frand4,
     rand_uniform_f, rand_uniform_vec2, rand_uniform_vec3, rand_uniform_vec4,
-    rand_normal_f, rand_normal_vec2, rand_normal_vec3, rand_normal_vec4, proj
+    rand_normal_f, rand_normal_vec2, rand_normal_vec3, rand_normal_vec4, proj,
+    value_noise_vec2, value_noise_vec3, simplex_noise_vec2, simplex_noise_vec3
 }
 
 struct Particle {
@@ -1,8 +1,9 @@
 #import bevy_hanabi::vfx_common::{
     IndirectBuffer, ParticleGroup, RenderEffectMetadata, RenderGroupIndirect, SimParams, Spawner,
-    seed, tau, pcg_hash, to_float01, frand, frand2, frand3, frand4,
+    seed, tau, pcg_hash, to_float01, hash_float01, frand, frand2, frand3, frand4,
     rand_uniform_f, rand_uniform_vec2, rand_uniform_vec3, rand_uniform_vec4,
-    rand_normal_f, rand_normal_vec2, rand_normal_vec3, rand_normal_vec4, proj
+    rand_normal_f, rand_normal_vec2, rand_normal_vec3, rand_normal_vec4, proj,
+    value_noise_vec2, value_noise_vec3, simplex_noise_vec2, simplex_noise_vec3
 }
 
 struct Particle {
@@ -43,6 +44,10 @@ fn main(@builtin(global_invocation_id) global_invocation_id: vec3<u32>) {
     let base_index = effect_particle_offset + particle_groups[{{GROUP_INDEX}}].indirect_index;
     let index = indirect_buffer.indices[3u * (base_index + thread_index) + pong];
 
+    // Particle counts at the beginning of the update pass
+    let particle_count = particle_groups[{{GROUP_INDEX}}].capacity;
+    let alive_count = render_group_indirect[{{GROUP_INDEX}}].max_update;
+
     var particle: Particle = particle_buffer.particles[index];
 
     // Update PRNG seed